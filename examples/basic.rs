@@ -1,12 +1,12 @@
 use bevy::prelude::*;
-use bevy_mod_atlas_loader::AtlasLoaderPlugin;
+use bevy_mod_atlas_loader::{sprite::AtlasAssetServerExt, AtlasLoaderPlugin};
 
 fn main() {
     App::new()
         .add_plugins((
             DefaultPlugins,
             // Add the plugin
-            AtlasLoaderPlugin,
+            AtlasLoaderPlugin::default(),
         ))
         .add_systems(Startup, startup)
         .run();
@@ -16,16 +16,6 @@ fn startup(mut commands: Commands, assets: Res<AssetServer>) {
     // Create a camera
     commands.spawn(Camera2d);
 
-    // Get the atlas texture and layout
-    let atlas_texture = assets.load::<Image>("basic.atlas.ron#texture");
-    let atlas_layout = assets.load::<TextureAtlasLayout>("basic.atlas.ron#layout");
-
-    // And spawn a sprite
-    commands.spawn(Sprite::from_atlas_image(
-        atlas_texture,
-        TextureAtlas {
-            index: 0,
-            layout: atlas_layout,
-        },
-    ));
+    // Load the atlas and spawn a sprite displaying one of its textures by name
+    commands.spawn(assets.load_atlas_sprite("basic.atlas.ron", "icon"));
 }
@@ -1,5 +1,5 @@
 use bevy::prelude::*;
-use bevy_mod_atlas_loader::AtlasLoaderPlugin;
+use bevy_mod_atlas_loader::{sprite::AtlasAssetServerExt, AtlasLoaderPlugin};
 
 fn main() {
     App::new()
@@ -10,7 +10,7 @@ fn main() {
                 ..default()
             }),
             // Add the plugin
-            AtlasLoaderPlugin,
+            AtlasLoaderPlugin::default(),
         ))
         .add_systems(Startup, startup)
         .run();
@@ -21,16 +21,6 @@ fn startup(mut commands: Commands, assets: Res<AssetServer>) {
     // Create a camera
     commands.spawn(Camera2d);
 
-    // Get the atlas texture and layout
-    let atlas_texture = assets.load::<Image>("processed.atlas.ron#texture");
-    let atlas_layout = assets.load::<TextureAtlasLayout>("processed.atlas.ron#layout");
-
-    // And spawn a sprite
-    commands.spawn(Sprite::from_atlas_image(
-        atlas_texture,
-        TextureAtlas {
-            index: 0,
-            layout: atlas_layout,
-        },
-    ));
+    // Load the atlas and spawn a sprite displaying one of its textures by name
+    commands.spawn(assets.load_atlas_sprite("processed.atlas.ron", "icon"));
 }
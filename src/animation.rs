@@ -0,0 +1,384 @@
+//! Frame animations defined alongside a texture atlas.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{AtlasLocation, AtlasPage};
+
+/// An [`Asset`] containing a named animation from a `.atlas.ron` file, as an ordered list of frames.
+///
+/// Animations are stored as labeled sub-assets of their [`TextureAtlasAsset`], e.g. `my.atlas.ron#anim/run`, and
+/// are also listed in [`TextureAtlasAsset::animations`].
+///
+/// [`TextureAtlasAsset`]: crate::TextureAtlasAsset
+/// [`TextureAtlasAsset::animations`]: crate::TextureAtlasAsset::animations
+#[derive(Asset, Reflect, Debug, Clone, Serialize, Deserialize)]
+pub struct AtlasAnimation {
+    /// The frames of the animation, in the order that they are displayed.
+    pub frames: Vec<AnimationFrame>,
+    /// Whether the animation restarts from its first frame after the last frame has been displayed.
+    pub looping: bool,
+    /// The pages of the atlas that the animation belongs to, which are set when it is added to the atlas.
+    #[serde(skip)]
+    pub pages: Vec<AtlasPage>,
+}
+
+/// A single frame of an [`AtlasAnimation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+pub struct AnimationFrame {
+    /// The location of the frame's texture within the atlas.
+    pub location: AtlasLocation,
+    /// How long the frame is displayed for.
+    pub duration: Duration,
+}
+
+impl AtlasAnimation {
+    /// Returns the total duration of a single play through of the animation.
+    pub fn duration(&self) -> Duration {
+        self.frames.iter().map(|frame| frame.duration).sum()
+    }
+}
+
+/// How an [`AtlasAnimationPlayer`] continues once it reaches the end of its animation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum PlaybackMode {
+    /// Stops on the last frame.
+    Once,
+    /// Restarts from the first frame.
+    Loop,
+    /// Plays the frames in reverse back to the first frame, then forwards again, indefinitely.
+    PingPong,
+}
+
+/// A [`Component`] that plays an [`AtlasAnimation`] on its entity's [`Sprite`].
+///
+/// Each frame, the player advances through the animation and sets the index of the sprite's
+/// [`Sprite::texture_atlas`] to that of the current frame. When a frame is on a different page of the atlas than the
+/// sprite's current layout, the sprite's layout and image are switched to those of the frame's page. Sprites without a
+/// texture atlas are left as is.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_mod_atlas_loader::{animation::{AtlasAnimationPlayer, PlaybackMode}, TextureAtlasAsset};
+/// fn spawn_hero(mut commands: Commands, atlases: Res<Assets<TextureAtlasAsset>>, hero: Res<HeroAtlas>) {
+///     let atlas = atlases.get(&hero.0).unwrap();
+///     commands.spawn((
+///         Sprite::from_atlas_image(atlas.texture.clone(), atlas.handle_from_name("run_0").unwrap()),
+///         AtlasAnimationPlayer::new(atlas.animation("run").unwrap().clone())
+///             .with_mode(PlaybackMode::PingPong)
+///             .with_speed(1.5),
+///     ));
+/// }
+/// # #[derive(Resource)]
+/// # struct HeroAtlas(Handle<TextureAtlasAsset>);
+/// ```
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct AtlasAnimationPlayer {
+    /// The animation being played.
+    pub animation: Handle<AtlasAnimation>,
+    /// Overrides how the animation continues after its last frame. If `None`, the animation loops if
+    /// [`AtlasAnimation::looping`] is set, and otherwise plays once.
+    pub mode: Option<PlaybackMode>,
+    /// The rate that the animation is played at, where `1.0` is its normal speed. Negative values are treated as zero.
+    pub speed: f32,
+    /// Whether the animation is paused on its current frame.
+    pub paused: bool,
+    frame: usize,
+    elapsed: Duration,
+    reversed: bool,
+    finished: bool,
+}
+
+impl AtlasAnimationPlayer {
+    /// Creates a player that starts the given animation from its first frame at normal speed.
+    pub fn new(animation: Handle<AtlasAnimation>) -> Self {
+        Self {
+            animation,
+            mode: None,
+            speed: 1.0,
+            paused: false,
+            frame: 0,
+            elapsed: Duration::ZERO,
+            reversed: false,
+            finished: false,
+        }
+    }
+
+    /// Sets the [`PlaybackMode`] of the player.
+    pub fn with_mode(mut self, mode: PlaybackMode) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// Sets the speed of the player.
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Switches to a different animation, restarting from its first frame. Does nothing if the animation is already
+    /// being played.
+    pub fn play(&mut self, animation: Handle<AtlasAnimation>) {
+        if self.animation != animation {
+            self.animation = animation;
+            self.restart();
+        }
+    }
+
+    /// Restarts the current animation from its first frame.
+    pub fn restart(&mut self) {
+        self.frame = 0;
+        self.elapsed = Duration::ZERO;
+        self.reversed = false;
+        self.finished = false;
+    }
+
+    /// Returns the index of the current frame within [`AtlasAnimation::frames`].
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    /// Returns `true` once a [`PlaybackMode::Once`] animation has reached the end of its last frame.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Advances the player by `delta`, before accounting for its speed.
+    fn advance(&mut self, animation: &AtlasAnimation, delta: Duration) {
+        let len = animation.frames.len();
+        if self.paused || self.finished || len == 0 || animation.duration().is_zero() {
+            return;
+        }
+
+        let mode = self.mode.unwrap_or(if animation.looping {
+            PlaybackMode::Loop
+        } else {
+            PlaybackMode::Once
+        });
+
+        self.frame = self.frame.min(len - 1);
+        self.elapsed += delta.mul_f32(self.speed.max(0.0));
+
+        while self.elapsed >= animation.frames[self.frame].duration {
+            self.elapsed -= animation.frames[self.frame].duration;
+
+            if !self.step(mode, len) {
+                self.elapsed = Duration::ZERO;
+                self.finished = true;
+                break;
+            }
+        }
+    }
+
+    /// Moves to the next frame, returning `false` if the animation has ended.
+    fn step(&mut self, mode: PlaybackMode, len: usize) -> bool {
+        match mode {
+            PlaybackMode::Once if self.frame + 1 < len => self.frame += 1,
+            PlaybackMode::Once => return false,
+            PlaybackMode::Loop => self.frame = (self.frame + 1) % len,
+            PlaybackMode::PingPong if len == 1 => {}
+            PlaybackMode::PingPong => {
+                if self.frame == 0 {
+                    self.reversed = false;
+                } else if self.frame + 1 == len {
+                    self.reversed = true;
+                }
+
+                if self.reversed {
+                    self.frame -= 1;
+                } else {
+                    self.frame += 1;
+                }
+            }
+        }
+
+        true
+    }
+}
+
+/// Advances every [`AtlasAnimationPlayer`] and updates the texture atlas index of its [`Sprite`], along with its layout
+/// and image if the current frame is on another page.
+pub(crate) fn play_animations(
+    time: Res<Time>,
+    animations: Res<Assets<AtlasAnimation>>,
+    mut players: Query<(&mut AtlasAnimationPlayer, &mut Sprite)>,
+) {
+    for (mut player, mut sprite) in &mut players {
+        let Some(animation) = animations.get(&player.animation) else {
+            continue;
+        };
+
+        player.advance(animation, time.delta());
+
+        let Some(frame) = animation.frames.get(player.frame) else {
+            continue;
+        };
+
+        let Some(atlas) = sprite.texture_atlas.as_ref() else {
+            continue;
+        };
+        let index = frame.location.index;
+        let page = animation
+            .pages
+            .get(frame.location.page)
+            .filter(|page| page.layout != atlas.layout);
+
+        // Only write to the sprite when the frame changes, to avoid triggering change detection every frame
+        if atlas.index == index && page.is_none() {
+            continue;
+        }
+        let sprite = sprite.as_mut();
+        if let Some(page) = page {
+            sprite.image = page.texture.clone();
+        }
+        if let Some(atlas) = &mut sprite.texture_atlas {
+            atlas.index = index;
+            if let Some(page) = page {
+                atlas.layout = page.layout.clone();
+            }
+        }
+    }
+}
+
+/// Returns the label of the [`AtlasAnimation`] sub-asset with the given name.
+pub(crate) fn animation_label(name: &str) -> String {
+    format!("anim/{name}")
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    fn animation(frames: usize, looping: bool) -> AtlasAnimation {
+        AtlasAnimation {
+            frames: (0..frames)
+                .map(|index| AnimationFrame {
+                    location: AtlasLocation { page: 0, index },
+                    duration: Duration::from_millis(100),
+                })
+                .collect(),
+            looping,
+            pages: Vec::new(),
+        }
+    }
+
+    /// Advances `player` by 100ms at a time, returning the frame after each step.
+    fn play(
+        player: &mut AtlasAnimationPlayer,
+        animation: &AtlasAnimation,
+        steps: usize,
+    ) -> Vec<usize> {
+        (0..steps)
+            .map(|_| {
+                player.advance(animation, Duration::from_millis(100));
+                player.frame()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn plays_once() {
+        let animation = animation(3, false);
+        let mut player = AtlasAnimationPlayer::new(Handle::default());
+        assert_eq!(play(&mut player, &animation, 2), [1, 2]);
+        assert!(!player.is_finished());
+        assert_eq!(play(&mut player, &animation, 2), [2, 2]);
+        assert!(player.is_finished());
+
+        player.restart();
+        assert_eq!(player.frame(), 0);
+        assert!(!player.is_finished());
+    }
+
+    #[test]
+    fn loops() {
+        let animation = animation(3, true);
+        let mut player = AtlasAnimationPlayer::new(Handle::default());
+        assert_eq!(play(&mut player, &animation, 5), [1, 2, 0, 1, 2]);
+        assert!(!player.is_finished());
+
+        let mut player = AtlasAnimationPlayer::new(Handle::default()).with_mode(PlaybackMode::Once);
+        assert_eq!(play(&mut player, &animation, 3), [1, 2, 2]);
+        assert!(player.is_finished());
+    }
+
+    #[test]
+    fn ping_pongs() {
+        let animation = animation(3, false);
+        let mut player =
+            AtlasAnimationPlayer::new(Handle::default()).with_mode(PlaybackMode::PingPong);
+        assert_eq!(play(&mut player, &animation, 6), [1, 2, 1, 0, 1, 2]);
+
+        let animation = self::animation(1, false);
+        let mut player =
+            AtlasAnimationPlayer::new(Handle::default()).with_mode(PlaybackMode::PingPong);
+        assert_eq!(play(&mut player, &animation, 2), [0, 0]);
+        assert!(!player.is_finished());
+    }
+
+    #[test]
+    fn scales_by_speed() {
+        let animation = animation(4, true);
+        let mut player = AtlasAnimationPlayer::new(Handle::default()).with_speed(2.0);
+        assert_eq!(play(&mut player, &animation, 2), [2, 0]);
+
+        let mut player = AtlasAnimationPlayer::new(Handle::default()).with_speed(0.5);
+        assert_eq!(play(&mut player, &animation, 4), [0, 1, 1, 2]);
+
+        let mut player = AtlasAnimationPlayer::new(Handle::default()).with_speed(-1.0);
+        assert_eq!(play(&mut player, &animation, 2), [0, 0]);
+    }
+
+    #[test]
+    fn pauses() {
+        let animation = animation(3, true);
+        let mut player = AtlasAnimationPlayer::new(Handle::default());
+        player.paused = true;
+        assert_eq!(play(&mut player, &animation, 2), [0, 0]);
+        player.paused = false;
+        assert_eq!(play(&mut player, &animation, 1), [1]);
+    }
+
+    #[test]
+    fn switches_the_page_of_the_sprite() {
+        let pages: Vec<AtlasPage> = (0..2)
+            .map(|page| AtlasPage {
+                layout: Handle::weak_from_u128(page),
+                texture: Handle::weak_from_u128(page),
+                lods: Vec::new(),
+                channels: Default::default(),
+            })
+            .collect();
+        let mut animation = animation(2, true);
+        animation.frames[1].location = AtlasLocation { page: 1, index: 0 };
+        animation.pages = pages.clone();
+
+        let mut world = World::new();
+        world.init_resource::<Time>();
+        let mut animations = Assets::<AtlasAnimation>::default();
+        let handle = animations.add(animation);
+        world.insert_resource(animations);
+        let mut player = AtlasAnimationPlayer::new(handle);
+        player.frame = 1;
+        let sprite = Sprite::from_atlas_image(
+            pages[0].texture.clone(),
+            TextureAtlas {
+                layout: pages[0].layout.clone(),
+                index: 1,
+            },
+        );
+        let entity = world.spawn((player, sprite)).id();
+
+        world.run_system_once(play_animations).unwrap();
+        let sprite = world.get::<Sprite>(entity).unwrap();
+        let atlas = sprite.texture_atlas.as_ref().unwrap();
+        assert_eq!(sprite.image, pages[1].texture);
+        assert_eq!(atlas.layout, pages[1].layout);
+        assert_eq!(atlas.index, 0);
+    }
+}
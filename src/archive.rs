@@ -0,0 +1,120 @@
+//! Reading of atlas textures and configs from `.zip` archives.
+
+use std::{
+    io::{Cursor, Read},
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    asset::{io::VecReader, AssetPath, AsyncReadExt, LoadContext, LoadedAsset},
+    prelude::*,
+};
+use zip::{result::ZipError, ZipArchive};
+
+use super::{
+    glob::{self, SourceReaders},
+    loader::BuildLoaderError,
+};
+
+/// A `.zip` archive that atlas textures are read from, instead of the asset source.
+#[derive(Clone)]
+pub(crate) struct Archive {
+    /// The asset path of the archive itself.
+    path: AssetPath<'static>,
+    zip: ZipArchive<Cursor<Vec<u8>>>,
+}
+
+impl Archive {
+    /// Opens an archive from its contents.
+    pub fn new(path: AssetPath<'static>, bytes: Vec<u8>) -> Result<Self, ZipError> {
+        let zip = ZipArchive::new(Cursor::new(bytes))?;
+        trace!("Opened archive {path} containing {} entries", zip.len());
+        Ok(Self { path, zip })
+    }
+
+    /// Reads and opens the archive at `path`. The archive is read from the unprocessed source, as it has no asset
+    /// loader of its own.
+    pub async fn open(
+        readers: &SourceReaders,
+        path: AssetPath<'static>,
+    ) -> Result<Self, BuildLoaderError> {
+        trace!("Reading atlas archive: {path}");
+        let reader = readers.reader(path.source().clone())?;
+        let mut bytes = Vec::new();
+        reader
+            .read(path.path())
+            .await?
+            .read_to_end(&mut bytes)
+            .await?;
+        Ok(Self::new(path, bytes)?)
+    }
+
+    /// Returns `true` if `bytes` start with the signature of a `.zip` archive.
+    pub fn is_archive(bytes: &[u8]) -> bool {
+        bytes.starts_with(b"PK\x03\x04")
+    }
+
+    /// Returns the paths of all files in the archive, sorted for deterministic ordering.
+    pub fn files(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = self
+            .zip
+            .file_names()
+            .filter(|name| !name.ends_with('/'))
+            .map(PathBuf::from)
+            .collect();
+        files.sort();
+        files
+    }
+
+    /// Returns the files in the archive matching the glob `pattern`.
+    pub fn expand(&self, pattern: &Path) -> Vec<PathBuf> {
+        let mut files = self.files();
+        files.retain(|path| glob::matches_pattern(pattern, path));
+        files
+    }
+
+    /// Returns the files in the archive below `directory`.
+    pub fn walk(&self, directory: &Path) -> Vec<PathBuf> {
+        let mut files = self.files();
+        files.retain(|path| path.starts_with(directory));
+        files
+    }
+
+    /// Returns the contents of the file at `path` within the archive.
+    pub fn read_file(&mut self, path: &Path) -> Result<Vec<u8>, ZipError> {
+        let name = path
+            .iter()
+            .map(|component| component.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let mut file = self.zip.by_name(&name)?;
+        let mut bytes = Vec::with_capacity(file.size() as usize);
+        file.read_to_end(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Loads the image at `path` within the archive. The image loader is chosen by the file's extension.
+    pub async fn load_image(
+        &mut self,
+        load_context: &mut LoadContext<'_>,
+        path: &Path,
+    ) -> Result<LoadedAsset<Image>, BuildLoaderError> {
+        let bytes = self.read_file(path)?;
+        // Images are given a path within the archive's path so that they can't be confused with the asset source's. They
+        // are loaded in a separate context, so that these paths aren't recorded as dependencies of the atlas, since the
+        // processor can never find them. The archive itself is the dependency instead.
+        let image_path = AssetPath::from(self.path.path().join(path));
+        let image = load_context
+            .begin_labeled_asset()
+            .loader()
+            .immediate()
+            // Load untyped to force asset loader detection based on path
+            .with_unknown_type()
+            .with_reader(&mut VecReader::new(bytes))
+            .load(&image_path)
+            .await?;
+        image
+            .downcast::<Image>()
+            .map_err(|_| BuildLoaderError::ImageTypeMismatch(image_path))
+    }
+}
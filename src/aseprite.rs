@@ -0,0 +1,382 @@
+//! Loading of sprite sheets exported by [Aseprite](https://www.aseprite.org/).
+
+use std::time::Duration;
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext, LoadDirectError, ParseAssetPathError},
+    prelude::*,
+};
+use serde::{Deserialize, Deserializer};
+use thiserror::Error;
+
+use super::{
+    animation::{AnimationFrame, AtlasAnimation},
+    json::JsonFrames,
+    AtlasLocation, TextureAtlasAsset, TextureAtlasPaths,
+};
+#[cfg(feature = "aseprite")]
+use super::{
+    loader::ATLAS_FORMAT,
+    packer::{self, PackSettings},
+    TextureInfo,
+};
+
+/// Errors encountered by [`AsepriteLoader`].
+#[derive(Debug, Error)]
+pub enum AsepriteLoaderError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    ParseAssetPath(#[from] ParseAssetPathError),
+    #[error(transparent)]
+    LoadDirect(#[from] LoadDirectError),
+    #[cfg(feature = "aseprite")]
+    #[error(transparent)]
+    Parse(#[from] asefile::AsepriteParseError),
+    #[error("The {frames} frames of the {size} sprite do not fit within the maximum atlas size ({max_size})")]
+    TooLarge {
+        frames: usize,
+        size: UVec2,
+        max_size: UVec2,
+    },
+    #[error(
+        "The tag \"{tag}\" refers to frames {from}..={to}, but the sheet only has {frames} frames"
+    )]
+    InvalidTag {
+        tag: String,
+        from: usize,
+        to: usize,
+        frames: usize,
+    },
+}
+
+/// An [`AssetLoader`] that loads a [`TextureAtlasAsset`] from a sprite sheet exported by Aseprite as JSON, in either
+/// the "Hash" or "Array" format. The sheet's image is loaded from the path in its `meta.image` field, relative to the
+/// JSON file.
+///
+/// Each frame is named after its `filename`, and trimmed frames keep their original size in [`TextureInfo::trim`].
+/// Every frame tag is added as an [`AtlasAnimation`] of the same name, following the tag's direction and repeat
+/// count.
+///
+/// Aseprite writes sheets with a plain `.json` extension, so they must be renamed to `.aseprite.json` to be picked up
+/// by this loader.
+///
+/// [`TextureInfo::trim`]: crate::TextureInfo::trim
+pub struct AsepriteLoader;
+
+impl AssetLoader for AsepriteLoader {
+    type Asset = TextureAtlasAsset;
+    type Settings = ();
+    type Error = AsepriteLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        &(): &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        debug!(
+            "Loading Aseprite sprite sheet from {:?}",
+            load_context.path()
+        );
+        let sheet = {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            serde_json::from_slice::<AsepriteSheet>(&bytes)?
+        };
+
+        let image_path = load_context.asset_path().resolve_embed(&sheet.meta.image)?;
+        trace!("Loading Aseprite sheet image from: {image_path}");
+        let texture = load_context
+            .loader()
+            .immediate()
+            .load::<Image>(image_path)
+            .await?;
+
+        let mut paths = TextureAtlasPaths::default();
+        let mut textures = Vec::with_capacity(sheet.frames.0.len());
+        for (name, frame) in sheet.frames.0.iter() {
+            paths.add(None, Some(name.clone()), frame.info());
+            textures.push(frame.rect());
+        }
+
+        let durations: Vec<u64> = sheet
+            .frames
+            .0
+            .iter()
+            .map(|(_, frame)| frame.duration.unwrap_or(100))
+            .collect();
+        let animations = resolve_tags(sheet.meta.frame_tags, &durations)
+            .map_err(|tag| tag.invalid(durations.len()))?;
+
+        let layout = TextureAtlasLayout {
+            size: texture.get().size(),
+            textures,
+        };
+        debug!(
+            "Loaded Aseprite sprite sheet containing {} frames and {} tags",
+            paths.path_indices.len(),
+            animations.len(),
+        );
+        let mut atlas = TextureAtlasAsset::from_pages(load_context, [(layout, texture)], paths);
+        atlas.add_animations(load_context, animations);
+        Ok(atlas)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["aseprite.json"]
+    }
+}
+
+/// An [`AssetLoader`] that loads a [`TextureAtlasAsset`] directly from an `.aseprite` or `.ase` file.
+///
+/// The visible layers of each frame are flattened and the frames are packed into a single atlas page. Frames are
+/// named the same way as Aseprite's JSON export, e.g. `hero 0.aseprite`, so switching between this loader and
+/// [`AsepriteLoader`] does not change any names. Every frame tag is added as an [`AtlasAnimation`] of the same name.
+///
+/// Requires the `aseprite` feature.
+#[cfg(feature = "aseprite")]
+pub struct AsepriteFileLoader;
+
+#[cfg(feature = "aseprite")]
+impl AssetLoader for AsepriteFileLoader {
+    type Asset = TextureAtlasAsset;
+    type Settings = ();
+    type Error = AsepriteLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        &(): &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        use bevy::render::{
+            render_asset::RenderAssetUsages,
+            render_resource::{Extent3d, TextureDimension},
+        };
+
+        debug!("Loading Aseprite file from {:?}", load_context.path());
+        let file = {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            asefile::AsepriteFile::read(bytes.as_slice())?
+        };
+
+        let size = UVec2::new(file.width() as u32, file.height() as u32);
+        let frame_count = file.num_frames() as usize;
+        let settings = PackSettings::default();
+        let packing = match packer::pack(&vec![size; frame_count], &settings) {
+            Ok(mut packings) if packings.len() == 1 => packings.remove(0),
+            _ => {
+                return Err(AsepriteLoaderError::TooLarge {
+                    frames: frame_count,
+                    size,
+                    max_size: settings.max_size,
+                })
+            }
+        };
+
+        trace!("Flattening {frame_count} Aseprite frames");
+        let frames: Vec<Image> = (0..file.num_frames())
+            .map(|frame| {
+                Image::new(
+                    Extent3d {
+                        width: size.x,
+                        height: size.y,
+                        depth_or_array_layers: 1,
+                    },
+                    TextureDimension::D2,
+                    file.frame(frame).image().into_raw(),
+                    ATLAS_FORMAT,
+                    RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD,
+                )
+            })
+            .collect();
+        let textures: Vec<&Image> = frames.iter().collect();
+        let texture = packer::composite(&packing, ATLAS_FORMAT, &textures, settings.extrude);
+
+        // Match the `{title} {frame}.{extension}` filenames of Aseprite's JSON export
+        let path = load_context.path();
+        let title = path.file_stem().unwrap_or_default().to_string_lossy();
+        let extension = path.extension().unwrap_or_default().to_string_lossy();
+        let mut paths = TextureAtlasPaths::default();
+        for frame in 0..frame_count {
+            paths.add(
+                None,
+                Some(format!("{title} {frame}.{extension}")),
+                TextureInfo::default(),
+            );
+        }
+
+        let tags = (0..file.num_tags())
+            .map(|tag| AsepriteTag::from(file.tag(tag)))
+            .collect();
+        let durations: Vec<u64> = (0..file.num_frames())
+            .map(|frame| file.frame(frame).duration().into())
+            .collect();
+        let animations =
+            resolve_tags(tags, &durations).map_err(|tag| tag.invalid(durations.len()))?;
+
+        let layout = TextureAtlasLayout {
+            size: packing.size,
+            textures: packing.rects,
+        };
+        debug!(
+            "Loaded Aseprite file containing {frame_count} frames and {} tags",
+            animations.len(),
+        );
+        let mut atlas =
+            TextureAtlasAsset::from_pages(load_context, [(layout, texture.into())], paths);
+        atlas.add_animations(load_context, animations);
+        Ok(atlas)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["aseprite", "ase"]
+    }
+}
+
+/// Converts frame tags into named animations over frames with the given durations in milliseconds. If a tag's frames
+/// are out of range, that tag is returned instead.
+fn resolve_tags(
+    tags: Vec<AsepriteTag>,
+    durations: &[u64],
+) -> Result<Vec<(String, AtlasAnimation)>, AsepriteTag> {
+    let mut animations = Vec::with_capacity(tags.len());
+    for tag in tags {
+        let Some(animation) = tag.resolve(durations) else {
+            return Err(tag);
+        };
+        animations.push((tag.name, animation));
+    }
+    Ok(animations)
+}
+
+/// The root of an Aseprite JSON sprite sheet.
+#[derive(Deserialize)]
+struct AsepriteSheet {
+    frames: JsonFrames,
+    meta: AsepriteMeta,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AsepriteMeta {
+    /// The path of the sheet image, relative to the JSON file.
+    image: String,
+    #[serde(default)]
+    frame_tags: Vec<AsepriteTag>,
+}
+
+#[derive(Deserialize)]
+struct AsepriteTag {
+    name: String,
+    from: usize,
+    to: usize,
+    #[serde(default)]
+    direction: AsepriteDirection,
+    /// How many times the tag is played. Tags without a repeat count loop forever.
+    #[serde(default, deserialize_with = "deserialize_repeat")]
+    repeat: Option<usize>,
+}
+
+/// Deserializes a tag's repeat count, which Aseprite writes as a string. A count of zero repeats forever.
+fn deserialize_repeat<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<usize>, D::Error> {
+    let repeat = Option::<String>::deserialize(deserializer)?;
+    Ok(repeat
+        .and_then(|repeat| repeat.parse::<usize>().ok())
+        .filter(|&repeat| repeat > 0))
+}
+
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+enum AsepriteDirection {
+    #[default]
+    Forward,
+    Reverse,
+    Pingpong,
+    PingpongReverse,
+}
+
+#[cfg(feature = "aseprite")]
+impl From<&asefile::Tag> for AsepriteTag {
+    fn from(tag: &asefile::Tag) -> Self {
+        Self {
+            name: tag.name().to_owned(),
+            from: tag.from_frame() as usize,
+            to: tag.to_frame() as usize,
+            direction: match tag.animation_direction() {
+                asefile::AnimationDirection::Forward => AsepriteDirection::Forward,
+                asefile::AnimationDirection::Reverse => AsepriteDirection::Reverse,
+                asefile::AnimationDirection::PingPong => AsepriteDirection::Pingpong,
+            },
+            repeat: tag.repeat().map(|repeat| repeat.get() as usize),
+        }
+    }
+}
+
+impl AsepriteTag {
+    /// Returns the error for a tag whose frames are out of range of a sheet with the given number of frames.
+    fn invalid(self, frames: usize) -> AsepriteLoaderError {
+        AsepriteLoaderError::InvalidTag {
+            tag: self.name,
+            from: self.from,
+            to: self.to,
+            frames,
+        }
+    }
+
+    /// Converts the tag into an animation over frames with the given durations in milliseconds, or returns `None` if
+    /// its frames are out of range.
+    fn resolve(&self, durations: &[u64]) -> Option<AtlasAnimation> {
+        if self.from > self.to || self.to >= durations.len() {
+            return None;
+        }
+
+        // Ping-pong directions play every frame except the first and last in both directions
+        let forward = self.from..=self.to;
+        let inner = self.from + 1..self.to;
+        let indices: Vec<usize> = match self.direction {
+            AsepriteDirection::Forward => forward.collect(),
+            AsepriteDirection::Reverse => forward.rev().collect(),
+            AsepriteDirection::Pingpong => forward.chain(inner.rev()).collect(),
+            AsepriteDirection::PingpongReverse => forward.rev().chain(inner).collect(),
+        };
+
+        let repeat = self.repeat;
+        let mut indices: Vec<usize> = indices
+            .iter()
+            .copied()
+            .cycle()
+            .take(indices.len() * repeat.unwrap_or(1))
+            .collect();
+        // Finite ping-pongs end back on the frame they started from
+        if repeat.is_some()
+            && matches!(
+                self.direction,
+                AsepriteDirection::Pingpong | AsepriteDirection::PingpongReverse
+            )
+            && indices.len() > 1
+        {
+            indices.push(indices[0]);
+        }
+
+        let frames = indices
+            .into_iter()
+            .map(|index| AnimationFrame {
+                location: AtlasLocation { page: 0, index },
+                duration: Duration::from_millis(durations[index]),
+            })
+            .collect();
+
+        Some(AtlasAnimation {
+            frames,
+            looping: repeat.is_none(),
+            pages: Vec::new(),
+        })
+    }
+}
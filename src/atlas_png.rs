@@ -0,0 +1,123 @@
+//! Loading of self-describing `.atlas.png` files, which store the layout of a texture atlas in a text chunk of the
+//! atlas image.
+
+use std::io::Cursor;
+
+use bevy::{
+    asset::{
+        io::{Reader, VecReader},
+        AssetLoader, LoadContext,
+    },
+    prelude::*,
+    render::texture::ImageFormat,
+};
+use png::text_metadata::{EncodableTextChunk, ITXtChunk};
+use thiserror::Error;
+
+use super::{
+    loader::{LoaderError, LoaderSettings, SaverError, TextureAtlasLoader},
+    TextureAtlasAsset,
+};
+
+/// The keyword of the PNG text chunk that contains the atlas layout.
+pub const LAYOUT_KEYWORD: &str = "bevy_mod_atlas_loader";
+
+/// The length of the PNG signature and `IHDR` chunk, which must come first in the file.
+const HEADER_LENGTH: usize = 33;
+
+/// Errors encountered by [`AtlasPngLoader`].
+#[derive(Debug, Error)]
+pub enum AtlasPngLoaderError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Png(#[from] png::DecodingError),
+    #[error("The PNG image has no `{LAYOUT_KEYWORD}` text chunk")]
+    MissingLayout,
+    #[error(transparent)]
+    Ron(#[from] ron::error::SpannedError),
+    #[error(transparent)]
+    Load(#[from] LoaderError),
+}
+
+/// An [`AssetLoader`] that loads a [`TextureAtlasAsset`] from a single `.atlas.png` image, with no `.meta` file. The
+/// [`LoaderSettings`] of the atlas are read as RON from the image's `tEXt`, `zTXt`, or `iTXt` chunk with the keyword
+/// [`LAYOUT_KEYWORD`], which must appear before the image data.
+///
+/// These images are written by the [`TextureAtlasSaver`] when [`PngSettings::embed_layout`] is set, and remain
+/// ordinary PNG images that can be viewed or loaded as an [`Image`] by other tools.
+///
+/// [`TextureAtlasSaver`]: crate::loader::TextureAtlasSaver
+/// [`PngSettings::embed_layout`]: crate::loader::PngSettings::embed_layout
+pub struct AtlasPngLoader;
+
+impl AssetLoader for AtlasPngLoader {
+    type Asset = TextureAtlasAsset;
+    type Settings = ();
+    type Error = AtlasPngLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        &(): &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        debug!("Loading atlas PNG from {:?}", load_context.path());
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let text = read_layout(&bytes)?.ok_or(AtlasPngLoaderError::MissingLayout)?;
+        let settings = ron::from_str::<LoaderSettings>(&text)?.with_format(ImageFormat::Png);
+        let atlas = TextureAtlasLoader
+            .load(&mut VecReader::new(bytes), &settings, load_context)
+            .await?;
+        Ok(atlas)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["atlas.png"]
+    }
+}
+
+/// Reads the serialized [`LoaderSettings`] stored in the text chunks of a PNG image, if it has any.
+fn read_layout(bytes: &[u8]) -> Result<Option<String>, png::DecodingError> {
+    let reader = png::Decoder::new(Cursor::new(bytes)).read_info()?;
+    let info = reader.info();
+
+    // Any type of text chunk is accepted, as other tools may have rewritten the image
+    let is_layout = |keyword: &str| keyword == LAYOUT_KEYWORD;
+    if let Some(chunk) = info.utf8_text.iter().find(|c| is_layout(&c.keyword)) {
+        return chunk.get_text().map(Some);
+    }
+    if let Some(chunk) = info
+        .compressed_latin1_text
+        .iter()
+        .find(|c| is_layout(&c.keyword))
+    {
+        return chunk.get_text().map(Some);
+    }
+    Ok(info
+        .uncompressed_latin1_text
+        .iter()
+        .find(|c| is_layout(&c.keyword))
+        .map(|chunk| chunk.text.clone()))
+}
+
+/// Returns a compressed `iTXt` chunk containing `settings`.
+pub(crate) fn layout_chunk(settings: &LoaderSettings) -> Result<ITXtChunk, SaverError> {
+    let text = ron::to_string(settings)?;
+    let mut chunk = ITXtChunk::new(LAYOUT_KEYWORD, text);
+    chunk.compressed = true;
+    Ok(chunk)
+}
+
+/// Inserts a compressed `iTXt` chunk containing `settings` into an encoded PNG image, directly after its header.
+pub(crate) fn embed_layout(image: &[u8], settings: &LoaderSettings) -> Result<Vec<u8>, SaverError> {
+    let chunk = layout_chunk(settings)?;
+    let (header, rest) = image.split_at(HEADER_LENGTH.min(image.len()));
+    let mut buffer = Vec::with_capacity(image.len() + 1024);
+    buffer.extend_from_slice(header);
+    chunk.encode(&mut buffer)?;
+    buffer.extend_from_slice(rest);
+    Ok(buffer)
+}
@@ -0,0 +1,210 @@
+//! Loading and saving of `.atlasbin` files, which bundle the encoded atlas image with its layout so that a processed
+//! atlas is a single file that doesn't depend on the settings in its `.meta` file.
+//!
+//! An `.atlasbin` file starts with the 8 byte magic `ATLASBIN`, followed by a little-endian `u16` format version and
+//! a little-endian `u16` of flags. The rest of the file is the payload, which is compressed with zstd if the lowest
+//! flag bit is set. The payload contains a little-endian `u32` byte length, that many bytes of the atlas's
+//! [`LoaderSettings`] as UTF-8 RON, and finally the encoded atlas image in [`LoaderSettings::format`].
+
+use bevy::asset::{
+    io::{Reader, VecReader, Writer},
+    saver::{AssetSaver, SavedAsset},
+    AssetLoader, AsyncWriteExt, LoadContext,
+};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{
+    loader::{
+        LoaderError, LoaderSettings, SaverError, SaverSettings, TextureAtlasLoader,
+        TextureAtlasSaver,
+    },
+    TextureAtlasAsset,
+};
+
+/// The magic bytes at the start of every `.atlasbin` file.
+pub const MAGIC: [u8; 8] = *b"ATLASBIN";
+/// The format version written by [`AtlasBinSaver`].
+pub const VERSION: u16 = 1;
+
+/// The flag set when the payload is compressed with zstd.
+const FLAG_ZSTD: u16 = 1;
+/// The length of the magic, version and flags.
+const HEADER_LENGTH: usize = 12;
+
+/// Errors encountered by [`AtlasBinLoader`].
+#[derive(Debug, Error)]
+pub enum AtlasBinLoaderError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Parse(#[from] AtlasBinParseError),
+    #[error(transparent)]
+    Load(#[from] LoaderError),
+}
+
+/// An error in the contents of an `.atlasbin` file.
+#[derive(Debug, Error)]
+pub enum AtlasBinParseError {
+    #[error("The file is not an `.atlasbin` atlas")]
+    InvalidMagic,
+    #[error("Unsupported `.atlasbin` version {0}, expected at most version {VERSION}")]
+    UnsupportedVersion(u16),
+    #[error("The `.atlasbin` atlas is compressed with zstd, which requires the `zstd` feature")]
+    UnsupportedCompression,
+    #[error("Unable to decompress the `.atlasbin` atlas: {0}")]
+    Decompress(std::io::Error),
+    #[error("The `.atlasbin` atlas ends before its layout")]
+    Truncated,
+    #[error("The layout of the `.atlasbin` atlas is not valid UTF-8")]
+    InvalidLayout,
+    #[error(transparent)]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+/// An [`AssetLoader`] that loads a [`TextureAtlasAsset`] from an `.atlasbin` file, as written by [`AtlasBinSaver`].
+/// The layout of the atlas is read from the file itself, so the loader has no settings.
+pub struct AtlasBinLoader;
+
+impl AssetLoader for AtlasBinLoader {
+    type Asset = TextureAtlasAsset;
+    type Settings = ();
+    type Error = AtlasBinLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        &(): &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        debug!("Loading atlasbin from {:?}", load_context.path());
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let (settings, image) = read(bytes)?;
+        let atlas = TextureAtlasLoader
+            .load(&mut VecReader::new(image), &settings, load_context)
+            .await?;
+        Ok(atlas)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["atlasbin"]
+    }
+}
+
+/// Splits an `.atlasbin` file into the atlas's [`LoaderSettings`] and its encoded image.
+fn read(bytes: Vec<u8>) -> Result<(LoaderSettings, Vec<u8>), AtlasBinParseError> {
+    let Some((header, payload)) = bytes.split_at_checked(HEADER_LENGTH) else {
+        return Err(AtlasBinParseError::InvalidMagic);
+    };
+    if header[..8] != MAGIC {
+        return Err(AtlasBinParseError::InvalidMagic);
+    }
+    let version = u16::from_le_bytes([header[8], header[9]]);
+    if version > VERSION {
+        return Err(AtlasBinParseError::UnsupportedVersion(version));
+    }
+    let flags = u16::from_le_bytes([header[10], header[11]]);
+
+    let mut payload = if flags & FLAG_ZSTD != 0 {
+        #[cfg(feature = "zstd")]
+        {
+            zstd::decode_all(payload).map_err(AtlasBinParseError::Decompress)?
+        }
+        #[cfg(not(feature = "zstd"))]
+        return Err(AtlasBinParseError::UnsupportedCompression);
+    } else {
+        payload.to_vec()
+    };
+
+    let Some((length, rest)) = payload.split_first_chunk::<4>() else {
+        return Err(AtlasBinParseError::Truncated);
+    };
+    let length = u32::from_le_bytes(*length) as usize;
+    let Some(layout) = rest.get(..length) else {
+        return Err(AtlasBinParseError::Truncated);
+    };
+    let layout = std::str::from_utf8(layout).map_err(|_| AtlasBinParseError::InvalidLayout)?;
+    let settings: LoaderSettings = ron::from_str(layout)?;
+
+    trace!(
+        "Read atlasbin layout with {} textures",
+        settings.textures.len()
+    );
+    let image = payload.split_off(4 + length);
+    Ok((settings, image))
+}
+
+/// Errors encountered by [`AtlasBinSaver`].
+#[derive(Debug, Error)]
+pub enum AtlasBinSaverError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Saver(#[from] SaverError),
+    #[error(transparent)]
+    Ron(#[from] ron::Error),
+    #[error("Unable to compress the `.atlasbin` atlas, as this requires the `zstd` feature")]
+    UnsupportedCompression,
+}
+
+/// Settings for [`AtlasBinSaver`].
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AtlasBinSaverSettings {
+    /// How the atlas image is encoded within the file.
+    pub image: SaverSettings,
+    /// The zstd compression level of the file's payload, or `None` to leave it uncompressed. Images that are already
+    /// compressed, like PNG images, gain little from this. Requires the `zstd` feature. Defaults to `None`.
+    pub zstd: Option<i32>,
+}
+
+/// An [`AssetSaver`] that saves a [`TextureAtlasAsset`] as a single `.atlasbin` file, which is loaded by
+/// [`AtlasBinLoader`].
+///
+/// To process atlas build configs into `.atlasbin` files, set their processor to the
+/// `LoadTransformAndSave<TextureAtlasBuildLoader, IdentityAssetTransformer<TextureAtlasAsset>, AtlasBinSaver>`
+/// processor in their `.meta` file.
+pub struct AtlasBinSaver;
+
+impl AssetSaver for AtlasBinSaver {
+    type Asset = TextureAtlasAsset;
+    type Settings = AtlasBinSaverSettings;
+    type OutputLoader = AtlasBinLoader;
+    type Error = AtlasBinSaverError;
+
+    async fn save(
+        &self,
+        writer: &mut Writer,
+        asset: SavedAsset<'_, Self::Asset>,
+        settings: &Self::Settings,
+    ) -> Result<(), Self::Error> {
+        let (image, loader_settings) = TextureAtlasSaver::export(&asset, &settings.image, None)?;
+        let layout = ron::to_string(&loader_settings)?;
+
+        let mut payload = Vec::with_capacity(4 + layout.len() + image.len());
+        payload.extend((layout.len() as u32).to_le_bytes());
+        payload.extend(layout.as_bytes());
+        payload.extend(image);
+
+        let (flags, payload): (u16, _) = match settings.zstd {
+            #[cfg(feature = "zstd")]
+            Some(level) => {
+                trace!("Compressing atlasbin payload with zstd level {level}");
+                (FLAG_ZSTD, zstd::encode_all(payload.as_slice(), level)?)
+            }
+            #[cfg(not(feature = "zstd"))]
+            Some(_) => return Err(AtlasBinSaverError::UnsupportedCompression),
+            None => (0, payload),
+        };
+
+        writer.write_all(&MAGIC).await?;
+        writer.write_all(&VERSION.to_le_bytes()).await?;
+        writer.write_all(&flags.to_le_bytes()).await?;
+        writer.write_all(&payload).await?;
+        debug!("Exported atlasbin ({} byte payload)", payload.len());
+        Ok(())
+    }
+}
@@ -0,0 +1,423 @@
+//! Loading of [BMFont](https://www.angelcode.com/products/bmfont/) bitmap fonts as texture atlases.
+
+use std::collections::BTreeMap;
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext, LoadDirectError, ParseAssetPathError},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{TextureAtlasAsset, TextureAtlasPaths, TextureInfo};
+
+/// Errors encountered by [`BmFontLoader`].
+#[derive(Debug, Error)]
+pub enum BmFontLoaderError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Parse(#[from] BmFontParseError),
+    #[error(transparent)]
+    ParseAssetPath(#[from] ParseAssetPathError),
+    #[error(transparent)]
+    LoadDirect(#[from] LoadDirectError),
+}
+
+/// An error in the contents of a BMFont `.fnt` file.
+#[derive(Debug, Error)]
+pub enum BmFontParseError {
+    #[error(transparent)]
+    Xml(#[from] roxmltree::Error),
+    #[error("Invalid BMFont file on line {line}: {message}")]
+    Syntax {
+        /// The line number of the error, starting from 1.
+        line: usize,
+        /// A description of the error.
+        message: &'static str,
+    },
+    #[error("Binary BMFont files are not supported")]
+    Binary,
+}
+
+/// The metadata of a glyph loaded by [`BmFontLoader`], stored in its [`TextureInfo::meta`].
+///
+/// ```no_run
+/// # use bevy_mod_atlas_loader::{bmfont::{glyph_name, BmFontGlyph}, TextureAtlasAsset};
+/// # fn advance(font: &TextureAtlasAsset) -> Option<i32> {
+/// let glyph: BmFontGlyph = font.meta_of(&glyph_name('A'))?.ok()?;
+/// Some(glyph.advance + glyph.kerning_with('V'))
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BmFontGlyph {
+    /// The offset from the cursor position to the top left corner of the glyph's image, with `y` pointing down.
+    pub offset: IVec2,
+    /// How far the cursor moves to the right after the glyph is drawn.
+    pub advance: i32,
+    /// The adjustments to [`BmFontGlyph::advance`] when the glyph is followed by another, by the other glyph's
+    /// codepoint.
+    pub kerning: BTreeMap<u32, i32>,
+}
+
+impl BmFontGlyph {
+    /// Returns the kerning adjustment when this glyph is followed by `next`, or `0` if there is none.
+    pub fn kerning_with(&self, next: char) -> i32 {
+        self.kerning.get(&u32::from(next)).copied().unwrap_or(0)
+    }
+}
+
+/// Returns the name of the glyph for `character` in an atlas loaded by [`BmFontLoader`], which is its codepoint in
+/// decimal.
+pub fn glyph_name(character: char) -> String {
+    u32::from(character).to_string()
+}
+
+/// An [`AssetLoader`] that loads a [`TextureAtlasAsset`] from a BMFont `.fnt` file in either the text or XML format.
+/// Each page of the font becomes a page of the atlas, with its image loaded from a path relative to the `.fnt` file.
+///
+/// Each glyph is named after its codepoint in decimal (see [`glyph_name`]), and its offset, advance, and kerning
+/// pairs are stored as a [`BmFontGlyph`] in its [`TextureInfo::meta`]. Glyphs without an image, such as spaces, are
+/// included with an empty rect so that their metadata is still available.
+///
+/// [`TextureInfo::meta`]: crate::TextureInfo::meta
+pub struct BmFontLoader;
+
+impl AssetLoader for BmFontLoader {
+    type Asset = TextureAtlasAsset;
+    type Settings = ();
+    type Error = BmFontLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        &(): &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        debug!("Loading BMFont from {:?}", load_context.path());
+        let font = {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            if bytes.starts_with(b"BMF") {
+                return Err(BmFontParseError::Binary.into());
+            }
+            parse(&String::from_utf8_lossy(&bytes))?
+        };
+
+        let mut kerning: BTreeMap<u32, BTreeMap<u32, i32>> = BTreeMap::new();
+        for (first, second, amount) in font.kernings {
+            kerning.entry(first).or_default().insert(second, amount);
+        }
+
+        let mut paths = TextureAtlasPaths::default();
+        let mut pages = Vec::with_capacity(font.pages.len());
+        for (page, image) in font.pages.iter().enumerate() {
+            if page > 0 {
+                paths.start_page();
+            }
+
+            let image_path = load_context.asset_path().resolve_embed(image)?;
+            trace!("Loading BMFont page {page} from: {image_path}");
+            let texture = load_context
+                .loader()
+                .immediate()
+                .load::<Image>(image_path)
+                .await?;
+
+            let mut textures = Vec::new();
+            for glyph in font.glyphs.iter().filter(|glyph| glyph.page == page) {
+                let meta = BmFontGlyph {
+                    offset: glyph.offset,
+                    advance: glyph.advance,
+                    kerning: u32::try_from(glyph.id)
+                        .ok()
+                        .and_then(|id| kerning.get(&id))
+                        .cloned()
+                        .unwrap_or_default(),
+                };
+                let info = TextureInfo {
+                    meta: ron::to_string(&meta)
+                        .ok()
+                        .and_then(|meta| ron::from_str(&meta).ok()),
+                    ..default()
+                };
+                paths.add(None, Some(glyph.id.to_string()), info);
+                textures.push(glyph.rect);
+            }
+
+            let layout = TextureAtlasLayout {
+                size: texture.get().size(),
+                textures,
+            };
+            pages.push((layout, texture));
+        }
+
+        debug!(
+            "Loaded BMFont containing {} glyphs on {} pages",
+            paths.path_indices.len(),
+            paths.page_count(),
+        );
+        Ok(TextureAtlasAsset::from_pages(load_context, pages, paths))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["fnt"]
+    }
+}
+
+/// The contents of a BMFont file.
+struct BmFont {
+    /// The path of each page image, relative to the `.fnt` file.
+    pages: Vec<String>,
+    glyphs: Vec<Glyph>,
+    /// The first codepoint, second codepoint, and amount of each kerning pair.
+    kernings: Vec<(u32, u32, i32)>,
+}
+
+/// A glyph of a BMFont.
+struct Glyph {
+    /// The glyph's codepoint, or `-1` for the glyph used for missing characters.
+    id: i64,
+    page: usize,
+    /// The area covered by the glyph within its page image.
+    rect: URect,
+    offset: IVec2,
+    advance: i32,
+}
+
+/// A single tag of a BMFont file, with its attributes.
+struct Tag<'a> {
+    name: &'a str,
+    line: usize,
+    attributes: Vec<(&'a str, &'a str)>,
+}
+
+impl Tag<'_> {
+    fn error(&self, message: &'static str) -> BmFontParseError {
+        BmFontParseError::Syntax {
+            line: self.line,
+            message,
+        }
+    }
+
+    /// Parses the value of an attribute, or returns `None` if it is missing.
+    fn get<T: std::str::FromStr>(&self, key: &str) -> Result<Option<T>, BmFontParseError> {
+        self.attributes
+            .iter()
+            .find(|&&(name, _)| name == key)
+            .map(|(_, value)| {
+                value
+                    .trim()
+                    .parse()
+                    .map_err(|_| self.error("invalid attribute value"))
+            })
+            .transpose()
+    }
+
+    fn require<T: std::str::FromStr>(&self, key: &str) -> Result<T, BmFontParseError> {
+        self.get(key)?
+            .ok_or_else(|| self.error("missing a required attribute"))
+    }
+}
+
+/// Splits a line of a text BMFont file into its tag.
+fn text_tag(line: usize, content: &str) -> Result<Option<Tag<'_>>, BmFontParseError> {
+    let content = content.trim();
+    let (name, mut rest) = content
+        .split_once(char::is_whitespace)
+        .unwrap_or((content, ""));
+    if name.is_empty() {
+        return Ok(None);
+    }
+
+    let mut attributes = Vec::new();
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            break;
+        }
+        let Some((key, value)) = rest.split_once('=') else {
+            return Err(BmFontParseError::Syntax {
+                line,
+                message: "expected `key=value`",
+            });
+        };
+        // Quoted values may contain spaces
+        let (value, remainder) = match value.strip_prefix('"') {
+            Some(quoted) => quoted.split_once('"').ok_or(BmFontParseError::Syntax {
+                line,
+                message: "unterminated string",
+            })?,
+            None => value.split_once(char::is_whitespace).unwrap_or((value, "")),
+        };
+        attributes.push((key.trim(), value));
+        rest = remainder;
+    }
+
+    Ok(Some(Tag {
+        name,
+        line,
+        attributes,
+    }))
+}
+
+/// Parses a BMFont file in either the text or XML format.
+fn parse(text: &str) -> Result<BmFont, BmFontParseError> {
+    let document;
+    let tags = if text.trim_start().starts_with('<') {
+        document = roxmltree::Document::parse(text)?;
+        document
+            .descendants()
+            .filter(|node| node.is_element())
+            .map(|node| Tag {
+                name: node.tag_name().name(),
+                line: document.text_pos_at(node.range().start).row as usize,
+                attributes: node
+                    .attributes()
+                    .map(|attribute| (attribute.name(), attribute.value()))
+                    .collect(),
+            })
+            .collect()
+    } else {
+        text.lines()
+            .enumerate()
+            .filter_map(|(line, content)| text_tag(line + 1, content).transpose())
+            .collect::<Result<Vec<_>, _>>()?
+    };
+
+    let mut pages = Vec::new();
+    let mut glyphs = Vec::new();
+    let mut kernings = Vec::new();
+    for tag in &tags {
+        match tag.name {
+            "page" => {
+                // Glyphs refer to pages by their position, so they must be listed in order
+                if tag.require::<usize>("id")? != pages.len() {
+                    return Err(tag.error("pages must be numbered consecutively from 0"));
+                }
+                pages.push(tag.require("file")?);
+            }
+            "char" => {
+                let position = UVec2::new(tag.require("x")?, tag.require("y")?);
+                let size = UVec2::new(tag.require("width")?, tag.require("height")?);
+                let page = tag.get("page")?.unwrap_or(0);
+                if page >= pages.len() {
+                    return Err(tag.error("glyph refers to an unknown page"));
+                }
+                glyphs.push(Glyph {
+                    id: tag.require("id")?,
+                    page,
+                    rect: URect::from_corners(position, position + size),
+                    offset: IVec2::new(
+                        tag.get("xoffset")?.unwrap_or(0),
+                        tag.get("yoffset")?.unwrap_or(0),
+                    ),
+                    advance: tag.get("xadvance")?.unwrap_or(0),
+                });
+            }
+            "kerning" => kernings.push((
+                tag.require("first")?,
+                tag.require("second")?,
+                tag.require("amount")?,
+            )),
+            // Font-wide information and tag counts are not needed
+            _ => {}
+        }
+    }
+
+    Ok(BmFont {
+        pages,
+        glyphs,
+        kernings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_the_text_format() {
+        let font = parse(
+            r#"info face="Pixel Sans" size=16 bold=0 padding=0,0,0,0
+common lineHeight=18 base=14 scaleW=64 scaleH=64 pages=2
+page id=0 file="font_0.png"
+page id=1 file="font 1.png"
+chars count=2
+char id=65   x=2  y=4  width=8 height=10 xoffset=1 yoffset=-2 xadvance=9 page=0
+char id=32   x=0  y=0  width=0 height=0  xadvance=4 page=1
+kernings count=1
+kerning first=65 second=86 amount=-1
+"#,
+        )
+        .unwrap();
+        assert_eq!(font.pages, ["font_0.png", "font 1.png"]);
+        assert_eq!(font.kernings, [(65, 86, -1)]);
+
+        let [a, space] = &font.glyphs[..] else {
+            panic!("expected two glyphs");
+        };
+        assert_eq!(a.id, 65);
+        assert_eq!(a.page, 0);
+        assert_eq!(a.rect, URect::new(2, 4, 10, 14));
+        assert_eq!(a.offset, IVec2::new(1, -2));
+        assert_eq!(a.advance, 9);
+        assert_eq!(space.page, 1);
+        assert_eq!(space.offset, IVec2::ZERO);
+        assert_eq!(space.advance, 4);
+    }
+
+    #[test]
+    fn parses_the_xml_format() {
+        let font = parse(
+            r#"<?xml version="1.0"?>
+<font>
+    <info face="Pixel Sans" size="16"/>
+    <pages>
+        <page id="0" file="font_0.png"/>
+    </pages>
+    <chars count="1">
+        <char id="-1" x="0" y="0" width="4" height="6" xoffset="0" yoffset="0" xadvance="5" page="0"/>
+    </chars>
+</font>"#,
+        )
+        .unwrap();
+        assert_eq!(font.pages, ["font_0.png"]);
+        assert_eq!(font.glyphs[0].id, -1);
+        assert_eq!(font.glyphs[0].rect, URect::new(0, 0, 4, 6));
+        assert!(font.kernings.is_empty());
+    }
+
+    #[test]
+    fn reports_the_line_of_errors() {
+        fn syntax_error(text: &str) -> (usize, &'static str) {
+            match parse(text) {
+                Err(BmFontParseError::Syntax { line, message }) => (line, message),
+                _ => panic!("expected a syntax error"),
+            }
+        }
+        let page = "page id=0 file=\"font.png\"\n";
+        assert_eq!(
+            syntax_error(&format!("{page}char id=65 x=0 y=0 width=1\n")),
+            (2, "missing a required attribute")
+        );
+        assert_eq!(
+            syntax_error(&format!("{page}char id=65 x=zero\n")),
+            (2, "invalid attribute value")
+        );
+        assert_eq!(
+            syntax_error(&format!(
+                "{page}char id=65 x=0 y=0 width=1 height=1 page=1\n"
+            )),
+            (2, "glyph refers to an unknown page")
+        );
+        assert_eq!(
+            syntax_error("page id=1 file=\"font.png\"\n"),
+            (1, "pages must be numbered consecutively from 0")
+        );
+        assert_eq!(
+            syntax_error("page id=0 file=\"font.png\n"),
+            (1, "unterminated string")
+        );
+    }
+}
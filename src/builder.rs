@@ -0,0 +1,230 @@
+//! Building atlases at runtime from images that were generated in memory, rather than loaded from asset files.
+
+use bevy::{
+    prelude::*,
+    render::render_resource::TextureFormat,
+    utils::{HashMap, HashSet},
+};
+use thiserror::Error;
+
+use super::{
+    loader::ATLAS_FORMAT,
+    packer::{self, PackSettings},
+    AtlasPage, TextureAtlasAsset, TextureAtlasPaths, TextureInfo, TextureTrim,
+};
+
+/// Errors encountered when building an atlas with [`AtlasBuilder`].
+#[derive(Debug, Error)]
+pub enum AtlasBuilderError {
+    #[error("The atlas has no textures")]
+    Empty,
+    #[error("The atlas has several textures named {0:?}")]
+    DuplicateName(String),
+    #[error(
+        "The texture {0:?} has the format {1:?}, which can't be converted to the atlas format"
+    )]
+    TextureFormat(String, TextureFormat),
+    #[error("The texture {name:?} does not fit in the maximum atlas size of {max_size}")]
+    NotEnoughSpace { name: String, max_size: UVec2 },
+}
+
+/// Packs named in-memory [`Image`]s into a [`TextureAtlasAsset`], for sprites that are generated procedurally and never
+/// exist on disk. Textures are packed with the same options as an `.atlas.ron` config, and can then be looked up by
+/// name like those of any other atlas.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_mod_atlas_loader::{builder::AtlasBuilder, TextureAtlasAsset};
+/// fn build_atlas(
+///     mut images: ResMut<Assets<Image>>,
+///     mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+///     mut atlases: ResMut<Assets<TextureAtlasAsset>>,
+/// ) {
+///     let mut builder = AtlasBuilder::new().with_padding(UVec2::ONE);
+///     builder.add_texture("red", Image::default());
+///     let atlas = builder.build(&mut images, &mut layouts, &mut atlases).unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AtlasBuilder {
+    textures: Vec<(String, Image, TextureInfo)>,
+    settings: PackSettings,
+    trim: bool,
+}
+
+impl AtlasBuilder {
+    /// Creates a builder with no textures and the default packing options of an `.atlas.ron` config.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the spacing added to the right and bottom of each texture.
+    pub fn with_padding(mut self, padding: UVec2) -> Self {
+        self.settings.padding = padding;
+        self
+    }
+
+    /// Sets the number of pixels that each texture's edges are extruded outward.
+    pub fn with_extrude(mut self, extrude: u32) -> Self {
+        self.settings.extrude = extrude;
+        self
+    }
+
+    /// Sets the largest size of each page of the atlas.
+    pub fn with_max_size(mut self, max_size: UVec2) -> Self {
+        self.settings.max_size = max_size;
+        self
+    }
+
+    /// Sets whether textures that don't fit within the max size are placed on additional pages.
+    pub fn with_multi_page(mut self, multi_page: bool) -> Self {
+        self.settings.multi_page = multi_page;
+        self
+    }
+
+    /// Sets whether the fully transparent edges of each texture are trimmed before packing.
+    pub fn with_trim(mut self, trim: bool) -> Self {
+        self.trim = trim;
+        self
+    }
+
+    /// Adds a texture with the given `name`.
+    pub fn add_texture(&mut self, name: impl Into<String>, image: Image) -> &mut Self {
+        self.add_texture_with_info(name, image, TextureInfo::default())
+    }
+
+    /// Adds a texture with the given `name`, along with its pivot, slices, and metadata. Its `rotated` and `trim` are
+    /// set when the atlas is built.
+    pub fn add_texture_with_info(
+        &mut self,
+        name: impl Into<String>,
+        image: Image,
+        info: TextureInfo,
+    ) -> &mut Self {
+        self.textures.push((name.into(), image, info));
+        self
+    }
+
+    /// Packs the textures, adding each page's layout and texture to `layouts` and `images`, and returns the atlas
+    /// without adding it to any [`Assets`]. This can be used to replace an existing atlas in place.
+    pub fn build_atlas(
+        self,
+        images: &mut Assets<Image>,
+        layouts: &mut Assets<TextureAtlasLayout>,
+    ) -> Result<TextureAtlasAsset, AtlasBuilderError> {
+        if self.textures.is_empty() {
+            return Err(AtlasBuilderError::Empty);
+        }
+        let mut names = HashSet::new();
+        let mut textures = Vec::with_capacity(self.textures.len());
+        let mut infos = Vec::with_capacity(self.textures.len());
+        for (name, image, mut info) in self.textures {
+            if !names.insert(name.clone()) {
+                return Err(AtlasBuilderError::DuplicateName(name));
+            }
+            let format = image.texture_descriptor.format;
+            let mut image = if format == ATLAS_FORMAT {
+                image
+            } else {
+                image
+                    .convert(ATLAS_FORMAT)
+                    .ok_or_else(|| AtlasBuilderError::TextureFormat(name.clone(), format))?
+            };
+            info.rotated = false;
+            info.trim = None;
+            if self.trim {
+                let source_size = image.size();
+                // Fully transparent textures are trimmed down to a single pixel
+                let bounds = packer::opaque_bounds(&image).unwrap_or_else(|| {
+                    URect::from_corners(UVec2::ZERO, source_size.min(UVec2::ONE))
+                });
+                if bounds.size() != source_size {
+                    image = packer::crop(&image, bounds);
+                    info.trim = Some(TextureTrim {
+                        offset: bounds.min,
+                        source_size,
+                    });
+                }
+            }
+            textures.push((name, image));
+            infos.push(info);
+        }
+
+        let sizes: Vec<UVec2> = textures.iter().map(|(_, image)| image.size()).collect();
+        let packing = packer::pack(&sizes, &self.settings).map_err(|index| {
+            AtlasBuilderError::NotEnoughSpace {
+                name: textures[index].0.clone(),
+                max_size: self.settings.max_size,
+            }
+        })?;
+
+        let mut paths = TextureAtlasPaths::default();
+        let mut pages = Vec::with_capacity(packing.len());
+        for (page, packing) in packing.into_iter().enumerate() {
+            if page > 0 {
+                paths.start_page();
+            }
+            for (&index, &rotated) in packing.indices.iter().zip(packing.rotated.iter()) {
+                let (name, image) = &mut textures[index];
+                if rotated {
+                    *image = packer::rotate(image);
+                }
+                let info = TextureInfo {
+                    rotated,
+                    ..infos[index].clone()
+                };
+                paths.add(None, Some(name.clone()), info);
+            }
+            let page_textures: Vec<&Image> = packing
+                .indices
+                .iter()
+                .map(|&index| &textures[index].1)
+                .collect();
+            let texture = packer::composite(
+                &packing,
+                ATLAS_FORMAT,
+                &page_textures,
+                self.settings.extrude,
+            );
+            let layout = TextureAtlasLayout {
+                size: packing.size,
+                textures: packing.rects,
+            };
+            pages.push(AtlasPage {
+                layout: layouts.add(layout),
+                texture: images.add(texture),
+                lods: Vec::new(),
+                channels: HashMap::new(),
+            });
+        }
+        debug!(
+            "Built runtime texture atlas containing {} sub-textures on {} pages",
+            paths.path_indices.len(),
+            paths.page_count(),
+        );
+
+        Ok(TextureAtlasAsset {
+            layout: pages[0].layout.clone(),
+            texture: pages[0].texture.clone(),
+            pages,
+            paths,
+            array: None,
+            animations: default(),
+            atlases: default(),
+            sprite_regions: default(),
+            sprite_images: default(),
+            premultiplied_alpha: false,
+            distance_field: None,
+        })
+    }
+
+    /// Packs the textures like [`AtlasBuilder::build_atlas`], and adds the atlas to `atlases`.
+    pub fn build(
+        self,
+        images: &mut Assets<Image>,
+        layouts: &mut Assets<TextureAtlasLayout>,
+        atlases: &mut Assets<TextureAtlasAsset>,
+    ) -> Result<Handle<TextureAtlasAsset>, AtlasBuilderError> {
+        Ok(atlases.add(self.build_atlas(images, layouts)?))
+    }
+}
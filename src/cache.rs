@@ -0,0 +1,105 @@
+//! Caching the results of atlas builds for configs that set `incremental`, so that rebuilding an atlas after some of
+//! its inputs changed reuses the work of the previous build.
+//!
+//! Each build records a hash of its packing settings and texture sizes, a hash of the pixels of each texture, and the
+//! resulting layout and page images. When the atlas is rebuilt with the same settings and sizes, the previous layout
+//! is reused instead of packing the textures again, and only the textures whose pixels changed are copied into the
+//! previous page images. The saver also records the last image it encoded for each cached build, which is reused
+//! when an atlas is saved again with the same images, layouts, and settings.
+
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::{Arc, Mutex},
+};
+
+use bevy::{prelude::*, utils::HashMap};
+
+use super::packer::Packing;
+
+/// Returns the hash of a value, which is only stable within a single run of the app.
+pub(crate) fn hash(value: impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Returns the hash of the size, format, and pixels of an image.
+pub(crate) fn hash_image(image: &Image) -> u64 {
+    hash((
+        image.texture_descriptor.size,
+        image.texture_descriptor.format,
+        &image.data,
+    ))
+}
+
+/// The result of the last build of an atlas.
+pub(crate) struct CachedBuild {
+    /// The hash of the packing settings and of the size of each unique texture, in the order they were packed.
+    pub layout: u64,
+    /// The hash of the pixels of each unique texture, in the order they were packed, before it was rotated.
+    pub textures: Vec<u64>,
+    pub packing: Vec<Packing>,
+    /// The image of each page, before pages were combined into an array texture.
+    pub pages: Vec<Image>,
+    /// The hash of each image of the built atlas, which identifies the atlas to the saver.
+    pub images: Vec<u64>,
+    /// The last encoding of the atlas's images by the saver.
+    pub encoded: Option<CachedEncoding>,
+}
+
+/// An encoded atlas image, along with the hash of the layouts and settings it was encoded with.
+#[derive(Clone)]
+pub(crate) struct CachedEncoding {
+    pub key: u64,
+    pub buffer: Vec<u8>,
+    /// The layout of the image after block compression aligned its textures, if it did.
+    pub aligned: Option<TextureAtlasLayout>,
+}
+
+/// The last build of each incremental atlas, by the path of its config and the label prefix of the atlas. Shared
+/// between the build loader and the saver.
+#[derive(Clone, Default)]
+pub(crate) struct BuildCache(Arc<Mutex<HashMap<String, CachedBuild>>>);
+
+impl BuildCache {
+    /// Removes and returns the last build of an atlas, so that its pages can be reused without copying them.
+    pub fn take(&self, key: &str) -> Option<CachedBuild> {
+        self.0.lock().unwrap().remove(key)
+    }
+
+    /// Records the latest build of an atlas.
+    pub fn insert(&self, key: String, build: CachedBuild) {
+        self.0.lock().unwrap().insert(key, build);
+    }
+
+    /// Returns `true` if no atlases have been built incrementally.
+    pub fn is_empty(&self) -> bool {
+        self.0.lock().unwrap().is_empty()
+    }
+
+    /// Returns `true` if there is a cached build with the given image hashes.
+    pub fn contains(&self, images: &[u64]) -> bool {
+        let cache = self.0.lock().unwrap();
+        cache.values().any(|build| build.images == images)
+    }
+
+    /// Returns the last encoding of the cached build with the given image hashes, if it was encoded with `key`.
+    pub fn encoding(&self, images: &[u64], key: u64) -> Option<CachedEncoding> {
+        let cache = self.0.lock().unwrap();
+        let build = cache.values().find(|build| build.images == images)?;
+        build
+            .encoded
+            .as_ref()
+            .filter(|encoded| encoded.key == key)
+            .cloned()
+    }
+
+    /// Records the encoding of the cached build with the given image hashes. Does nothing if there is no such build,
+    /// as the atlas wasn't built incrementally.
+    pub fn set_encoding(&self, images: &[u64], encoding: impl FnOnce() -> CachedEncoding) {
+        let mut cache = self.0.lock().unwrap();
+        if let Some(build) = cache.values_mut().find(|build| build.images == images) {
+            build.encoded = Some(encoding());
+        }
+    }
+}
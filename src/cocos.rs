@@ -0,0 +1,525 @@
+//! Loading of [Cocos2d](https://www.cocos.com/) `.plist` sprite sheets.
+
+use bevy::{
+    asset::{
+        io::Reader, AssetLoader, AsyncReadExt, LoadContext, LoadDirectError, ParseAssetPathError,
+    },
+    prelude::*,
+};
+use thiserror::Error;
+
+use super::{TextureAtlasAsset, TextureAtlasPaths, TextureInfo, TextureTrim};
+
+/// Errors encountered by [`CocosLoader`].
+#[derive(Debug, Error)]
+pub enum CocosLoaderError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Parse(#[from] CocosParseError),
+    #[error(transparent)]
+    ParseAssetPath(#[from] ParseAssetPathError),
+    #[error(transparent)]
+    LoadDirect(#[from] LoadDirectError),
+}
+
+/// An error in the contents of a Cocos2d `.plist` sprite sheet.
+#[derive(Debug, Error)]
+pub enum CocosParseError {
+    #[error(transparent)]
+    Xml(#[from] roxmltree::Error),
+    #[error("Expected an XML property list containing a `frames` dictionary")]
+    MissingFrames,
+    #[error("Unsupported sprite sheet format {0}")]
+    UnsupportedFormat(i64),
+    #[error("The frame \"{frame}\" is missing its `{key}` value")]
+    MissingValue { frame: String, key: &'static str },
+    #[error("The `{key}` value of the frame \"{frame}\" is invalid")]
+    InvalidValue { frame: String, key: &'static str },
+}
+
+/// An [`AssetLoader`] that loads a [`TextureAtlasAsset`] from a Cocos2d `.plist` sprite sheet, in any of the formats
+/// `0` to `3` written by TexturePacker and Zwoptex. The sheet image is loaded from the `textureFileName` in the
+/// sheet's metadata, relative to the `.plist` file, or from a `.png` file with the same name if it has none.
+///
+/// Each frame is named after its key in the `frames` dictionary, and keeps its rotation and trimming in its
+/// [`TextureInfo`]. Only XML property lists are supported.
+///
+/// Cocos2d rotates frames 90° clockwise and lists the size of rotated frames before they were rotated, which is
+/// accounted for so that the frame's rect covers its rotated area of the sheet.
+///
+/// [`TextureInfo`]: crate::TextureInfo
+pub struct CocosLoader;
+
+impl AssetLoader for CocosLoader {
+    type Asset = TextureAtlasAsset;
+    type Settings = ();
+    type Error = CocosLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        &(): &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        debug!(
+            "Loading Cocos2d sprite sheet from {:?}",
+            load_context.path()
+        );
+        let sheet = {
+            let mut text = String::new();
+            reader.read_to_string(&mut text).await?;
+            parse(&text)?
+        };
+
+        let image = sheet.image.unwrap_or_else(|| {
+            let path = load_context.path().with_extension("png");
+            let name = path.file_name().unwrap_or_default();
+            name.to_string_lossy().into_owned()
+        });
+        let image_path = load_context.asset_path().resolve_embed(&image)?;
+        trace!("Loading Cocos2d sprite sheet image from: {image_path}");
+        let texture = load_context
+            .loader()
+            .immediate()
+            .load::<Image>(image_path)
+            .await?;
+
+        let mut paths = TextureAtlasPaths::default();
+        let mut textures = Vec::with_capacity(sheet.frames.len());
+        for (name, rect, info) in sheet.frames {
+            paths.add(None, Some(name), info);
+            textures.push(rect);
+        }
+
+        let layout = TextureAtlasLayout {
+            size: texture.get().size(),
+            textures,
+        };
+        debug!(
+            "Loaded Cocos2d sprite sheet containing {} frames",
+            paths.path_indices.len(),
+        );
+        Ok(TextureAtlasAsset::from_pages(
+            load_context,
+            [(layout, texture)],
+            paths,
+        ))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["plist"]
+    }
+}
+
+/// The contents of a Cocos2d sprite sheet.
+struct CocosSheet {
+    /// The path of the sheet image, relative to the `.plist` file.
+    image: Option<String>,
+    /// The name, area, and info of each frame.
+    frames: Vec<(String, URect, TextureInfo)>,
+}
+
+/// A value in an XML property list.
+enum PlistValue<'a> {
+    String(&'a str),
+    Integer(i64),
+    Real(f64),
+    Bool(bool),
+    Dict(Vec<(&'a str, PlistValue<'a>)>),
+    Array,
+}
+
+impl<'a> PlistValue<'a> {
+    /// Reads the value of an element, or returns `None` if it is not a property list value.
+    fn parse(node: roxmltree::Node<'a, '_>) -> Option<Self> {
+        let text = || node.text().unwrap_or_default().trim();
+        Some(match node.tag_name().name() {
+            "string" => Self::String(text()),
+            "integer" => Self::Integer(text().parse().ok()?),
+            "real" => Self::Real(text().parse().ok()?),
+            "true" => Self::Bool(true),
+            "false" => Self::Bool(false),
+            "array" => Self::Array,
+            "dict" => {
+                let mut entries = Vec::new();
+                let mut elements = node.children().filter(|child| child.is_element());
+                while let (Some(key), Some(value)) = (elements.next(), elements.next()) {
+                    if !key.has_tag_name("key") {
+                        return None;
+                    }
+                    entries.push((key.text().unwrap_or_default(), Self::parse(value)?));
+                }
+                Self::Dict(entries)
+            }
+            _ => return None,
+        })
+    }
+
+    fn get(&self, key: &str) -> Option<&PlistValue<'a>> {
+        match self {
+            Self::Dict(entries) => entries
+                .iter()
+                .find_map(|(name, value)| (*name == key).then_some(value)),
+            _ => None,
+        }
+    }
+
+    fn as_number(&self) -> Option<f32> {
+        match *self {
+            Self::Integer(value) => Some(value as f32),
+            Self::Real(value) => Some(value as f32),
+            Self::String(value) => value.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Reads the numbers of a `{x,y}` or `{{x,y},{w,h}}` string.
+    fn as_numbers(&self) -> Option<Vec<f32>> {
+        let Self::String(value) = self else {
+            return None;
+        };
+        value
+            .split(',')
+            .map(|number| number.trim_matches(|c: char| c == '{' || c == '}' || c.is_whitespace()))
+            .map(|number| number.parse().ok())
+            .collect()
+    }
+}
+
+/// The values of a single frame.
+struct CocosFrame {
+    /// The frame's area within the sheet, with its unrotated size.
+    rect: Rect,
+    /// Whether the frame is rotated 90° clockwise within the sheet.
+    rotated: bool,
+    /// The offset of the frame's center from the center of the untrimmed image, with `y` pointing up.
+    offset: Vec2,
+    /// The size of the untrimmed image.
+    source_size: Vec2,
+    /// The frame's area within the untrimmed image, if it is given.
+    source_rect: Option<Rect>,
+}
+
+/// Reads the values of a single frame.
+struct FrameReader<'a, 'b> {
+    name: &'b str,
+    frame: &'b PlistValue<'a>,
+}
+
+impl<'a> FrameReader<'a, '_> {
+    fn get<T>(
+        &self,
+        key: &'static str,
+        convert: impl FnOnce(&PlistValue<'a>) -> Option<T>,
+    ) -> Result<Option<T>, CocosParseError> {
+        self.frame
+            .get(key)
+            .map(|value| {
+                convert(value).ok_or_else(|| CocosParseError::InvalidValue {
+                    frame: self.name.to_owned(),
+                    key,
+                })
+            })
+            .transpose()
+    }
+
+    fn number(&self, key: &'static str) -> Result<Option<f32>, CocosParseError> {
+        self.get(key, PlistValue::as_number)
+    }
+
+    fn vec2(&self, key: &'static str) -> Result<Option<Vec2>, CocosParseError> {
+        self.get(key, |value| match value.as_numbers()?[..] {
+            [x, y] => Some(Vec2::new(x, y)),
+            _ => None,
+        })
+    }
+
+    fn rect(&self, key: &'static str) -> Result<Option<Rect>, CocosParseError> {
+        self.get(key, |value| match value.as_numbers()?[..] {
+            [x, y, width, height] => Some(Rect::new(x, y, x + width, y + height)),
+            _ => None,
+        })
+    }
+
+    fn bool(&self, key: &'static str) -> Result<bool, CocosParseError> {
+        self.get(key, |value| match *value {
+            PlistValue::Bool(value) => Some(value),
+            _ => None,
+        })
+        .map(Option::unwrap_or_default)
+    }
+
+    /// Reads the frame's values in the given sheet format.
+    fn read(&self, format: i64) -> Result<CocosFrame, CocosParseError> {
+        let missing = |key| CocosParseError::MissingValue {
+            frame: self.name.to_owned(),
+            key,
+        };
+        match format {
+            0 => {
+                let required = |key| self.number(key)?.ok_or_else(|| missing(key));
+                let (x, y) = (required("x")?, required("y")?);
+                let size = Vec2::new(required("width")?, required("height")?);
+                let offset = Vec2::new(
+                    self.number("offsetX")?.unwrap_or_default(),
+                    self.number("offsetY")?.unwrap_or_default(),
+                );
+                let source_size = match (
+                    self.number("originalWidth")?,
+                    self.number("originalHeight")?,
+                ) {
+                    (Some(width), Some(height)) => Vec2::new(width, height).abs(),
+                    _ => size,
+                };
+                Ok(CocosFrame {
+                    rect: Rect::new(x, y, x + size.x, y + size.y),
+                    rotated: false,
+                    offset,
+                    source_size,
+                    source_rect: None,
+                })
+            }
+            1 | 2 => {
+                let rect = self.rect("frame")?.ok_or_else(|| missing("frame"))?;
+                let rotated = format == 2 && self.bool("rotated")?;
+                let offset = self.vec2("offset")?.unwrap_or_default();
+                let source_size = self.vec2("sourceSize")?.unwrap_or(rect.size());
+                Ok(CocosFrame {
+                    rect,
+                    rotated,
+                    offset,
+                    source_size,
+                    source_rect: self.rect("sourceColorRect")?,
+                })
+            }
+            3 => {
+                let rect = self
+                    .rect("textureRect")?
+                    .ok_or_else(|| missing("textureRect"))?;
+                let rotated = self.bool("textureRotated")?;
+                let offset = self.vec2("spriteOffset")?.unwrap_or_default();
+                let source_size = self.vec2("spriteSourceSize")?.unwrap_or(rect.size());
+                Ok(CocosFrame {
+                    rect,
+                    rotated,
+                    offset,
+                    source_size,
+                    source_rect: None,
+                })
+            }
+            format => Err(CocosParseError::UnsupportedFormat(format)),
+        }
+    }
+}
+
+/// Parses a Cocos2d sprite sheet.
+fn parse(text: &str) -> Result<CocosSheet, CocosParseError> {
+    // Property lists are usually written with a DTD declaration, which must be explicitly allowed
+    let options = roxmltree::ParsingOptions {
+        allow_dtd: true,
+        ..default()
+    };
+    let document = roxmltree::Document::parse_with_options(text, options)?;
+    let root = document.root_element();
+    let plist = if root.has_tag_name("plist") {
+        root.children()
+            .find(|node| node.is_element())
+            .and_then(PlistValue::parse)
+    } else {
+        PlistValue::parse(root)
+    }
+    .ok_or(CocosParseError::MissingFrames)?;
+
+    let metadata = plist.get("metadata");
+    let format = metadata
+        .and_then(|metadata| metadata.get("format"))
+        .and_then(PlistValue::as_number)
+        .map_or(0, |format| format as i64);
+    let image = metadata
+        .and_then(|metadata| {
+            metadata
+                .get("textureFileName")
+                .or_else(|| metadata.get("realTextureFileName"))
+        })
+        .and_then(|name| match *name {
+            PlistValue::String(name) if !name.is_empty() => Some(name.to_owned()),
+            _ => None,
+        });
+
+    let Some(PlistValue::Dict(entries)) = plist.get("frames") else {
+        return Err(CocosParseError::MissingFrames);
+    };
+    let mut frames = Vec::with_capacity(entries.len());
+    for (name, frame) in entries {
+        let reader = FrameReader { name, frame };
+        let CocosFrame {
+            rect,
+            rotated,
+            offset,
+            source_size,
+            source_rect,
+        } = reader.read(format)?;
+
+        let size = rect.size();
+        let packed_size = if rotated { size.yx() } else { size };
+        let rect = Rect::from_corners(rect.min, rect.min + packed_size).as_urect();
+
+        // The trimmed area is centered in the untrimmed image, then moved by the offset
+        let source_rect = source_rect.unwrap_or_else(|| {
+            let min = (source_size - size) / 2.0 + Vec2::new(offset.x, -offset.y);
+            Rect::from_corners(min, min + size)
+        });
+        let trim = (source_rect.min != Vec2::ZERO || source_size != size).then(|| TextureTrim {
+            offset: source_rect.min.max(Vec2::ZERO).round().as_uvec2(),
+            source_size: source_size.round().as_uvec2(),
+        });
+
+        frames.push((
+            (*name).to_owned(),
+            rect,
+            TextureInfo {
+                rotated,
+                trim,
+                ..default()
+            },
+        ));
+    }
+
+    Ok(CocosSheet { image, frames })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Wraps the `frames` and `metadata` dictionaries' contents in a property list.
+    fn plist(frames: &str, metadata: &str) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple Computer//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+    <dict>
+        <key>frames</key>
+        <dict>{frames}</dict>
+        <key>metadata</key>
+        <dict>{metadata}</dict>
+    </dict>
+</plist>"#
+        )
+    }
+
+    #[test]
+    fn parses_format_2() {
+        let sheet = parse(&plist(
+            "<key>hero.png</key>
+            <dict>
+                <key>frame</key><string>{{2,4},{16,32}}</string>
+                <key>offset</key><string>{-1,1}</string>
+                <key>rotated</key><false/>
+                <key>sourceColorRect</key><string>{{1,1},{16,32}}</string>
+                <key>sourceSize</key><string>{20,36}</string>
+            </dict>
+            <key>door.png</key>
+            <dict>
+                <key>frame</key><string>{{20,0},{8,12}}</string>
+                <key>offset</key><string>{0,0}</string>
+                <key>rotated</key><true/>
+                <key>sourceSize</key><string>{8,12}</string>
+            </dict>",
+            "<key>format</key><integer>2</integer>
+            <key>textureFileName</key><string>sheet.png</string>",
+        ))
+        .unwrap();
+        assert_eq!(sheet.image.as_deref(), Some("sheet.png"));
+
+        let [(hero, hero_rect, hero_info), (door, door_rect, door_info)] = &sheet.frames[..] else {
+            panic!("expected two frames");
+        };
+        assert_eq!(hero, "hero.png");
+        assert_eq!(*hero_rect, URect::new(2, 4, 18, 36));
+        let trim = hero_info.trim.as_ref().unwrap();
+        assert_eq!(trim.offset, UVec2::new(1, 1));
+        assert_eq!(trim.source_size, UVec2::new(20, 36));
+
+        // Rotated frames list their size before they were rotated
+        assert_eq!(door, "door.png");
+        assert!(door_info.rotated);
+        assert_eq!(*door_rect, URect::new(20, 0, 32, 8));
+        assert!(door_info.trim.is_none());
+    }
+
+    #[test]
+    fn parses_format_0() {
+        let sheet = parse(&plist(
+            "<key>coin</key>
+            <dict>
+                <key>x</key><integer>4</integer>
+                <key>y</key><integer>8</integer>
+                <key>width</key><integer>10</integer>
+                <key>height</key><integer>12</integer>
+                <key>offsetX</key><real>1</real>
+                <key>offsetY</key><real>-1</real>
+                <key>originalWidth</key><integer>16</integer>
+                <key>originalHeight</key><integer>-16</integer>
+            </dict>",
+            "<key>format</key><integer>0</integer>",
+        ))
+        .unwrap();
+        assert_eq!(sheet.image, None);
+        let (_, rect, info) = &sheet.frames[0];
+        assert_eq!(*rect, URect::new(4, 8, 14, 20));
+        // The trimmed area is centered, then moved right and down by the offset
+        let trim = info.trim.as_ref().unwrap();
+        assert_eq!(trim.offset, UVec2::new(4, 3));
+        assert_eq!(trim.source_size, UVec2::new(16, 16));
+    }
+
+    #[test]
+    fn parses_format_3() {
+        let sheet = parse(&plist(
+            "<key>gem</key>
+            <dict>
+                <key>textureRect</key><string>{{0,0},{6,10}}</string>
+                <key>textureRotated</key><true/>
+                <key>spriteOffset</key><string>{0,0}</string>
+                <key>spriteSourceSize</key><string>{6,10}</string>
+            </dict>",
+            "<key>format</key><integer>3</integer>
+            <key>realTextureFileName</key><string>gems.png</string>",
+        ))
+        .unwrap();
+        assert_eq!(sheet.image.as_deref(), Some("gems.png"));
+        let (_, rect, info) = &sheet.frames[0];
+        assert!(info.rotated);
+        assert_eq!(*rect, URect::new(0, 0, 10, 6));
+    }
+
+    #[test]
+    fn reports_invalid_sheets() {
+        let frame = "<key>coin</key><dict><key>frame</key><string>{{0,0},{8,8}}</string></dict>";
+        let format = |format| format!("<key>format</key><integer>{format}</integer>");
+
+        let error = parse(&plist(frame, &format(4))).err().unwrap();
+        assert!(matches!(error, CocosParseError::UnsupportedFormat(4)));
+
+        let error = parse(&plist(frame, &format(3))).err().unwrap();
+        assert!(matches!(
+            error,
+            CocosParseError::MissingValue {
+                key: "textureRect",
+                ..
+            }
+        ));
+
+        let frame = "<key>coin</key><dict><key>frame</key><string>{0,0}</string></dict>";
+        let error = parse(&plist(frame, &format(2))).err().unwrap();
+        assert!(matches!(
+            error,
+            CocosParseError::InvalidValue { key: "frame", .. }
+        ));
+
+        let error = parse("<plist><array/></plist>").err().unwrap();
+        assert!(matches!(error, CocosParseError::MissingFrames));
+    }
+}
@@ -0,0 +1,67 @@
+//! Encoding of atlas images as DDS files containing BCn block compressed textures.
+
+use bevy::prelude::*;
+use ddsfile::{AlphaMode, D3D10ResourceDimension, Dds, DxgiFormat, NewDxgiParams};
+use intel_tex_2::{bc1, bc3, bc7, RgbaSurface};
+
+use super::loader::{BcFormat, DdsSettings, SaverError};
+
+/// Encodes an image with the given RGBA8 `pixels` as a DDS file containing a single texture in
+/// [`DdsSettings::format`]. Both dimensions of `size` must be multiples of 4.
+pub(crate) fn encode(
+    pixels: &[u8],
+    size: UVec2,
+    srgb: bool,
+    settings: &DdsSettings,
+) -> Result<Vec<u8>, SaverError> {
+    let surface = RgbaSurface {
+        data: pixels,
+        width: size.x,
+        height: size.y,
+        stride: size.x * 4,
+    };
+
+    trace!("Encoding {size} atlas image as {:?}", settings.format);
+    let (blocks, formats) = match settings.format {
+        BcFormat::Bc1 => (
+            bc1::compress_blocks(&surface),
+            (DxgiFormat::BC1_UNorm, DxgiFormat::BC1_UNorm_sRGB),
+        ),
+        BcFormat::Bc3 => (
+            bc3::compress_blocks(&surface),
+            (DxgiFormat::BC3_UNorm, DxgiFormat::BC3_UNorm_sRGB),
+        ),
+        BcFormat::Bc7 => {
+            // Opaque images are encoded with the modes that leave the most bits for color
+            let opaque = pixels.chunks_exact(4).all(|pixel| pixel[3] == u8::MAX);
+            let encode_settings = if opaque {
+                bc7::opaque_basic_settings()
+            } else {
+                bc7::alpha_basic_settings()
+            };
+            (
+                bc7::compress_blocks(&encode_settings, &surface),
+                (DxgiFormat::BC7_UNorm, DxgiFormat::BC7_UNorm_sRGB),
+            )
+        }
+    };
+    let format = if srgb { formats.1 } else { formats.0 };
+
+    let mut dds = Dds::new_dxgi(NewDxgiParams {
+        height: size.y,
+        width: size.x,
+        depth: None,
+        format,
+        mipmap_levels: None,
+        array_layers: None,
+        caps2: None,
+        is_cubemap: false,
+        resource_dimension: D3D10ResourceDimension::Texture2D,
+        alpha_mode: AlphaMode::Straight,
+    })?;
+    dds.data = blocks;
+
+    let mut buffer = Vec::new();
+    dds.write(&mut buffer)?;
+    Ok(buffer)
+}
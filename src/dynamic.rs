@@ -0,0 +1,255 @@
+//! An atlas that textures can be added to and removed from while the game runs, for content that is only known at
+//! runtime such as glyphs, avatars, or downloaded thumbnails.
+
+use bevy::{prelude::*, render::render_resource::TextureFormat};
+use thiserror::Error;
+
+use super::{
+    loader::ATLAS_FORMAT,
+    packer::{self, Guillotine},
+};
+
+/// Errors encountered when allocating space in a [`DynamicAtlas`].
+#[derive(Debug, Error)]
+pub enum DynamicAtlasError {
+    #[error("The atlas's texture or layout asset was removed")]
+    NotLoaded,
+    #[error("The image has the format {0:?}, which can't be converted to the atlas format")]
+    TextureFormat(TextureFormat),
+    #[error(
+        "The image has the size {size}, which is larger than the maximum atlas size of {max_size}"
+    )]
+    TooLarge { size: UVec2, max_size: UVec2 },
+    #[error("There is no room for an image of size {0} in the atlas")]
+    Full(UVec2),
+}
+
+/// A single page atlas whose textures are allocated and freed at runtime, rather than packed ahead of time.
+///
+/// Space is allocated with a guillotine allocator, and freed space is merged with its neighbors so that it can be
+/// reused by later allocations. The atlas's texture starts at an initial size and doubles in size whenever an image
+/// doesn't fit, up to a maximum size. Its texture and layout are ordinary assets that are modified in place, so
+/// sprites using them stay valid as images are added.
+///
+/// The texture is kept in main memory and modified there, rather than being a GPU texture that only the allocated
+/// area is written to. Every allocation therefore re-uploads the whole texture to the GPU, which costs as much as
+/// uploading a new texture of the atlas's current size, so images are best allocated in batches within a frame.
+///
+/// Adding it with `app.init_resource::<DynamicAtlas>()` creates a 256x256 atlas that grows up to 2048x2048.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_mod_atlas_loader::dynamic::DynamicAtlas;
+/// fn add_avatar(
+///     mut commands: Commands,
+///     mut atlas: ResMut<DynamicAtlas>,
+///     mut images: ResMut<Assets<Image>>,
+///     mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+/// ) {
+///     let avatar = Image::default();
+///     let (index, _) = atlas.allocate(&avatar, &mut images, &mut layouts).unwrap();
+///     commands.spawn(atlas.sprite(index).unwrap());
+/// }
+/// ```
+#[derive(Resource, Debug, Clone)]
+pub struct DynamicAtlas {
+    texture: Handle<Image>,
+    layout: Handle<TextureAtlasLayout>,
+    size: UVec2,
+    max_size: UVec2,
+    padding: UVec2,
+    allocator: Guillotine,
+    /// The area of each allocated texture by index, or `None` if it was freed.
+    rects: Vec<Option<URect>>,
+}
+
+impl FromWorld for DynamicAtlas {
+    fn from_world(world: &mut World) -> Self {
+        world.resource_scope(|world, mut images: Mut<Assets<Image>>| {
+            let mut layouts = world.resource_mut::<Assets<TextureAtlasLayout>>();
+            Self::new(
+                UVec2::splat(256),
+                UVec2::splat(2048),
+                &mut images,
+                &mut layouts,
+            )
+        })
+    }
+}
+
+impl DynamicAtlas {
+    /// Creates an empty atlas of the given size, which grows up to `max_size` as images are allocated. Its texture and
+    /// layout are added to `images` and `layouts`.
+    pub fn new(
+        size: UVec2,
+        max_size: UVec2,
+        images: &mut Assets<Image>,
+        layouts: &mut Assets<TextureAtlasLayout>,
+    ) -> Self {
+        let size = size.max(UVec2::ONE).min(max_size);
+        Self {
+            texture: images.add(packer::blank(size, ATLAS_FORMAT)),
+            layout: layouts.add(TextureAtlasLayout::new_empty(size)),
+            size,
+            max_size,
+            padding: UVec2::ZERO,
+            allocator: Guillotine::new(size),
+            rects: Vec::new(),
+        }
+    }
+
+    /// Sets the spacing left to the right and bottom of each allocated image. This should be set before any images
+    /// are allocated.
+    pub fn with_padding(mut self, padding: UVec2) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Returns the atlas's texture.
+    pub fn texture(&self) -> Handle<Image> {
+        self.texture.clone()
+    }
+
+    /// Returns the atlas's layout, which lists the area of each allocated image by index.
+    pub fn layout(&self) -> Handle<TextureAtlasLayout> {
+        self.layout.clone()
+    }
+
+    /// Returns the current size of the atlas's texture.
+    pub fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    /// Returns the area of the image allocated at the given index, if it hasn't been freed.
+    pub fn rect(&self, index: usize) -> Option<URect> {
+        self.rects.get(index).copied().flatten()
+    }
+
+    /// Returns the number of allocated images.
+    pub fn len(&self) -> usize {
+        self.rects.iter().flatten().count()
+    }
+
+    /// Returns `true` if no images are allocated.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Creates a [`TextureAtlas`] handle for the image allocated at the given index, if it hasn't been freed.
+    pub fn texture_atlas(&self, index: usize) -> Option<TextureAtlas> {
+        self.rect(index)?;
+        Some(TextureAtlas {
+            layout: self.layout.clone(),
+            index,
+        })
+    }
+
+    /// Creates a [`Sprite`] displaying the image allocated at the given index, if it hasn't been freed.
+    pub fn sprite(&self, index: usize) -> Option<Sprite> {
+        Some(Sprite::from_atlas_image(
+            self.texture.clone(),
+            self.texture_atlas(index)?,
+        ))
+    }
+
+    /// Copies `image` into free space in the atlas, growing the atlas if there is no room for it, and returns its
+    /// index in the atlas's layout along with its area. Indices of freed images are reused.
+    pub fn allocate(
+        &mut self,
+        image: &Image,
+        images: &mut Assets<Image>,
+        layouts: &mut Assets<TextureAtlasLayout>,
+    ) -> Result<(usize, URect), DynamicAtlasError> {
+        let format = image.texture_descriptor.format;
+        let converted;
+        let image = if format == ATLAS_FORMAT {
+            image
+        } else {
+            converted = image
+                .convert(ATLAS_FORMAT)
+                .ok_or(DynamicAtlasError::TextureFormat(format))?;
+            &converted
+        };
+        let size = image.size();
+        let padded = size + self.padding;
+        if padded.cmpgt(self.max_size).any() {
+            return Err(DynamicAtlasError::TooLarge {
+                size,
+                max_size: self.max_size,
+            });
+        }
+        if !images.contains(&self.texture) || !layouts.contains(&self.layout) {
+            return Err(DynamicAtlasError::NotLoaded);
+        }
+
+        let position = loop {
+            if let Some(position) = self.allocator.insert(padded) {
+                break position;
+            }
+            if !self.grow(images) {
+                return Err(DynamicAtlasError::Full(size));
+            }
+        };
+        let rect = URect::from_corners(position, position + size);
+        let texture = images.get_mut(&self.texture).unwrap();
+        packer::blit(texture, image, position);
+
+        let index = match self.rects.iter().position(Option::is_none) {
+            Some(index) => {
+                self.rects[index] = Some(rect);
+                index
+            }
+            None => {
+                self.rects.push(Some(rect));
+                self.rects.len() - 1
+            }
+        };
+        let layout = layouts.get_mut(&self.layout).unwrap();
+        layout.size = self.size;
+        if index < layout.textures.len() {
+            layout.textures[index] = rect;
+        } else {
+            layout.textures.push(rect);
+        }
+        Ok((index, rect))
+    }
+
+    /// Frees the space of the image allocated at the given index so that it can be reused, returning its area. The
+    /// image's pixels are left in the texture until they are overwritten, but its rect in the layout is emptied, so
+    /// that sprites still displaying the index show nothing rather than whichever image later takes its place.
+    pub fn free(
+        &mut self,
+        index: usize,
+        layouts: &mut Assets<TextureAtlasLayout>,
+    ) -> Option<URect> {
+        let rect = self.rects.get_mut(index)?.take()?;
+        let layout = layouts.get_mut(&self.layout);
+        if let Some(layout_rect) = layout.and_then(|layout| layout.textures.get_mut(index)) {
+            *layout_rect = URect::default();
+        }
+        let padded = URect::from_corners(rect.min, rect.max + self.padding);
+        // Empty images are placed without taking any space
+        if padded.width() > 0 && padded.height() > 0 {
+            self.allocator.free(padded);
+        }
+        Some(rect)
+    }
+
+    /// Doubles the size of the atlas's texture, up to its max size, keeping the allocated images in place. Returns
+    /// `false` if the atlas is already at its max size.
+    fn grow(&mut self, images: &mut Assets<Image>) -> bool {
+        let size = (self.size * 2).min(self.max_size);
+        if size == self.size {
+            return false;
+        }
+        trace!("Growing dynamic atlas from {} to {size}", self.size);
+        let texture = images.get_mut(&self.texture).unwrap();
+        let mut grown = packer::blank(size, ATLAS_FORMAT);
+        packer::blit(&mut grown, texture, UVec2::ZERO);
+        grown.sampler = texture.sampler.clone();
+        *texture = grown;
+        self.allocator.grow(self.size, size);
+        self.size = size;
+        true
+    }
+}
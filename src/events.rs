@@ -0,0 +1,91 @@
+//! Events sent as texture atlases finish loading, are rebuilt, or fail to load.
+
+use bevy::{
+    asset::{AssetLoadError, AssetLoadFailedEvent, AssetPath},
+    prelude::*,
+    utils::HashSet,
+};
+
+use super::TextureAtlasAsset;
+
+/// An [`Event`] sent when a [`TextureAtlasAsset`] finishes loading, is rebuilt, or fails to load, so that systems can
+/// react to atlases without polling [`AssetServer::load_state`].
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_mod_atlas_loader::{events::AtlasEvent, TextureAtlasAsset};
+/// fn spawn_hero(mut events: EventReader<AtlasEvent>, mut commands: Commands, atlases: Res<Assets<TextureAtlasAsset>>) {
+///     for event in events.read() {
+///         if let AtlasEvent::Ready { handle } = event {
+///             let atlas = atlases.get(handle).unwrap();
+///             if let Some(texture_atlas) = atlas.handle_from_name("hero") {
+///                 commands.spawn(Sprite::from_atlas_image(atlas.texture.clone(), texture_atlas));
+///             }
+///         }
+///     }
+/// }
+/// ```
+#[derive(Event, Debug, Clone)]
+pub enum AtlasEvent {
+    /// The atlas and all of its sub-assets finished loading for the first time.
+    Ready { handle: Handle<TextureAtlasAsset> },
+    /// The atlas was modified after it was ready, such as when it is rebuilt by hot reloading.
+    Rebuilt { handle: Handle<TextureAtlasAsset> },
+    /// The atlas failed to load or rebuild.
+    Failed {
+        handle: Handle<TextureAtlasAsset>,
+        /// The path of the atlas.
+        path: AssetPath<'static>,
+        /// Why the atlas failed to load.
+        error: AssetLoadError,
+    },
+}
+
+impl AtlasEvent {
+    /// Returns the handle of the atlas that the event is about.
+    pub fn handle(&self) -> &Handle<TextureAtlasAsset> {
+        match self {
+            Self::Ready { handle } | Self::Rebuilt { handle } | Self::Failed { handle, .. } => {
+                handle
+            }
+        }
+    }
+}
+
+/// Sends an [`AtlasEvent`] for each [`AssetEvent`] and [`AssetLoadFailedEvent`] of a [`TextureAtlasAsset`]. Atlases
+/// that are no longer referenced by any handle are skipped.
+pub(crate) fn send_atlas_events(
+    mut asset_events: EventReader<AssetEvent<TextureAtlasAsset>>,
+    mut failed_events: EventReader<AssetLoadFailedEvent<TextureAtlasAsset>>,
+    asset_server: Res<AssetServer>,
+    mut ready: Local<HashSet<AssetId<TextureAtlasAsset>>>,
+    mut events: EventWriter<AtlasEvent>,
+) {
+    for event in asset_events.read() {
+        match *event {
+            AssetEvent::LoadedWithDependencies { id } if ready.insert(id) => {
+                if let Some(handle) = asset_server.get_id_handle(id) {
+                    events.send(AtlasEvent::Ready { handle });
+                }
+            }
+            AssetEvent::Modified { id } if ready.contains(&id) => {
+                if let Some(handle) = asset_server.get_id_handle(id) {
+                    events.send(AtlasEvent::Rebuilt { handle });
+                }
+            }
+            AssetEvent::Removed { id } => {
+                ready.remove(&id);
+            }
+            _ => {}
+        }
+    }
+    for event in failed_events.read() {
+        if let Some(handle) = asset_server.get_id_handle(event.id) {
+            events.send(AtlasEvent::Failed {
+                handle,
+                path: event.path.clone(),
+                error: event.error.clone(),
+            });
+        }
+    }
+}
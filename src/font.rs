@@ -0,0 +1,238 @@
+//! Rasterizing the glyphs of TrueType and OpenType fonts for the `font` option of atlas build configs, which requires
+//! the `font` feature.
+//!
+//! Glyphs are rasterized from their outlines at a single size, without hinting. Bitmap and color glyphs are skipped,
+//! and kerning pairs are only read from the font's `kern` table.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use bevy::{
+    asset::RenderAssetUsages,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+use ttf_parser::{Face, FaceParsingError, GlyphId, OutlineBuilder};
+use zeno::{Command, Mask, Transform};
+
+use super::{
+    bmfont::BmFontGlyph,
+    sdf::{self, Contour},
+    DistanceField,
+};
+
+/// A rasterized glyph, which is white with its coverage in the alpha channel, or a distance field of its outline.
+pub(crate) struct Glyph {
+    pub character: char,
+    pub image: Image,
+    pub metrics: BmFontGlyph,
+}
+
+/// Rasterizes each of `characters` that the font in `bytes` has a glyph for at `size` pixels per em, or generates a
+/// distance field of its outline if `field` is given. Returns the glyphs in the order of their codepoints, along with
+/// the characters that the font has no glyph for.
+pub(crate) fn rasterize(
+    bytes: &[u8],
+    size: f32,
+    characters: impl IntoIterator<Item = char>,
+    field: Option<DistanceField>,
+) -> Result<(Vec<Glyph>, Vec<char>), FaceParsingError> {
+    let characters: BTreeSet<char> = characters.into_iter().collect();
+    let face = Face::parse(bytes, 0)?;
+    let scale = size / f32::from(face.units_per_em());
+    let ascender = (f32::from(face.ascender()) * scale).round() as i32;
+
+    let mut ids = Vec::with_capacity(characters.len());
+    let mut missing = Vec::new();
+    for character in characters {
+        match face.glyph_index(character) {
+            Some(id) => ids.push((character, id)),
+            None => missing.push(character),
+        }
+    }
+
+    let glyphs = ids
+        .iter()
+        .map(|&(character, id)| {
+            let mut outline = Outline::default();
+            let (image, offset) = match (face.outline_glyph(id, &mut outline), field) {
+                (Some(bounds), Some(field)) if !outline.0.is_empty() => {
+                    // The field is grown past the glyph's bounds to make room for the distances outside of it
+                    let padding = field.padding() as f32;
+                    let min = Vec2::new(bounds.x_min.into(), -f32::from(bounds.y_max));
+                    let max = Vec2::new(bounds.x_max.into(), -f32::from(bounds.y_min));
+                    let min = (min * scale).floor() - padding;
+                    let max = (max * scale).ceil() + padding;
+                    let contours = outline.contours(|x, y| Vec2::new(x, -y) * scale - min);
+                    let image = sdf::from_outline(&contours, (max - min).as_uvec2(), field);
+                    (image, IVec2::new(0, ascender) + min.as_ivec2())
+                }
+                (Some(_), None) if !outline.0.is_empty() => {
+                    // Font units point up, so the outline is flipped to point down like the image does
+                    let (coverage, placement) = Mask::new(&outline.0)
+                        .transform(Some(Transform::scale(scale, -scale)))
+                        .render();
+                    let size = UVec2::new(placement.width, placement.height);
+                    let image = glyph_image(size.max(UVec2::ONE), &coverage);
+                    (image, IVec2::new(placement.left, ascender + placement.top))
+                }
+                _ => (glyph_image(UVec2::ONE, &[0]), IVec2::ZERO),
+            };
+            let advance = face.glyph_hor_advance(id).unwrap_or(0);
+            let metrics = BmFontGlyph {
+                offset,
+                advance: (f32::from(advance) * scale).round() as i32,
+                kerning: kerning(&face, id, &ids, scale),
+            };
+            Glyph {
+                character,
+                image,
+                metrics,
+            }
+        })
+        .collect();
+    Ok((glyphs, missing))
+}
+
+/// Returns the kerning adjustments in pixels when the glyph `left` is followed by each of `glyphs`, by the codepoint of
+/// the following glyph.
+fn kerning(
+    face: &Face,
+    left: GlyphId,
+    glyphs: &[(char, GlyphId)],
+    scale: f32,
+) -> BTreeMap<u32, i32> {
+    let Some(kern) = face.tables().kern else {
+        return BTreeMap::new();
+    };
+    let subtables: Vec<_> = kern
+        .subtables
+        .into_iter()
+        .filter(|subtable| subtable.horizontal && !subtable.variable && !subtable.has_cross_stream)
+        .collect();
+    glyphs
+        .iter()
+        .filter_map(|&(character, right)| {
+            let units: i32 = subtables
+                .iter()
+                .filter_map(|subtable| subtable.glyphs_kerning(left, right))
+                .map(i32::from)
+                .sum();
+            let amount = (units as f32 * scale).round() as i32;
+            (amount != 0).then_some((u32::from(character), amount))
+        })
+        .collect()
+}
+
+/// Returns a white image with the given coverage of each pixel as its alpha.
+fn glyph_image(size: UVec2, coverage: &[u8]) -> Image {
+    let data = coverage
+        .iter()
+        .flat_map(|&alpha| [255, 255, 255, alpha])
+        .collect();
+    Image::new(
+        Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    )
+}
+
+/// Collects the outline of a glyph in font units as path commands.
+#[derive(Default)]
+struct Outline(Vec<Command>);
+
+impl Outline {
+    /// Returns the closed contours of the outline, with each point mapped to pixel coordinates by `point` and curves
+    /// flattened into polylines whose segments are about a pixel long.
+    fn contours(&self, point: impl Fn(f32, f32) -> Vec2) -> Vec<Contour> {
+        let point = |vector: zeno::Vector| point(vector.x, vector.y);
+        let mut contours = Vec::new();
+        let mut contour: Contour = Vec::new();
+        let (mut start, mut current) = (Vec2::ZERO, Vec2::ZERO);
+        let mut close = |contour: &mut Contour, current: Vec2, start: Vec2| {
+            if current != start {
+                contour.push(vec![current, start]);
+            }
+            if !contour.is_empty() {
+                contours.push(std::mem::take(contour));
+            }
+        };
+        for &command in &self.0 {
+            let edge: Vec<Vec2> = match command {
+                Command::MoveTo(to) => {
+                    close(&mut contour, current, start);
+                    (start, current) = (point(to), point(to));
+                    continue;
+                }
+                Command::LineTo(to) => vec![current, point(to)],
+                Command::QuadTo(control, to) => {
+                    let (control, to) = (point(control), point(to));
+                    flatten(current.distance(control) + control.distance(to), |t| {
+                        current.lerp(control, t).lerp(control.lerp(to, t), t)
+                    })
+                }
+                Command::CurveTo(first, second, to) => {
+                    let (first, second, to) = (point(first), point(second), point(to));
+                    let length =
+                        current.distance(first) + first.distance(second) + second.distance(to);
+                    flatten(length, |t| {
+                        let (a, b, c) = (
+                            current.lerp(first, t),
+                            first.lerp(second, t),
+                            second.lerp(to, t),
+                        );
+                        a.lerp(b, t).lerp(b.lerp(c, t), t)
+                    })
+                }
+                Command::Close => {
+                    close(&mut contour, current, start);
+                    current = start;
+                    continue;
+                }
+            };
+            current = edge[edge.len() - 1];
+            contour.push(edge);
+        }
+        close(&mut contour, current, start);
+        contours
+    }
+}
+
+/// Returns the points of a curve of about the given length, sampled at each `t` from 0 to 1 by `at`.
+fn flatten(length: f32, at: impl Fn(f32) -> Vec2) -> Vec<Vec2> {
+    let segments = (length.ceil() as usize).clamp(1, 64);
+    (0..=segments)
+        .map(|segment| at(segment as f32 / segments as f32))
+        .collect()
+}
+
+impl OutlineBuilder for Outline {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.0.push(Command::MoveTo([x, y].into()));
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.0.push(Command::LineTo([x, y].into()));
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.0.push(Command::QuadTo([x1, y1].into(), [x, y].into()));
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.0.push(Command::CurveTo(
+            [x1, y1].into(),
+            [x2, y2].into(),
+            [x, y].into(),
+        ));
+    }
+
+    fn close(&mut self) {
+        self.0.push(Command::Close);
+    }
+}
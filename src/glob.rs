@@ -0,0 +1,292 @@
+//! Glob pattern matching, expansion, and directory walking for texture paths in `.atlas.ron` files.
+//!
+//! Patterns support `?` (any single character), `*` (any run of characters within a path component), and `**` (any
+//! number of whole path components). Paths may also contain numbered ranges such as `{0..7}`, which are expanded
+//! before any wildcards.
+
+use std::path::{Path, PathBuf};
+
+use bevy::{
+    asset::{
+        io::{AssetReaderError, AssetSourceId, ErasedAssetReader, MissingAssetSourceError},
+        processor::AssetProcessor,
+    },
+    prelude::*,
+    tasks::futures_lite::StreamExt,
+};
+
+/// Provides access to the unprocessed asset readers used to expand glob patterns. Asset loaders have no way to list
+/// directories through their [`LoadContext`], so the readers are captured when the plugin is built.
+///
+/// [`LoadContext`]: bevy::asset::LoadContext
+#[derive(Clone)]
+pub(crate) enum SourceReaders {
+    /// Reads from the main [`AssetServer`]'s sources.
+    Server(AssetServer),
+    /// Reads from the [`AssetProcessor`]'s unprocessed sources.
+    Processor(AssetProcessor),
+}
+
+impl FromWorld for SourceReaders {
+    fn from_world(world: &mut World) -> Self {
+        match world.get_resource::<AssetProcessor>() {
+            Some(processor) => Self::Processor(processor.clone()),
+            None => Self::Server(world.resource::<AssetServer>().clone()),
+        }
+    }
+}
+
+impl SourceReaders {
+    /// Returns the reader for the given asset source.
+    pub fn reader(
+        &self,
+        source: AssetSourceId<'_>,
+    ) -> Result<&dyn ErasedAssetReader, MissingAssetSourceError> {
+        let source = match self {
+            Self::Server(server) => server.get_source(source)?,
+            Self::Processor(processor) => processor.get_source(source)?,
+        };
+        Ok(source.reader())
+    }
+
+    /// Returns `true` if the readers belong to an [`AssetProcessor`], so that loaded assets may be processed and saved.
+    pub fn is_processor(&self) -> bool {
+        matches!(self, Self::Processor(_))
+    }
+
+    /// Returns `true` if the dependencies that loaders record are used, which they are by the asset processor to
+    /// reprocess assets and by an asset server that watches for changes to reload them.
+    pub fn tracks_dependencies(&self) -> bool {
+        match self {
+            Self::Server(server) => server.watching_for_changes(),
+            Self::Processor(_) => true,
+        }
+    }
+}
+
+/// Returns all file paths in `reader` matching `pattern`, sorted for deterministic ordering.
+pub(crate) async fn expand(
+    reader: &dyn ErasedAssetReader,
+    pattern: &Path,
+) -> Result<Vec<PathBuf>, AssetReaderError> {
+    // Only search below the longest leading part of the pattern without wildcards
+    let root: PathBuf = pattern
+        .iter()
+        .take_while(|component| !is_pattern(Path::new(component)))
+        .collect();
+    let recursive = pattern.iter().any(|component| component == "**");
+    let max_depth = (!recursive).then(|| pattern.iter().count());
+
+    let mut matches = walk(reader, &root, max_depth).await?;
+    matches.retain(|path| matches_pattern(pattern, path));
+    Ok(matches)
+}
+
+/// Returns all file paths below `root` in `reader`, sorted for deterministic ordering. If `max_depth` is given, files
+/// with more path components than `max_depth` are not searched for.
+pub(crate) async fn walk(
+    reader: &dyn ErasedAssetReader,
+    root: &Path,
+    max_depth: Option<usize>,
+) -> Result<Vec<PathBuf>, AssetReaderError> {
+    let mut files = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let mut entries = reader.read_directory(&dir).await?;
+        while let Some(entry) = entries.next().await {
+            if reader.is_directory(&entry).await? {
+                if max_depth.is_none_or(|depth| entry.iter().count() < depth) {
+                    pending.push(entry);
+                }
+            } else {
+                files.push(entry);
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// The largest number of paths that the numbered ranges of a single path can expand into.
+const MAX_RANGE_PATHS: usize = 65_536;
+
+/// Expands each numbered range such as `{0..7}` in `path` into one path per number, in ascending order. Ranges are
+/// inclusive, and numbers are zero-padded to the width of the range's start if it has leading zeros, as in
+/// `{00..15}`. Braces that don't contain `..` are left as they are. Returns `None` if a range is invalid, or if the
+/// ranges expand into more than [`MAX_RANGE_PATHS`] paths.
+pub(crate) fn expand_ranges(path: &Path) -> Option<Vec<PathBuf>> {
+    let text = path.to_string_lossy();
+    let range = text.match_indices('{').find_map(|(open, _)| {
+        let close = open + text[open..].find('}')?;
+        text[open + 1..close]
+            .contains("..")
+            .then_some((open, close))
+    });
+    let Some((open, close)) = range else {
+        return Some(vec![path.to_path_buf()]);
+    };
+
+    let (start_text, end_text) = text[open + 1..close].split_once("..")?;
+    let start: u32 = start_text.parse().ok()?;
+    let end: u32 = end_text.parse().ok()?;
+    if start > end || (end - start) as usize >= MAX_RANGE_PATHS {
+        return None;
+    }
+    let width = if start_text.len() > 1 && start_text.starts_with('0') {
+        start_text.len()
+    } else {
+        0
+    };
+
+    let (prefix, suffix) = (&text[..open], &text[close + 1..]);
+    let mut paths = Vec::with_capacity((end - start + 1) as usize);
+    for number in start..=end {
+        let path = PathBuf::from(format!("{prefix}{number:0width$}{suffix}"));
+        paths.extend(expand_ranges(&path)?);
+        if paths.len() > MAX_RANGE_PATHS {
+            return None;
+        }
+    }
+    Some(paths)
+}
+
+/// Returns `true` if the given path contains any wildcards.
+pub(crate) fn is_pattern(path: &Path) -> bool {
+    path.to_string_lossy().contains(['*', '?'])
+}
+
+/// Returns `true` if `path` matches the glob `pattern`.
+pub(crate) fn matches_pattern(pattern: &Path, path: &Path) -> bool {
+    let (pattern, path) = (components(pattern), components(path));
+    let pattern: Vec<&str> = pattern.iter().map(AsRef::as_ref).collect();
+    let path: Vec<&str> = path.iter().map(AsRef::as_ref).collect();
+    matches_components(&pattern, &path)
+}
+
+/// Returns `true` if the end of `path` matches the glob `pattern`, so that patterns such as `*.psd` match files in any
+/// directory.
+pub(crate) fn matches_suffix(pattern: &Path, path: &Path) -> bool {
+    let (pattern, path) = (components(pattern), components(path));
+    let pattern: Vec<&str> = pattern.iter().map(AsRef::as_ref).collect();
+    let path: Vec<&str> = path.iter().map(AsRef::as_ref).collect();
+    (0..path.len()).any(|skip| matches_components(&pattern, &path[skip..]))
+}
+
+fn components(path: &Path) -> Vec<std::borrow::Cow<'_, str>> {
+    path.iter().map(|c| c.to_string_lossy()).collect()
+}
+
+fn matches_components(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| matches_components(rest, &path[skip..])),
+        Some((first, rest)) => path.split_first().is_some_and(|(component, path)| {
+            matches_component(first, component) && matches_components(rest, path)
+        }),
+    }
+}
+
+/// Matches a single path component against a pattern containing `*` and `?` wildcards.
+fn matches_component(pattern: &str, component: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let component: Vec<char> = component.chars().collect();
+
+    let (mut p, mut c) = (0, 0);
+    // The position of the last `*` in the pattern, and the component position it was matched at
+    let mut backtrack = None;
+    while c < component.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, c));
+                p += 1;
+            }
+            Some(&ch) if ch == '?' || ch == component[c] => {
+                p += 1;
+                c += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    c = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&ch| ch == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, path: &str) -> bool {
+        let pattern: Vec<&str> = pattern.split('/').collect();
+        let path: Vec<&str> = path.split('/').collect();
+        matches_components(&pattern, &path)
+    }
+
+    fn expand(path: &str) -> Option<Vec<String>> {
+        let paths = expand_ranges(Path::new(path))?;
+        Some(
+            paths
+                .iter()
+                .map(|path| path.to_string_lossy().into_owned())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn matches_wildcards() {
+        assert!(matches("*.png", "hero.png"));
+        assert!(matches("hero_??.png", "hero_01.png"));
+        assert!(!matches("hero_??.png", "hero_1.png"));
+        assert!(matches("a*b*c", "aXbYbZc"));
+        assert!(!matches("a*b*c", "aXbYbZ"));
+        // Wildcards don't cross directories
+        assert!(!matches("*.png", "ui/hero.png"));
+        assert!(matches("*/*.png", "ui/hero.png"));
+    }
+
+    #[test]
+    fn matches_any_number_of_directories() {
+        assert!(matches("**/*.png", "hero.png"));
+        assert!(matches("**/*.png", "sprites/ui/hero.png"));
+        assert!(matches("sprites/**/hero.png", "sprites/hero.png"));
+        assert!(matches("sprites/**/hero.png", "sprites/a/b/hero.png"));
+        assert!(!matches("sprites/**/hero.png", "tiles/a/hero.png"));
+        assert!(!matches("sprites/**", "tiles/hero.png"));
+    }
+
+    #[test]
+    fn expands_ranges() {
+        assert_eq!(
+            expand("run_{1..3}.png").unwrap(),
+            ["run_1.png", "run_2.png", "run_3.png"]
+        );
+        assert_eq!(
+            expand("run_{08..10}.png").unwrap(),
+            ["run_08.png", "run_09.png", "run_10.png"]
+        );
+        assert_eq!(
+            expand("{0..1}/{0..1}.png").unwrap(),
+            ["0/0.png", "0/1.png", "1/0.png", "1/1.png"]
+        );
+        // Braces without a range are left alone
+        assert_eq!(expand("{name}.png").unwrap(), ["{name}.png"]);
+    }
+
+    #[test]
+    fn rejects_invalid_and_huge_ranges() {
+        assert_eq!(expand("{3..1}.png"), None);
+        assert_eq!(expand("{a..z}.png"), None);
+        assert_eq!(expand("{0..4294967295}.png"), None);
+        assert_eq!(
+            expand("{0..65535}.png").map(|paths| paths.len()),
+            Some(MAX_RANGE_PATHS)
+        );
+        assert_eq!(expand("{0..255}/{0..255}/{0..1}.png"), None);
+    }
+}
@@ -0,0 +1,100 @@
+//! Compositing atlas pages on the GPU for configs that set `gpu_composite`, rather than copying each texture's pixels
+//! into the page image on the CPU while the atlas loads.
+//!
+//! The loader leaves each page image blank and adds the textures that belong on it as labeled sub-assets. Once the
+//! render world has prepared the page and all of its textures, they are copied into place with
+//! `copy_texture_to_texture`, and the textures are then dropped.
+
+use std::sync::{Arc, Mutex};
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssets,
+        render_resource::{CommandEncoderDescriptor, ImageCopyTexture, Origin3d},
+        renderer::{RenderDevice, RenderQueue},
+        texture::GpuImage,
+        Render, RenderApp, RenderSet,
+    },
+};
+
+/// The textures to copy into a page image, along with the position of each on the page.
+pub(crate) struct GpuComposition {
+    pub page: Handle<Image>,
+    pub copies: Vec<(Handle<Image>, UVec2)>,
+}
+
+/// The compositions queued by the loader that haven't been picked up by the render world yet. Shared between the
+/// loader and the render world.
+#[derive(Resource, Clone, Default)]
+pub(crate) struct GpuCompositions(Arc<Mutex<Vec<GpuComposition>>>);
+
+impl GpuCompositions {
+    /// Queues a page to be composited once it and its textures are prepared.
+    pub fn push(&self, composition: GpuComposition) {
+        self.0.lock().unwrap().push(composition);
+    }
+}
+
+/// Adds the compositing system to the render app, returning the queue that the loader should add compositions to.
+/// Returns `None` if there is no render app, in which case pages are composited on the CPU.
+pub(crate) fn setup(app: &mut App) -> Option<GpuCompositions> {
+    let render_app = app.get_sub_app_mut(RenderApp)?;
+    let compositions = GpuCompositions::default();
+    render_app
+        .insert_resource(compositions.clone())
+        .add_systems(Render, composite_pages.in_set(RenderSet::PrepareResources));
+    Some(compositions)
+}
+
+/// Copies the textures of each queued page into it once the page and all of its textures have been prepared.
+fn composite_pages(
+    compositions: Res<GpuCompositions>,
+    mut pending: Local<Vec<GpuComposition>>,
+    images: Res<RenderAssets<GpuImage>>,
+    device: Res<RenderDevice>,
+    queue: Res<RenderQueue>,
+) {
+    pending.append(&mut compositions.0.lock().unwrap());
+    if pending.is_empty() {
+        return;
+    }
+
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+        label: Some("atlas_composition"),
+    });
+    let mut composited = 0;
+    pending.retain(|composition| {
+        let Some(page) = images.get(&composition.page) else {
+            return true;
+        };
+        let sources: Option<Vec<&GpuImage>> = composition
+            .copies
+            .iter()
+            .map(|(texture, _)| images.get(texture))
+            .collect();
+        let Some(sources) = sources else {
+            return true;
+        };
+        for (source, &(_, position)) in sources.iter().zip(composition.copies.iter()) {
+            encoder.copy_texture_to_texture(
+                source.texture.as_image_copy(),
+                ImageCopyTexture {
+                    origin: Origin3d {
+                        x: position.x,
+                        y: position.y,
+                        z: 0,
+                    },
+                    ..page.texture.as_image_copy()
+                },
+                source.texture.size(),
+            );
+        }
+        composited += 1;
+        false
+    });
+    if composited > 0 {
+        trace!("Composited {composited} atlas pages on the GPU");
+        queue.submit([encoder.finish()]);
+    }
+}
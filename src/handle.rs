@@ -0,0 +1,107 @@
+//! A typed handle to a texture atlas, with shortcuts for the handles and sprites it contains.
+
+use bevy::prelude::*;
+
+use super::{TextureAtlasAsset, TextureKey};
+
+/// A [`Handle`] to a [`TextureAtlasAsset`] that can be stored directly as a [`Component`] or [`Resource`], with
+/// shortcuts for looking up the atlas's textures without first fetching the asset.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_mod_atlas_loader::{handle::AtlasHandle, TextureAtlasAsset};
+/// fn setup(mut commands: Commands, assets: Res<AssetServer>) {
+///     commands.insert_resource(AtlasHandle::new(assets.load("hero.atlas.ron")));
+/// }
+///
+/// fn spawn_hero(
+///     mut commands: Commands,
+///     atlas: Res<AtlasHandle>,
+///     atlases: Res<Assets<TextureAtlasAsset>>,
+///     layouts: Res<Assets<TextureAtlasLayout>>,
+///     mut spawned: Local<bool>,
+/// ) {
+///     if *spawned || !atlas.is_ready(&atlases) {
+///         return;
+///     }
+///     if let Some(sprite) = atlas.sprite(&atlases, &layouts, "hero_idle") {
+///         commands.spawn(sprite);
+///     }
+///     *spawned = true;
+/// }
+/// ```
+#[derive(
+    Component, Resource, Reflect, Debug, Clone, Default, PartialEq, Eq, Hash, Deref, DerefMut,
+)]
+#[reflect(Component, Resource, Default)]
+pub struct AtlasHandle(pub Handle<TextureAtlasAsset>);
+
+impl AtlasHandle {
+    /// Wraps a handle to an atlas.
+    pub fn new(handle: Handle<TextureAtlasAsset>) -> Self {
+        Self(handle)
+    }
+
+    /// Returns the atlas, if it is loaded.
+    pub fn get<'a>(&self, atlases: &'a Assets<TextureAtlasAsset>) -> Option<&'a TextureAtlasAsset> {
+        atlases.get(&self.0)
+    }
+
+    /// Returns `true` if the atlas is loaded. An atlas's labeled sub-assets are added at the same time as the atlas
+    /// itself, so its layouts and textures are then available as well.
+    pub fn is_ready(&self, atlases: &Assets<TextureAtlasAsset>) -> bool {
+        atlases.contains(&self.0)
+    }
+
+    /// Returns the texture of the atlas's first page, if the atlas is loaded.
+    pub fn texture(&self, atlases: &Assets<TextureAtlasAsset>) -> Option<Handle<Image>> {
+        Some(self.get(atlases)?.texture.clone())
+    }
+
+    /// Returns the layout of the atlas's first page, if the atlas is loaded.
+    pub fn layout(
+        &self,
+        atlases: &Assets<TextureAtlasAsset>,
+    ) -> Option<Handle<TextureAtlasLayout>> {
+        Some(self.get(atlases)?.layout.clone())
+    }
+
+    /// Creates a [`TextureAtlas`] handle for the texture with the given `name`. Returns `None` if the atlas is not
+    /// loaded or the name is unknown.
+    pub fn texture_atlas(
+        &self,
+        atlases: &Assets<TextureAtlasAsset>,
+        name: &str,
+    ) -> Option<TextureAtlas> {
+        self.get(atlases)?.handle_from_name(name)
+    }
+
+    /// Creates a [`Sprite`] displaying the texture identified by `texture`, like [`TextureAtlasAsset::sprite`]. Returns
+    /// `None` if the atlas is not loaded or the texture is unknown.
+    pub fn sprite<'a>(
+        &self,
+        atlases: &Assets<TextureAtlasAsset>,
+        layouts: &Assets<TextureAtlasLayout>,
+        texture: impl Into<TextureKey<'a>>,
+    ) -> Option<Sprite> {
+        self.get(atlases)?.sprite(layouts, texture)
+    }
+}
+
+impl From<Handle<TextureAtlasAsset>> for AtlasHandle {
+    fn from(handle: Handle<TextureAtlasAsset>) -> Self {
+        Self(handle)
+    }
+}
+
+impl From<AtlasHandle> for AssetId<TextureAtlasAsset> {
+    fn from(handle: AtlasHandle) -> Self {
+        handle.id()
+    }
+}
+
+impl From<&AtlasHandle> for AssetId<TextureAtlasAsset> {
+    fn from(handle: &AtlasHandle) -> Self {
+        handle.id()
+    }
+}
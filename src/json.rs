@@ -0,0 +1,178 @@
+//! JSON sprite sheets in the "Hash" and "Array" formats, as written by TexturePacker and Aseprite.
+
+use std::fmt;
+
+use bevy::prelude::*;
+use serde::{
+    de::{MapAccess, SeqAccess, Visitor},
+    ser::SerializeMap,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use super::{TextureInfo, TextureTrim};
+
+/// The frames of a sheet in the order they were exported, as `(filename, frame)` pairs.
+///
+/// The "Hash" format stores frames in an object keyed by filename, while the "Array" format stores them in a list with
+/// a `filename` field.
+pub(crate) struct JsonFrames(pub Vec<(String, JsonFrame)>);
+
+impl<'de> Deserialize<'de> for JsonFrames {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FramesVisitor;
+
+        impl<'de> Visitor<'de> for FramesVisitor {
+            type Value = JsonFrames;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an object or array of frames")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut frames = Vec::with_capacity(map.size_hint().unwrap_or(0));
+                while let Some(entry) = map.next_entry()? {
+                    frames.push(entry);
+                }
+                Ok(JsonFrames(frames))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut frames = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                while let Some(NamedFrame { filename, frame }) = seq.next_element()? {
+                    frames.push((filename, frame));
+                }
+                Ok(JsonFrames(frames))
+            }
+        }
+
+        deserializer.deserialize_any(FramesVisitor)
+    }
+}
+
+/// Frames are always serialized in the "Hash" format.
+impl Serialize for JsonFrames {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (filename, frame) in &self.0 {
+            map.serialize_entry(filename, frame)?;
+        }
+        map.end()
+    }
+}
+
+/// An entry of an "Array" format sheet.
+#[derive(Deserialize)]
+struct NamedFrame {
+    filename: String,
+    #[serde(flatten)]
+    frame: JsonFrame,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct JsonFrame {
+    /// The area of the frame within the sheet image, with its unrotated size.
+    frame: JsonRect,
+    /// Whether the frame is rotated 90° clockwise within the sheet image.
+    #[serde(default)]
+    rotated: bool,
+    #[serde(default)]
+    trimmed: bool,
+    /// The area of the trimmed frame within the untrimmed sprite.
+    sprite_source_size: JsonRect,
+    /// The size of the untrimmed sprite.
+    source_size: JsonSize,
+    /// The pivot of the untrimmed sprite, where `(0, 0)` is its top left corner and `(1, 1)` is its bottom right.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pivot: Option<JsonPoint>,
+    /// How long the frame is displayed for, in milliseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration: Option<u64>,
+}
+
+impl JsonFrame {
+    /// Creates a frame covering `rect` within the sheet image, described by `info`.
+    pub fn new(rect: URect, info: &TextureInfo) -> Self {
+        let size = if info.rotated {
+            rect.size().yx()
+        } else {
+            rect.size()
+        };
+        let trim = info.trim.unwrap_or(TextureTrim {
+            offset: UVec2::ZERO,
+            source_size: size,
+        });
+        Self {
+            frame: JsonRect::new(rect.min, size),
+            rotated: info.rotated,
+            trimmed: info.trim.is_some(),
+            sprite_source_size: JsonRect::new(trim.offset, size),
+            source_size: JsonSize {
+                w: trim.source_size.x,
+                h: trim.source_size.y,
+            },
+            pivot: info.pivot.map(|pivot| JsonPoint {
+                x: pivot.x + 0.5,
+                y: 0.5 - pivot.y,
+            }),
+            duration: None,
+        }
+    }
+
+    /// Returns the area covered by the frame within the sheet image.
+    pub fn rect(&self) -> URect {
+        let JsonRect { x, y, w, h } = self.frame;
+        if self.rotated {
+            URect::new(x, y, x + h, y + w)
+        } else {
+            URect::new(x, y, x + w, y + h)
+        }
+    }
+
+    /// Returns the [`TextureInfo`] describing the frame's rotation, trim, and pivot.
+    pub fn info(&self) -> TextureInfo {
+        TextureInfo {
+            rotated: self.rotated,
+            trim: self.trimmed.then(|| TextureTrim {
+                offset: UVec2::new(self.sprite_source_size.x, self.sprite_source_size.y),
+                source_size: UVec2::new(self.source_size.w, self.source_size.h),
+            }),
+            pivot: self
+                .pivot
+                .as_ref()
+                .map(|pivot| Vec2::new(pivot.x - 0.5, 0.5 - pivot.y)),
+            ..default()
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+struct JsonRect {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+impl JsonRect {
+    fn new(position: UVec2, size: UVec2) -> Self {
+        Self {
+            x: position.x,
+            y: position.y,
+            w: size.x,
+            h: size.y,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct JsonSize {
+    pub w: u32,
+    pub h: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonPoint {
+    x: f32,
+    y: f32,
+}
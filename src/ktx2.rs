@@ -0,0 +1,163 @@
+//! Encoding of atlas images as KTX2 files containing Basis Universal UASTC textures.
+
+use std::io::Write;
+
+use basis_universal::{BasisTextureFormat, ColorSpace, Compressor, CompressorParams};
+use bevy::prelude::*;
+
+use super::loader::{Ktx2Settings, SaverError};
+
+/// The KTX2 file identifier.
+const IDENTIFIER: [u8; 12] = [
+    0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+];
+/// The `supercompressionScheme` of zlib compressed KTX2 files.
+const SUPERCOMPRESSION_ZLIB: u32 = 3;
+/// The size of a UASTC block in bytes.
+const BLOCK_BYTES: usize = 16;
+
+/// Encodes an image with the given RGBA8 `pixels` as a KTX2 file containing a single UASTC texture, which is zlib
+/// supercompressed if [`Ktx2Settings::supercompress`] is set.
+pub(crate) fn encode(
+    pixels: &[u8],
+    size: UVec2,
+    srgb: bool,
+    settings: &Ktx2Settings,
+) -> Result<Vec<u8>, SaverError> {
+    let mut params = CompressorParams::new();
+    params.set_basis_format(BasisTextureFormat::UASTC4x4);
+    params.set_rdo_uastc(settings.rdo);
+    params.set_color_space(if srgb {
+        ColorSpace::Srgb
+    } else {
+        ColorSpace::Linear
+    });
+    params.set_generate_mipmaps(false);
+    params.source_image_mut(0).init(pixels, size.x, size.y, 4);
+
+    trace!("Encoding {size} atlas image as UASTC");
+    let mut compressor = Compressor::new(1);
+    // SAFETY: The parameters only contain a single RGBA image which was initialized from a buffer of the right size
+    unsafe {
+        if !compressor.init(&params) {
+            return Err(SaverError::Ktx2("invalid encoder parameters".into()));
+        }
+        compressor
+            .process()
+            .map_err(|error| SaverError::Ktx2(format!("{error:?}")))?;
+    }
+    let blocks = uastc_blocks(compressor.basis_file(), size)
+        .ok_or_else(|| SaverError::Ktx2("the encoder did not produce a UASTC image".into()))?;
+
+    let level = if settings.supercompress {
+        let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::best());
+        encoder.write_all(blocks)?;
+        encoder.finish()?
+    } else {
+        blocks.to_vec()
+    };
+    Ok(write_ktx2(
+        size,
+        srgb,
+        settings.supercompress,
+        blocks.len(),
+        &level,
+    ))
+}
+
+/// Finds the UASTC blocks of the first image in a `.basis` file, which are stored without any further compression.
+fn uastc_blocks(basis: &[u8], size: UVec2) -> Option<&[u8]> {
+    let read = |offset: usize, bytes: usize| {
+        let bytes = basis.get(offset..offset + bytes)?;
+        Some(
+            bytes
+                .iter()
+                .rev()
+                .fold(0, |value, &byte| (value << 8) | byte as usize),
+        )
+    };
+    // The offsets of the `basis_file_header` and `basis_slice_desc` fields that are needed
+    let (total_slices, tex_format) = (read(14, 3)?, read(20, 1)?);
+    let slice = read(65, 4)?;
+    if total_slices == 0 || tex_format != 1 {
+        return None;
+    }
+    let (blocks_x, blocks_y) = (read(slice + 9, 2)?, read(slice + 11, 2)?);
+    let (offset, length) = (read(slice + 13, 4)?, read(slice + 17, 4)?);
+    (blocks_x == size.x.div_ceil(4) as usize
+        && blocks_y == size.y.div_ceil(4) as usize
+        && length == blocks_x * blocks_y * BLOCK_BYTES)
+        .then(|| basis.get(offset..offset + length))
+        .flatten()
+}
+
+/// Writes a KTX2 file containing a single mip level of UASTC data.
+fn write_ktx2(
+    size: UVec2,
+    srgb: bool,
+    supercompressed: bool,
+    uncompressed_length: usize,
+    level: &[u8],
+) -> Vec<u8> {
+    // The data format descriptor has a single basic descriptor block with one sample covering the whole block
+    let mut dfd = Vec::with_capacity(44);
+    dfd.extend(44u32.to_le_bytes());
+    dfd.extend(0u32.to_le_bytes()); // Khronos vendor, basic descriptor type
+    dfd.extend(2u16.to_le_bytes()); // Version 1.3
+    dfd.extend(40u16.to_le_bytes()); // Descriptor block size
+    dfd.extend([
+        166,                      // KHR_DF_MODEL_UASTC
+        1,                        // KHR_DF_PRIMARIES_BT709
+        if srgb { 2 } else { 1 }, // KHR_DF_TRANSFER_SRGB or KHR_DF_TRANSFER_LINEAR
+        0,                        // Straight alpha
+    ]);
+    dfd.extend([3, 3, 0, 0]); // 4x4 texel blocks
+    dfd.extend([BLOCK_BYTES as u8, 0, 0, 0, 0, 0, 0, 0]); // Bytes in each plane
+    dfd.extend(0u16.to_le_bytes()); // Bit offset
+    dfd.extend([127, 3]); // Bit length minus one, KHR_DF_CHANNEL_UASTC_RGBA
+    dfd.extend([0; 4]); // Sample position
+    dfd.extend(0u32.to_le_bytes()); // Sample lower
+    dfd.extend(u32::MAX.to_le_bytes()); // Sample upper
+
+    const HEADER_LENGTH: usize = 80;
+    const LEVEL_INDEX_LENGTH: usize = 24;
+    let dfd_offset = HEADER_LENGTH + LEVEL_INDEX_LENGTH;
+    // Uncompressed levels are aligned to the size of a block
+    let level_offset = match supercompressed {
+        true => dfd_offset + dfd.len(),
+        false => (dfd_offset + dfd.len()).next_multiple_of(BLOCK_BYTES),
+    };
+
+    let mut file = Vec::with_capacity(level_offset + level.len());
+    file.extend(IDENTIFIER);
+    for value in [
+        0, // VK_FORMAT_UNDEFINED
+        1, // Type size
+        size.x,
+        size.y,
+        0, // Depth
+        0, // Layer count
+        1, // Face count
+        1, // Level count
+        if supercompressed {
+            SUPERCOMPRESSION_ZLIB
+        } else {
+            0
+        },
+        dfd_offset as u32,
+        dfd.len() as u32,
+        0, // Key/value data offset
+        0, // Key/value data length
+    ] {
+        file.extend(value.to_le_bytes());
+    }
+    file.extend(0u64.to_le_bytes()); // Supercompression global data offset
+    file.extend(0u64.to_le_bytes()); // Supercompression global data length
+    for value in [level_offset, level.len(), uncompressed_length] {
+        file.extend((value as u64).to_le_bytes());
+    }
+    file.extend(dfd);
+    file.resize(level_offset, 0);
+    file.extend(level);
+    file
+}
@@ -0,0 +1,226 @@
+//! Loading of the tileset definitions in [LDtk](https://ldtk.io/) projects as texture atlases.
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext, LoadDirectError, ParseAssetPathError},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{TextureAtlasAsset, TextureAtlasPaths, TextureInfo};
+
+/// Errors encountered by [`LdtkLoader`].
+#[derive(Debug, Error)]
+pub enum LdtkLoaderError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    ParseAssetPath(#[from] ParseAssetPathError),
+    #[error(transparent)]
+    LoadDirect(#[from] LoadDirectError),
+    #[error("The project has no tileset named \"{0}\"")]
+    UnknownTileset(String),
+    #[error("The project has no tilesets")]
+    NoTilesets,
+    #[error("The tileset \"{0}\" has no image")]
+    MissingImage(String),
+}
+
+/// Settings for [`LdtkLoader`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LdtkLoaderSettings {
+    /// The identifier of the tileset to load. If `None`, the project's first tileset is loaded.
+    pub tileset: Option<String>,
+}
+
+/// An [`AssetLoader`] that loads a [`TextureAtlasAsset`] from one of the tileset definitions of an LDtk `.ldtk`
+/// project. The tileset is chosen by [`LdtkLoaderSettings::tileset`], and its image is loaded from a path relative to
+/// the project file.
+///
+/// Each tile is named after its tile ID in decimal. A tile's custom data and enum tags are stored in its
+/// [`TextureInfo::meta`] as a map with a `data` entry holding its custom data, parsed as JSON if possible, and a
+/// `tags` entry listing the values of its enum tags. Either entry is omitted if the tile has none.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_mod_atlas_loader::{ldtk::LdtkLoaderSettings, TextureAtlasAsset};
+/// # fn load(asset_server: Res<AssetServer>) {
+/// let tiles: Handle<TextureAtlasAsset> = asset_server.load_with_settings(
+///     "world.ldtk",
+///     |settings: &mut LdtkLoaderSettings| settings.tileset = Some("Dungeon".into()),
+/// );
+/// # }
+/// ```
+///
+/// Other plugins may also load `.ldtk` files, in which case the [`TextureAtlasAsset`] type must be given when the
+/// project is loaded so that this loader is chosen.
+///
+/// [`TextureInfo::meta`]: crate::TextureInfo::meta
+pub struct LdtkLoader;
+
+impl AssetLoader for LdtkLoader {
+    type Asset = TextureAtlasAsset;
+    type Settings = LdtkLoaderSettings;
+    type Error = LdtkLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        debug!("Loading LDtk tileset from {:?}", load_context.path());
+        let project = {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            serde_json::from_slice::<LdtkProject>(&bytes)?
+        };
+
+        let mut tilesets = project.defs.tilesets.into_iter();
+        let tileset = match settings.tileset.as_ref() {
+            Some(name) => tilesets
+                .find(|tileset| tileset.identifier == *name)
+                .ok_or_else(|| LdtkLoaderError::UnknownTileset(name.clone()))?,
+            None => tilesets.next().ok_or(LdtkLoaderError::NoTilesets)?,
+        };
+        // Tilesets using LDtk's built-in icons have no image of their own
+        let Some(image) = tileset.rel_path.as_ref() else {
+            return Err(LdtkLoaderError::MissingImage(tileset.identifier));
+        };
+
+        let image_path = load_context.asset_path().resolve_embed(image)?;
+        trace!("Loading LDtk tileset image from: {image_path}");
+        let texture = load_context
+            .loader()
+            .immediate()
+            .load::<Image>(image_path)
+            .await?;
+
+        let mut layout = TextureAtlasLayout::from_grid(
+            UVec2::splat(tileset.tile_grid_size),
+            tileset.columns,
+            tileset.rows,
+            Some(UVec2::splat(tileset.spacing)),
+            Some(UVec2::splat(tileset.padding)),
+        );
+        layout.size = texture.get().size();
+
+        let mut paths = TextureAtlasPaths::default();
+        for id in 0..layout.textures.len() {
+            let info = TextureInfo {
+                meta: tileset.meta(id),
+                ..default()
+            };
+            paths.add(None, Some(id.to_string()), info);
+        }
+
+        debug!(
+            "Loaded LDtk tileset \"{}\" containing {} tiles",
+            tileset.identifier,
+            paths.path_indices.len(),
+        );
+        Ok(TextureAtlasAsset::from_pages(
+            load_context,
+            [(layout, texture)],
+            paths,
+        ))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ldtk"]
+    }
+}
+
+/// The parts of an LDtk project that describe its tilesets.
+#[derive(Deserialize)]
+struct LdtkProject {
+    defs: LdtkDefinitions,
+}
+
+#[derive(Deserialize)]
+struct LdtkDefinitions {
+    tilesets: Vec<LdtkTileset>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LdtkTileset {
+    identifier: String,
+    /// The path of the tileset image, relative to the project file.
+    rel_path: Option<String>,
+    tile_grid_size: u32,
+    /// The space between adjacent tiles in the image.
+    spacing: u32,
+    /// The space between the tiles and the edges of the image.
+    padding: u32,
+    #[serde(rename = "__cWid")]
+    columns: u32,
+    #[serde(rename = "__cHei")]
+    rows: u32,
+    #[serde(default)]
+    custom_data: Vec<LdtkCustomData>,
+    #[serde(default)]
+    enum_tags: Vec<LdtkEnumTag>,
+}
+
+impl LdtkTileset {
+    /// Returns the metadata of the tile with the given ID, if it has any custom data or enum tags.
+    fn meta(&self, id: usize) -> Option<ron::Value> {
+        let mut meta = ron::Map::new();
+        if let Some(data) = self.custom_data.iter().find(|data| data.tile_id == id) {
+            let value = match serde_json::from_str(&data.data) {
+                Ok(value) => json_to_ron(value),
+                Err(_) => ron::Value::String(data.data.clone()),
+            };
+            meta.insert(ron::Value::String("data".into()), value);
+        }
+        let tags: Vec<ron::Value> = self
+            .enum_tags
+            .iter()
+            .filter(|tag| tag.tile_ids.contains(&id))
+            .map(|tag| ron::Value::String(tag.enum_value_id.clone()))
+            .collect();
+        if !tags.is_empty() {
+            meta.insert(ron::Value::String("tags".into()), ron::Value::Seq(tags));
+        }
+        (!meta.is_empty()).then_some(ron::Value::Map(meta))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LdtkCustomData {
+    tile_id: usize,
+    data: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LdtkEnumTag {
+    enum_value_id: String,
+    tile_ids: Vec<usize>,
+}
+
+/// Converts a JSON value to the equivalent RON value.
+fn json_to_ron(value: serde_json::Value) -> ron::Value {
+    match value {
+        serde_json::Value::Null => ron::Value::Option(None),
+        serde_json::Value::Bool(value) => ron::Value::Bool(value),
+        serde_json::Value::Number(number) => match number.as_i64() {
+            Some(integer) => ron::Value::Number(ron::Number::Integer(integer)),
+            None => ron::Value::Number(ron::Number::new(number.as_f64().unwrap_or_default())),
+        },
+        serde_json::Value::String(value) => ron::Value::String(value),
+        serde_json::Value::Array(values) => {
+            ron::Value::Seq(values.into_iter().map(json_to_ron).collect())
+        }
+        serde_json::Value::Object(entries) => ron::Value::Map(
+            entries
+                .into_iter()
+                .map(|(key, value)| (ron::Value::String(key), json_to_ron(value)))
+                .collect(),
+        ),
+    }
+}
@@ -1,15 +1,66 @@
 #![doc = include_str!("../README.md")]
 
 use bevy::{
-    asset::{processor::LoadTransformAndSave, transformer::IdentityAssetTransformer, AssetPath},
+    asset::{
+        processor::LoadTransformAndSave, transformer::IdentityAssetTransformer, AssetPath,
+        LoadContext, LoadedAsset,
+    },
     prelude::*,
+    sprite::{Anchor, BorderRect, SpriteImageMode, TextureSlicer},
     utils::HashMap,
 };
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
+pub mod animation;
+mod archive;
+pub mod aseprite;
+pub mod atlas_png;
+pub mod atlasbin;
+pub mod bmfont;
+pub mod builder;
+mod cache;
+pub mod cocos;
+#[cfg(feature = "dds")]
+mod dds;
+pub mod dynamic;
+pub mod events;
+#[cfg(feature = "font")]
+mod font;
+mod glob;
+mod gpu;
+pub mod handle;
+mod json;
+#[cfg(feature = "ktx2")]
+mod ktx2;
+pub mod ldtk;
+pub mod libgdx;
 pub mod loader;
+mod packer;
+pub mod reload;
+mod sdf;
+pub mod sparrow;
+pub mod sprite;
+#[cfg(feature = "svg")]
+pub mod svg;
+pub mod texture_packer;
+pub mod tiled;
+#[cfg(feature = "ui")]
+mod ui;
 
-use loader::{TextureAtlasBuildLoader, TextureAtlasLoader, TextureAtlasSaver};
+use animation::{animation_label, AtlasAnimation};
+use aseprite::AsepriteLoader;
+use atlas_png::AtlasPngLoader;
+use atlasbin::{AtlasBinLoader, AtlasBinSaver};
+use bmfont::BmFontLoader;
+use cocos::CocosLoader;
+use events::AtlasEvent;
+use ldtk::LdtkLoader;
+use libgdx::LibGdxLoader;
+use loader::{ErrorPolicy, TextureAtlasBuildLoader, TextureAtlasLoader, TextureAtlasSaver};
+use reload::{AtlasRemapped, AtlasSnapshots};
+use sparrow::SparrowLoader;
+use texture_packer::TexturePackerLoader;
+use tiled::TiledLoader;
 
 /// An asset processor that loads individual tile images, builds a texture atlas, then saves the compiled texture
 /// atlas.
@@ -19,58 +70,475 @@ type TextureAtlasBuildProcessor = LoadTransformAndSave<
     TextureAtlasSaver,
 >;
 
+/// An asset processor that builds a texture atlas like [`TextureAtlasBuildProcessor`], but saves it as a single
+/// `.atlasbin` file.
+type AtlasBinBuildProcessor = LoadTransformAndSave<
+    TextureAtlasBuildLoader,
+    IdentityAssetTransformer<TextureAtlasAsset>,
+    AtlasBinSaver,
+>;
+
 /// Provides a [`TextureAtlasAsset`] and associated asset loaders/savers.
 ///
 /// See the [crate-level](self) documentation for more information.
-pub struct AtlasLoaderPlugin;
+#[derive(Default)]
+pub struct AtlasLoaderPlugin {
+    /// How atlas build configs that don't set their own `on_error` handle textures that can't be packed. Defaults to
+    /// [`ErrorPolicy::Fail`].
+    pub on_error: ErrorPolicy,
+    /// Whether to update the [`TextureAtlas`] of each [`Sprite`] when its atlas is rebuilt and its texture moves, such
+    /// as when the atlas is hot reloaded. Textures are matched by name, or by path if they have no name. Defaults to
+    /// `false`.
+    pub patch_sprites: bool,
+    /// The most sub-texture images loaded at once by atlas build configs that don't set their own
+    /// `max_concurrent_loads`. Defaults to [`loader::DEFAULT_MAX_CONCURRENT_LOADS`].
+    pub max_concurrent_loads: Option<usize>,
+}
 
 impl Plugin for AtlasLoaderPlugin {
     fn build(&self, app: &mut App) {
+        let gpu_compositions = gpu::setup(app);
+        let cache = cache::BuildCache::default();
+        let build_loader = TextureAtlasBuildLoader::from_world(app.world_mut())
+            .with_on_error(self.on_error)
+            .with_max_concurrent_loads(
+                self.max_concurrent_loads
+                    .unwrap_or(loader::DEFAULT_MAX_CONCURRENT_LOADS),
+            )
+            .with_gpu_compositions(gpu_compositions)
+            .with_cache(cache.clone());
         app.init_asset::<TextureAtlasAsset>()
+            .init_asset::<AtlasAnimation>()
+            .init_asset::<AtlasRegion>()
+            .register_asset_reflect::<TextureAtlasAsset>()
+            .register_asset_reflect::<AtlasAnimation>()
+            .register_asset_reflect::<AtlasRegion>()
+            .register_type::<handle::AtlasHandle>()
+            .register_type::<sprite::AtlasSprite>()
+            .register_type::<sprite::AtlasSpriteRef>()
+            .register_type::<animation::AtlasAnimationPlayer>()
+            .register_type::<loader::LoaderSettings>()
             .register_asset_loader(TextureAtlasLoader)
-            .register_asset_loader(TextureAtlasBuildLoader)
+            .register_asset_loader(AsepriteLoader)
+            .register_asset_loader(TexturePackerLoader)
+            .register_asset_loader(LibGdxLoader)
+            .register_asset_loader(SparrowLoader)
+            .register_asset_loader(CocosLoader)
+            .register_asset_loader(BmFontLoader)
+            .register_asset_loader(TiledLoader)
+            .register_asset_loader(LdtkLoader)
+            .register_asset_loader(AtlasPngLoader)
+            .register_asset_loader(AtlasBinLoader)
+            .register_asset_loader(build_loader)
             .register_asset_processor(TextureAtlasBuildProcessor::new(
                 IdentityAssetTransformer::new(),
-                TextureAtlasSaver,
+                TextureAtlasSaver::with_cache(cache),
+            ))
+            .register_asset_processor(AtlasBinBuildProcessor::new(
+                IdentityAssetTransformer::new(),
+                AtlasBinSaver,
             ))
-            .set_default_asset_processor::<TextureAtlasBuildProcessor>("atlas.ron");
+            .set_default_asset_processor::<TextureAtlasBuildProcessor>("atlas.ron")
+            .set_default_asset_processor::<TextureAtlasBuildProcessor>("atlas.json")
+            .set_default_asset_processor::<TextureAtlasBuildProcessor>("atlas.toml")
+            .set_default_asset_processor::<TextureAtlasBuildProcessor>("atlas.zip")
+            .add_event::<AtlasEvent>()
+            .add_event::<AtlasRemapped>()
+            .init_resource::<AtlasSnapshots>()
+            .add_systems(
+                PreUpdate,
+                (events::send_atlas_events, reload::remap_atlases),
+            )
+            .add_systems(Update, animation::play_animations)
+            .add_systems(
+                PostUpdate,
+                (
+                    sprite::load_atlas_sprite_refs,
+                    sprite::resolve_atlas_sprites,
+                )
+                    .chain(),
+            );
+        if self.patch_sprites {
+            app.add_systems(
+                PreUpdate,
+                reload::patch_sprites.after(reload::remap_atlases),
+            );
+        }
+
+        #[cfg(feature = "aseprite")]
+        app.register_asset_loader(aseprite::AsepriteFileLoader);
     }
 }
 
-/// Stores a mapping from sub texture paths to the related area index.
+/// Stores a mapping from sub texture paths and names to the related area index.
+///
+/// Textures are stored page by page, so that the textures on the first page of an atlas have the same indices here as
+/// in its [`TextureAtlasLayout`]. Textures on later pages are indexed within their own page's layout, and should be
+/// looked up by [`AtlasLocation`].
 ///
 /// Generated by [`TextureAtlasBuildLoader`].
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Reflect)]
 pub struct TextureAtlasPaths {
     texture_ids: HashMap<AssetPath<'static>, usize>,
     path_indices: Vec<Option<AssetPath<'static>>>,
+    #[serde(default)]
+    name_ids: HashMap<String, usize>,
+    #[serde(default)]
+    name_indices: Vec<Option<String>>,
+    #[serde(default)]
+    infos: Vec<TextureInfo>,
+    /// The index of the first texture of each page after the first.
+    #[serde(default)]
+    page_starts: Vec<usize>,
+}
+
+/// The location of a texture within a (possibly multi-page) atlas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Reflect)]
+pub struct AtlasLocation {
+    /// The index of the page containing the texture.
+    pub page: usize,
+    /// The index of the texture within its page's [`TextureAtlasLayout`].
+    pub index: usize,
+}
+
+/// Identifies a texture within an atlas by its name, its index on the first page, its [`AtlasLocation`], or the path
+/// it was loaded from. Converted from `&str`, `usize`, [`AtlasLocation`], and [`AssetPath`] respectively.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TextureKey<'a> {
+    /// The texture's name.
+    Name(&'a str),
+    /// The texture's index within the layout of the first page.
+    Index(usize),
+    /// The texture's page and index within that page's layout.
+    Location(AtlasLocation),
+    /// The path that the texture was loaded from.
+    Path(AssetPath<'a>),
+}
+
+impl<'a> From<&'a str> for TextureKey<'a> {
+    fn from(name: &'a str) -> Self {
+        Self::Name(name)
+    }
+}
+
+impl<'a> From<&'a String> for TextureKey<'a> {
+    fn from(name: &'a String) -> Self {
+        Self::Name(name)
+    }
+}
+
+impl From<usize> for TextureKey<'_> {
+    fn from(index: usize) -> Self {
+        Self::Index(index)
+    }
+}
+
+impl From<AtlasLocation> for TextureKey<'_> {
+    fn from(location: AtlasLocation) -> Self {
+        Self::Location(location)
+    }
+}
+
+impl<'a> From<AssetPath<'a>> for TextureKey<'a> {
+    fn from(path: AssetPath<'a>) -> Self {
+        Self::Path(path)
+    }
+}
+
+/// Additional metadata about how a texture was packed into an atlas.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Reflect)]
+pub struct TextureInfo {
+    /// Whether the texture was rotated 90° clockwise when it was packed. The texture's rect covers the rotated
+    /// texture, so its width and height are swapped relative to the original image. See [`TextureInfo::rotation`].
+    #[serde(default)]
+    pub rotated: bool,
+    /// How the texture was trimmed before it was packed, if its transparent borders were removed.
+    #[serde(default)]
+    pub trim: Option<TextureTrim>,
+    /// The texture's pivot point relative to the original image, using the same coordinates as [`Anchor::Custom`].
+    /// See [`TextureInfo::anchor`].
+    #[serde(default)]
+    pub pivot: Option<Vec2>,
+    /// The nine-slice borders of the original image, if any. See [`TextureInfo::slicer`].
+    #[serde(default)]
+    pub slices: Option<SliceBorder>,
+    /// Arbitrary user data attached to the texture in its `.atlas.ron` entry, or by the loader that loaded it. See
+    /// [`TextureInfo::meta_as`].
+    #[serde(default)]
+    #[reflect(ignore)]
+    pub meta: Option<ron::Value>,
+}
+
+/// The widths of the borders of a nine-slice texture, in pixels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+pub struct SliceBorder {
+    #[serde(default)]
+    pub left: u32,
+    #[serde(default)]
+    pub right: u32,
+    #[serde(default)]
+    pub top: u32,
+    #[serde(default)]
+    pub bottom: u32,
+}
+
+impl From<SliceBorder> for BorderRect {
+    fn from(border: SliceBorder) -> Self {
+        Self {
+            left: border.left as f32,
+            right: border.right as f32,
+            top: border.top as f32,
+            bottom: border.bottom as f32,
+        }
+    }
+}
+
+/// Describes how a texture's transparent borders were cropped before it was packed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+pub struct TextureTrim {
+    /// The position of the top left corner of the trimmed texture within the original image.
+    pub offset: UVec2,
+    /// The size of the original image before it was trimmed.
+    pub source_size: UVec2,
+}
+
+impl TextureInfo {
+    /// Returns the rotation that displays this texture upright, e.g. when applied to the [`Transform`] of a sprite
+    /// using it.
+    pub fn rotation(&self) -> Quat {
+        match self.rotated {
+            true => Quat::from_rotation_z(std::f32::consts::FRAC_PI_2),
+            false => Quat::IDENTITY,
+        }
+    }
+
+    /// Returns the size of the original image, before it was trimmed or rotated. `rect` must be the texture's rect in
+    /// its atlas layout.
+    pub fn source_size(&self, rect: URect) -> UVec2 {
+        match self.trim {
+            Some(trim) => trim.source_size,
+            None => self.unrotated_size(rect),
+        }
+    }
+
+    /// Returns the translation from the center of the original image to the center of the trimmed texture, with `y`
+    /// pointing up. Offsetting a sprite using this texture by this amount places it as if it was never trimmed.
+    /// `rect` must be the texture's rect in its atlas layout.
+    pub fn trim_offset(&self, rect: URect) -> Vec2 {
+        let Some(trim) = self.trim else {
+            return Vec2::ZERO;
+        };
+        let center = trim.offset.as_vec2() + self.unrotated_size(rect).as_vec2() / 2.0;
+        let offset = center - trim.source_size.as_vec2() / 2.0;
+        Vec2::new(offset.x, -offset.y)
+    }
+
+    /// Returns the [`Anchor`] that places this texture's pivot (or the center of the original image, if it has no
+    /// pivot) at a sprite's [`Transform`]. This accounts for trimming and rotation, so that once the sprite is
+    /// rotated by [`TextureInfo::rotation`] it is positioned exactly like the original image would be. `rect` must be
+    /// the texture's rect in its atlas layout.
+    pub fn anchor(&self, rect: URect) -> Anchor {
+        if self.trim.is_none() && self.pivot.is_none_or(|pivot| pivot == Vec2::ZERO) {
+            return Anchor::Center;
+        }
+        let size = self.unrotated_size(rect).as_vec2().max(Vec2::ONE);
+        let trim = self.trim.unwrap_or(TextureTrim {
+            offset: UVec2::ZERO,
+            source_size: size.as_uvec2(),
+        });
+
+        // Find the pivot within the packed texture, in normalized coordinates from its top left corner
+        let pivot = self.pivot.unwrap_or_default();
+        let source_pixel = (Vec2::new(pivot.x, -pivot.y) + 0.5) * trim.source_size.as_vec2();
+        let point = (source_pixel - trim.offset.as_vec2()) / size;
+        let point = if self.rotated {
+            Vec2::new(1.0 - point.y, point.x)
+        } else {
+            point
+        };
+        Anchor::Custom(Vec2::new(point.x - 0.5, 0.5 - point.y))
+    }
+
+    /// Returns the nine-slice borders of the packed texture, if it has any. Unlike [`TextureInfo::slices`], these are
+    /// adjusted for any trimming and rotation so that they match `rect`, which must be the texture's rect in its atlas
+    /// layout.
+    pub fn slice_border(&self, rect: URect) -> Option<SliceBorder> {
+        let mut border = self.slices?;
+        if let Some(trim) = self.trim {
+            let far = trim.source_size - trim.offset - self.unrotated_size(rect);
+            border = SliceBorder {
+                left: border.left.saturating_sub(trim.offset.x),
+                right: border.right.saturating_sub(far.x),
+                top: border.top.saturating_sub(trim.offset.y),
+                bottom: border.bottom.saturating_sub(far.y),
+            };
+        }
+        if self.rotated {
+            // Rotating clockwise moves each border to the next side clockwise
+            border = SliceBorder {
+                left: border.bottom,
+                right: border.top,
+                top: border.left,
+                bottom: border.right,
+            };
+        }
+        Some(border)
+    }
+
+    /// Creates a [`TextureSlicer`] for this texture's nine-slice borders (see [`TextureInfo::slice_border`]), for use
+    /// with [`SpriteImageMode::Sliced`] or `NodeImageMode::Sliced` in UI. Returns `None` if the texture has no
+    /// borders. `rect` must be the texture's rect in its atlas layout.
+    pub fn slicer(&self, rect: URect) -> Option<TextureSlicer> {
+        Some(TextureSlicer {
+            border: self.slice_border(rect)?.into(),
+            ..default()
+        })
+    }
+
+    /// Deserializes this texture's [`meta`](TextureInfo::meta) value into `T`. Returns `None` if the texture has no
+    /// metadata.
+    ///
+    /// The metadata is stored as an untyped [`ron::Value`], so optional fields of `T` must be written as `Some(...)`,
+    /// and enum variant names are not preserved.
+    pub fn meta_as<T: DeserializeOwned>(&self) -> Option<Result<T, ron::Error>> {
+        self.meta.clone().map(ron::Value::into_rust)
+    }
+
+    /// Returns the size of the texture as it was before it was rotated.
+    fn unrotated_size(&self, rect: URect) -> UVec2 {
+        if self.rotated {
+            rect.size().yx()
+        } else {
+            rect.size()
+        }
+    }
 }
 
 impl TextureAtlasPaths {
-    /// Adds a texture with the given path. If the given path is `None`, no path will be associated with this index.
-    fn add(&mut self, path: Option<AssetPath<'static>>) {
+    /// Adds a texture with the given path, name, and info. If either the path or name is `None`, no path or name
+    /// (respectively) will be associated with this index.
+    fn add(&mut self, path: Option<AssetPath<'static>>, name: Option<String>, info: TextureInfo) {
+        let index = self.path_indices.len();
         if let Some(asset_path) = path.as_ref() {
-            self.texture_ids
-                .insert(asset_path.clone(), self.path_indices.len());
+            self.texture_ids.insert(asset_path.clone(), index);
+        }
+        if let Some(name) = name.as_ref() {
+            self.name_ids.insert(name.clone(), index);
         }
         self.path_indices.push(path);
+        self.name_indices.push(name);
+        self.infos.push(info);
+    }
+
+    /// Begins a new page. Textures added after this are placed on the new page.
+    fn start_page(&mut self) {
+        self.page_starts.push(self.path_indices.len());
+    }
+
+    /// Returns the number of pages in the atlas.
+    pub fn page_count(&self) -> usize {
+        self.page_starts.len() + 1
+    }
+
+    /// Returns the index of the first texture on the given page.
+    fn page_start(&self, page: usize) -> usize {
+        match page {
+            0 => 0,
+            page => self.page_starts[page - 1],
+        }
+    }
+
+    /// Converts an index into the full list of textures to its [`AtlasLocation`].
+    fn location(&self, entry: usize) -> AtlasLocation {
+        let page = self.page_starts.partition_point(|&start| start <= entry);
+        AtlasLocation {
+            page,
+            index: entry - self.page_start(page),
+        }
     }
 
-    /// Retrieves the texture *section* index of the given `texture` path.
+    /// Converts an [`AtlasLocation`] to an index into the full list of textures, if it exists.
+    fn entry(&self, location: AtlasLocation) -> Option<usize> {
+        if location.page >= self.page_count() {
+            return None;
+        }
+        let entry = self.page_start(location.page) + location.index;
+        let page_end = self
+            .page_starts
+            .get(location.page)
+            .copied()
+            .unwrap_or(self.path_indices.len());
+        (entry < page_end).then_some(entry)
+    }
+
+    /// Retrieves the texture *section* index of the given `texture` path, within the layout of its page.
     pub fn texture_index<'a>(&self, texture: impl Into<AssetPath<'a>>) -> Option<usize> {
-        self.texture_ids.get(&texture.into()).copied()
+        self.texture_location(texture)
+            .map(|location| location.index)
+    }
+
+    /// Retrieves the [`AtlasLocation`] of the given `texture` path.
+    pub fn texture_location<'a>(&self, texture: impl Into<AssetPath<'a>>) -> Option<AtlasLocation> {
+        self.texture_ids
+            .get(&texture.into())
+            .map(|&entry| self.location(entry))
     }
 
-    /// Returns the [`AssetPath`] associated with the given index, if any.
+    /// Returns the [`AssetPath`] associated with the given index on the first page, if any.
     pub fn texture_path(&self, index: usize) -> Option<&AssetPath<'static>> {
-        if index < self.path_indices.len() {
-            self.path_indices[index].as_ref()
-        } else {
-            None
+        self.path_at(AtlasLocation { page: 0, index })
+    }
+
+    /// Returns the [`AssetPath`] associated with the given [`AtlasLocation`], if any.
+    pub fn path_at(&self, location: AtlasLocation) -> Option<&AssetPath<'static>> {
+        self.path_indices[self.entry(location)?].as_ref()
+    }
+
+    /// Retrieves the texture *section* index of the texture with the given `name`, within the layout of its page.
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.location_of(name).map(|location| location.index)
+    }
+
+    /// Retrieves the [`AtlasLocation`] of the texture with the given `name`.
+    pub fn location_of(&self, name: &str) -> Option<AtlasLocation> {
+        self.name_ids.get(name).map(|&entry| self.location(entry))
+    }
+
+    /// Returns the name associated with the given index on the first page, if any.
+    pub fn texture_name(&self, index: usize) -> Option<&str> {
+        self.name_at(AtlasLocation { page: 0, index })
+    }
+
+    /// Returns the name associated with the given [`AtlasLocation`], if any.
+    pub fn name_at(&self, location: AtlasLocation) -> Option<&str> {
+        self.name_indices[self.entry(location)?].as_deref()
+    }
+
+    /// Retrieves the [`AtlasLocation`] of the texture identified by `key`, if it exists.
+    pub fn location_of_key<'a>(&self, key: impl Into<TextureKey<'a>>) -> Option<AtlasLocation> {
+        match key.into() {
+            TextureKey::Name(name) => self.location_of(name),
+            TextureKey::Index(index) => {
+                let location = AtlasLocation { page: 0, index };
+                self.entry(location).map(|_| location)
+            }
+            TextureKey::Location(location) => self.entry(location).map(|_| location),
+            TextureKey::Path(path) => self.texture_location(path),
         }
     }
 
-    /// Creates a [`TextureAtlas`] handle for the given `texture` path.
+    /// Returns the [`TextureInfo`] of the texture at the given [`AtlasLocation`], if it exists.
+    pub fn info_at(&self, location: AtlasLocation) -> Option<&TextureInfo> {
+        self.infos.get(self.entry(location)?)
+    }
+
+    /// Returns the [`TextureInfo`] of the texture with the given `name`.
+    pub fn info_of(&self, name: &str) -> Option<&TextureInfo> {
+        self.info_at(self.location_of(name)?)
+    }
+
+    /// Creates a [`TextureAtlas`] handle for the given `texture` path. The `layout` must be the layout of the page
+    /// that contains the texture.
     pub fn handle<'a>(
         &self,
         layout: Handle<TextureAtlasLayout>,
@@ -80,7 +548,8 @@ impl TextureAtlasPaths {
             .map(|index| TextureAtlas { layout, index })
     }
 
-    /// Retrieves the texture *section* rectangle of the given `texture` path.
+    /// Retrieves the texture *section* rectangle of the given `texture` path. The `layout` must be the layout of the
+    /// page that contains the texture.
     pub fn texture_rect<'a>(
         &self,
         layout: &TextureAtlasLayout,
@@ -89,6 +558,188 @@ impl TextureAtlasPaths {
         self.texture_index(texture.into())
             .map(|index| layout.textures[index])
     }
+
+    /// Retrieves the texture *section* rectangle of the texture with the given `name`. The `layout` must be the
+    /// layout of the page that contains the texture.
+    pub fn rect_of(&self, layout: &TextureAtlasLayout, name: &str) -> Option<URect> {
+        self.index_of(name)
+            .and_then(|index| layout.textures.get(index).copied())
+    }
+}
+
+/// Describes what each layer of an atlas's texture array contains, for atlases that are built as a 2D array texture
+/// rather than a single image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Reflect)]
+pub enum ArrayLayers {
+    /// Each texture is stored in the top left corner of its own layer. The atlas has a single page, and each texture's
+    /// index is also its layer.
+    Textures,
+    /// Each page of the atlas is stored in its own layer, and a texture's page is also its layer.
+    Pages,
+}
+
+/// How the textures of an atlas built with `distance_field` were converted into distance fields, which shaders can
+/// draw with crisp edges at any scale.
+///
+/// Each channel stores the signed distance from the nearest edge of the texture's shape, where `0.5` is on the edge
+/// and larger values are inside it, scaled so that values saturate at `range / 2` pixels from the edge. Each texture
+/// is grown by [`DistanceField::padding`] pixels on every side to make room for the distances outside of its shape.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Reflect)]
+#[serde(default)]
+pub struct DistanceField {
+    /// The range of distances in pixels that the values of each channel span, from `0` to `1`. Defaults to `4`.
+    pub range: f32,
+    /// Whether the red, green, and blue channels store a multi-channel distance field, whose median is the distance
+    /// and keeps the sharp corners of glyphs, while alpha stores the true distance. Only glyphs rasterized from a
+    /// `font` have separate channels, and every other texture stores the same distance in each channel.
+    pub multi_channel: bool,
+}
+
+impl Default for DistanceField {
+    fn default() -> Self {
+        Self {
+            range: 4.0,
+            multi_channel: false,
+        }
+    }
+}
+
+impl DistanceField {
+    /// Returns the number of pixels that each texture was grown by on every side, which is half of the `range`
+    /// rounded up.
+    pub fn padding(&self) -> u32 {
+        (self.range / 2.0).ceil() as u32
+    }
+}
+
+/// Which labeled sub-asset each named texture of an atlas is added as, if any. See [`TextureAtlasAsset`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Reflect)]
+pub enum SpriteAssets {
+    /// Each named texture is added as an [`AtlasRegion`] referencing the atlas's shared layout and texture.
+    Regions,
+    /// Each named texture is copied into its own [`Image`]. This duplicates the atlas's pixels, and is unavailable
+    /// for atlases with a compressed texture format.
+    Images,
+}
+
+/// A single page of a [`TextureAtlasAsset`].
+#[derive(Debug, Clone, Reflect)]
+pub struct AtlasPage {
+    pub layout: Handle<TextureAtlasLayout>,
+    pub texture: Handle<Image>,
+    /// Copies of `texture` at 1/2, 1/4, and so on of its size, for atlases built with `lods`. They can be drawn with
+    /// `layout` in place of `texture`, as layouts are scaled to the size of the image they're drawn from.
+    pub lods: Vec<Handle<Image>>,
+    /// The images of the page in each channel of atlases built with `channels`, such as `normal` or `emissive`, by the
+    /// name of the channel. They share `layout`, with each texture's image in a channel in the same place as the
+    /// texture itself, so that lit sprites can sample all of them with the same UVs.
+    pub channels: HashMap<String, Handle<Image>>,
+}
+
+/// An [`Asset`] describing a single named texture of a [`TextureAtlasAsset`], so that it can be loaded by its own
+/// path, as in `my.atlas.ron#sprite/hero_idle`. The region shares the atlas's layout and texture, which must be loaded
+/// alongside it.
+#[derive(Asset, Reflect, Debug, Clone)]
+pub struct AtlasRegion {
+    /// The texture of the page containing the region.
+    pub texture: Handle<Image>,
+    /// The layout of the page containing the region, and the region's index within it.
+    pub texture_atlas: TextureAtlas,
+    /// The area of the page's texture covered by the region.
+    pub rect: URect,
+    /// The array texture layer containing the region, if the atlas is an array texture.
+    pub layer: Option<u32>,
+    /// How the region was packed into the atlas.
+    pub info: TextureInfo,
+}
+
+impl AtlasRegion {
+    /// Creates a [`Sprite`] displaying the region, using the [`Anchor`] given by [`TextureInfo::anchor`].
+    ///
+    /// Rotated textures (see [`TextureInfo::rotated`]) must also be rotated by [`TextureInfo::rotation`] to be
+    /// displayed upright.
+    pub fn sprite(&self) -> Sprite {
+        Sprite {
+            anchor: self.info.anchor(self.rect),
+            ..Sprite::from_atlas_image(self.texture.clone(), self.texture_atlas.clone())
+        }
+    }
+}
+
+/// A named texture of an atlas that is added as a `#sprite/NAME` sub-asset once the atlas's pages are added.
+struct SpriteTexture {
+    name: String,
+    location: AtlasLocation,
+    rect: URect,
+    /// The texture copied out of the atlas, for [`SpriteAssets::Images`].
+    image: Option<Image>,
+}
+
+impl SpriteTexture {
+    /// Finds the named textures of an atlas with the given page `layouts`, copying them out of `images` for
+    /// [`SpriteAssets::Images`]. `images` holds the texture of each page, or the single array texture of an atlas
+    /// with the given `array` layers.
+    fn collect(
+        sprites: Option<SpriteAssets>,
+        paths: &TextureAtlasPaths,
+        layouts: &[&TextureAtlasLayout],
+        images: &[&Image],
+        array: Option<ArrayLayers>,
+    ) -> Vec<Self> {
+        let Some(sprites) = sprites else {
+            return Vec::new();
+        };
+        let copy = sprites == SpriteAssets::Images;
+        if copy {
+            if let Some(format) = images
+                .iter()
+                .map(|image| image.texture_descriptor.format)
+                .find(|format| format.is_compressed())
+            {
+                warn!(
+                    "Sprite images can't be copied from an atlas with compressed format {format:?}"
+                );
+                return Vec::new();
+            }
+        }
+        let mut textures: Vec<Self> = paths
+            .name_ids
+            .iter()
+            .filter_map(|(name, &entry)| {
+                let location = paths.location(entry);
+                let rect = *layouts.get(location.page)?.textures.get(location.index)?;
+                let image = copy.then(|| {
+                    let image = images[if array.is_some() { 0 } else { location.page }];
+                    let layer = array_layer(array, location).unwrap_or(0);
+                    let rotated = paths.infos.get(entry).is_some_and(|info| info.rotated);
+                    packer::extract(image, rect, layer, rotated)
+                });
+                Some(Self {
+                    name: name.clone(),
+                    location,
+                    rect,
+                    image,
+                })
+            })
+            .collect();
+        // Sub-assets are added in a deterministic order so that processed atlases are reproducible
+        textures.sort_by(|a, b| a.name.cmp(&b.name));
+        textures
+    }
+}
+
+/// Returns the label of the `#sprite/NAME` sub-asset of the texture with the given `name`.
+pub(crate) fn sprite_label(name: &str) -> String {
+    format!("sprite/{name}")
+}
+
+/// Returns the array texture layer containing the texture at the given [`AtlasLocation`], for an atlas with the given
+/// `array` layers.
+fn array_layer(array: Option<ArrayLayers>, location: AtlasLocation) -> Option<u32> {
+    match array? {
+        ArrayLayers::Textures => Some(location.index as u32),
+        ArrayLayers::Pages => Some(location.page as u32),
+    }
 }
 
 /// An [`Asset`] containing a full texture atlas. See [`TextureAtlas`] and [`TextureAtlasLayout`] for more information
@@ -98,8 +749,27 @@ impl TextureAtlasPaths {
 /// - `#layout`: A [`TextureAtlasLayout`] for the atlas.
 /// - `#texture`: The [`Image`] texture for the atlas.
 ///
-/// Indices into the atlas may be obtained from the initial tile texture paths using the [`TextureAtlasPaths`] that is
-/// contained in this asset.
+/// Atlases that were built with `multi_page` enabled may be split over several pages. The first page is stored in
+/// `layout` and `texture` as usual, and each page `N` after it is stored in the `#pageN/layout` and `#pageN/texture`
+/// sub-assets. All pages (including the first) are listed in `pages`.
+///
+/// Atlases that were built with `array` enabled store their textures in the layers of a single 2D array texture
+/// instead. In this case `texture` and the texture of every page are the same array image, and `array` describes how
+/// its layers are used. The layer of each texture can be looked up with [`TextureAtlasAsset::layer`].
+///
+/// Animations defined in the atlas's `.atlas.ron` file are stored in `#anim/NAME` [`AtlasAnimation`] sub-assets, and
+/// are listed in `animations`.
+///
+/// Atlases that were built with `sprite_assets` set also store each named texture in a `#sprite/NAME` sub-asset, which
+/// is either an [`AtlasRegion`] listed in `sprite_regions` or, for [`SpriteAssets::Images`], a cropped [`Image`]
+/// listed in `sprite_images`.
+///
+/// An `.atlas.ron` file that defines several named atlases in `atlases` is loaded as an atlas with no pages of its
+/// own. Each named atlas is stored in a `#NAME` [`TextureAtlasAsset`] sub-asset and listed in `atlases`, and its own
+/// sub-assets are prefixed with `NAME/`, as in `#NAME/layout` and `#NAME/texture`.
+///
+/// Indices into the atlas may be obtained from the initial tile texture paths or their names using the
+/// [`TextureAtlasPaths`] that is contained in this asset.
 ///
 /// # Example
 ///
@@ -114,34 +784,623 @@ impl TextureAtlasPaths {
 ///     )
 /// }
 /// ```
-#[derive(Asset, TypePath, Clone)]
+#[derive(Asset, Reflect, Clone)]
 pub struct TextureAtlasAsset {
     pub layout: Handle<TextureAtlasLayout>,
     pub texture: Handle<Image>,
+    pub pages: Vec<AtlasPage>,
     pub paths: TextureAtlasPaths,
+    /// How the layers of `texture` are used, if it is an array texture.
+    pub array: Option<ArrayLayers>,
+    /// The animations defined for this atlas, by name.
+    pub animations: HashMap<String, Handle<AtlasAnimation>>,
+    /// The named atlases defined alongside this atlas, by name.
+    pub atlases: HashMap<String, Handle<TextureAtlasAsset>>,
+    /// The `#sprite/NAME` region of each named texture, by name, for atlases built with [`SpriteAssets::Regions`].
+    pub sprite_regions: HashMap<String, Handle<AtlasRegion>>,
+    /// The `#sprite/NAME` image of each named texture, by name, for atlases built with [`SpriteAssets::Images`].
+    pub sprite_images: HashMap<String, Handle<Image>>,
+    /// Whether the colors of the atlas's images are premultiplied by their alpha, in which case sprites drawn from it
+    /// need a premultiplied blend mode such as [`AlphaMode::Premultiplied`].
+    pub premultiplied_alpha: bool,
+    /// How the atlas's textures were converted into distance fields, if they were.
+    pub distance_field: Option<DistanceField>,
+}
+
+/// Returns the label of a sub-asset belonging to the given page. Sub-assets of the first page use the bare `label`,
+/// while those of later pages are prefixed with `pageN/`.
+pub(crate) fn page_label(page: usize, label: &str) -> String {
+    match page {
+        0 => label.to_owned(),
+        page => format!("page{page}/{label}"),
+    }
+}
+
+/// Returns the prefix of the labels of sub-assets belonging to the named atlas `name`, or an empty prefix for the
+/// loaded atlas itself.
+pub(crate) fn atlas_prefix(name: Option<&str>) -> String {
+    name.map(|name| format!("{name}/")).unwrap_or_default()
+}
+
+/// Returns the label of the downscaled copy of an atlas image at the given level of detail, where level 1 is half the
+/// size of the image.
+pub(crate) fn lod_label(level: usize) -> String {
+    format!("lod{level}")
+}
+
+/// Returns the name of the variant of an atlas built for the given window scale factor, such as `@2x`.
+pub(crate) fn resolution_name(resolution: f32) -> String {
+    format!("@{resolution}x")
 }
 
 impl TextureAtlasAsset {
-    /// Retrieves the texture *section* index of the given `texture` path.
+    /// Creates an atlas from its pages, adding each page's layout and texture as labeled sub-assets. There must be at
+    /// least one page.
+    pub(crate) fn from_pages(
+        load_context: &mut LoadContext,
+        pages: impl IntoIterator<Item = (TextureAtlasLayout, LoadedAsset<Image>)>,
+        paths: TextureAtlasPaths,
+    ) -> Self {
+        Self::from_pages_with_prefix(load_context, "", pages, paths, None)
+    }
+
+    /// Creates an atlas from its pages like [`TextureAtlasAsset::from_pages`], with each label prefixed by `prefix`.
+    /// If `sprites` is set, each named texture is also added as a `#sprite/NAME` sub-asset.
+    pub(crate) fn from_pages_with_prefix(
+        load_context: &mut LoadContext,
+        prefix: &str,
+        pages: impl IntoIterator<Item = (TextureAtlasLayout, LoadedAsset<Image>)>,
+        paths: TextureAtlasPaths,
+        sprites: Option<SpriteAssets>,
+    ) -> Self {
+        let pages: Vec<_> = pages.into_iter().collect();
+        let layouts: Vec<&TextureAtlasLayout> = pages.iter().map(|(layout, _)| layout).collect();
+        let images: Vec<&Image> = pages.iter().map(|(_, texture)| texture.get()).collect();
+        let sprite_textures = SpriteTexture::collect(sprites, &paths, &layouts, &images, None);
+
+        let pages: Vec<AtlasPage> = pages
+            .into_iter()
+            .enumerate()
+            .map(|(page, (layout, texture))| AtlasPage {
+                layout: load_context
+                    .add_labeled_asset(format!("{prefix}{}", page_label(page, "layout")), layout),
+                texture: load_context.add_loaded_labeled_asset(
+                    format!("{prefix}{}", page_label(page, "texture")),
+                    texture,
+                ),
+                lods: Vec::new(),
+                channels: HashMap::new(),
+            })
+            .collect();
+        let mut atlas = Self {
+            layout: pages[0].layout.clone(),
+            texture: pages[0].texture.clone(),
+            pages,
+            paths,
+            array: None,
+            animations: HashMap::default(),
+            atlases: HashMap::default(),
+            sprite_regions: HashMap::default(),
+            sprite_images: HashMap::default(),
+            premultiplied_alpha: false,
+            distance_field: None,
+        };
+        atlas.add_sprites(load_context, prefix, sprite_textures);
+        atlas
+    }
+
+    /// Creates an atlas whose pages share a single array texture, adding each page's layout and the texture as labeled
+    /// sub-assets with each label prefixed by `prefix`. There must be at least one layout. If `sprites` is set, each
+    /// named texture is also added as a `#sprite/NAME` sub-asset.
+    pub(crate) fn from_array(
+        load_context: &mut LoadContext,
+        prefix: &str,
+        layouts: impl IntoIterator<Item = TextureAtlasLayout>,
+        texture: LoadedAsset<Image>,
+        array: ArrayLayers,
+        paths: TextureAtlasPaths,
+        sprites: Option<SpriteAssets>,
+    ) -> Self {
+        let layouts: Vec<TextureAtlasLayout> = layouts.into_iter().collect();
+        let sprite_textures = SpriteTexture::collect(
+            sprites,
+            &paths,
+            &layouts.iter().collect::<Vec<_>>(),
+            &[texture.get()],
+            Some(array),
+        );
+
+        let texture = load_context.add_loaded_labeled_asset(format!("{prefix}texture"), texture);
+        let pages: Vec<AtlasPage> = layouts
+            .into_iter()
+            .enumerate()
+            .map(|(page, layout)| AtlasPage {
+                layout: load_context
+                    .add_labeled_asset(format!("{prefix}{}", page_label(page, "layout")), layout),
+                texture: texture.clone(),
+                lods: Vec::new(),
+                channels: HashMap::new(),
+            })
+            .collect();
+        let mut atlas = Self {
+            layout: pages[0].layout.clone(),
+            texture,
+            pages,
+            paths,
+            array: Some(array),
+            animations: HashMap::default(),
+            atlases: HashMap::default(),
+            sprite_regions: HashMap::default(),
+            sprite_images: HashMap::default(),
+            premultiplied_alpha: false,
+            distance_field: None,
+        };
+        atlas.add_sprites(load_context, prefix, sprite_textures);
+        atlas
+    }
+
+    /// Creates an atlas with no pages of its own from a set of named atlases, which must already have been added as
+    /// labeled sub-assets.
+    pub(crate) fn from_atlases(atlases: HashMap<String, Handle<TextureAtlasAsset>>) -> Self {
+        Self {
+            layout: Handle::default(),
+            texture: Handle::default(),
+            pages: Vec::new(),
+            paths: TextureAtlasPaths::default(),
+            array: None,
+            animations: HashMap::default(),
+            atlases,
+            sprite_regions: HashMap::default(),
+            sprite_images: HashMap::default(),
+            premultiplied_alpha: false,
+            distance_field: None,
+        }
+    }
+
+    /// Adds each of the given named textures as a `#sprite/NAME` sub-asset, with each label prefixed by `prefix`.
+    fn add_sprites(
+        &mut self,
+        load_context: &mut LoadContext,
+        prefix: &str,
+        textures: Vec<SpriteTexture>,
+    ) {
+        if !textures.is_empty() {
+            trace!("Adding {} sprite sub-assets", textures.len());
+        }
+        for texture in textures {
+            let label = format!("{prefix}{}", sprite_label(&texture.name));
+            if let Some(image) = texture.image {
+                let handle = load_context.add_labeled_asset(label, image);
+                self.sprite_images.insert(texture.name, handle);
+                continue;
+            }
+            let region = AtlasRegion {
+                texture: self.pages[texture.location.page].texture.clone(),
+                texture_atlas: self
+                    .handle_from_location(texture.location)
+                    .expect("sprite textures should be on a page of the atlas"),
+                rect: texture.rect,
+                layer: self.layer(texture.location),
+                info: self
+                    .paths
+                    .info_at(texture.location)
+                    .cloned()
+                    .unwrap_or_default(),
+            };
+            let handle = load_context.add_labeled_asset(label, region);
+            self.sprite_regions.insert(texture.name, handle);
+        }
+    }
+
+    /// Adds the downscaled copies of each page image as labeled sub-assets, with each label prefixed by `prefix`. An
+    /// array atlas has a single set of copies of its array texture, which is shared by all of its pages.
+    pub(crate) fn add_lods(
+        &mut self,
+        load_context: &mut LoadContext,
+        prefix: &str,
+        lods: impl IntoIterator<Item = Vec<Image>>,
+    ) {
+        for (page, lods) in lods.into_iter().enumerate() {
+            let handles: Vec<Handle<Image>> = lods
+                .into_iter()
+                .enumerate()
+                .map(|(level, image)| {
+                    let label = page_label(page, &lod_label(level + 1));
+                    load_context.add_labeled_asset(format!("{prefix}{label}"), image)
+                })
+                .collect();
+            match self.array {
+                Some(_) => {
+                    for atlas_page in self.pages.iter_mut() {
+                        atlas_page.lods = handles.clone();
+                    }
+                }
+                None => self.pages[page].lods = handles,
+            }
+        }
+    }
+
+    /// Adds the image of each page in the channel `name` to the atlas as a labeled sub-asset, such as `normal` or
+    /// `page1/normal`, in the order of the pages.
+    pub(crate) fn add_channel(
+        &mut self,
+        load_context: &mut LoadContext,
+        prefix: &str,
+        name: &str,
+        images: impl IntoIterator<Item = Image>,
+    ) {
+        for (page, (atlas_page, image)) in self.pages.iter_mut().zip(images).enumerate() {
+            let label = page_label(page, name);
+            let handle = load_context.add_labeled_asset(format!("{prefix}{label}"), image);
+            atlas_page.channels.insert(name.to_owned(), handle);
+        }
+    }
+
+    /// Adds the given animations to the atlas as labeled sub-assets.
+    pub(crate) fn add_animations(
+        &mut self,
+        load_context: &mut LoadContext,
+        animations: impl IntoIterator<Item = (String, AtlasAnimation)>,
+    ) {
+        self.add_animations_with_prefix(load_context, "", animations);
+    }
+
+    /// Adds the given animations to the atlas like [`TextureAtlasAsset::add_animations`], with each label prefixed by
+    /// `prefix`.
+    pub(crate) fn add_animations_with_prefix(
+        &mut self,
+        load_context: &mut LoadContext,
+        prefix: &str,
+        animations: impl IntoIterator<Item = (String, AtlasAnimation)>,
+    ) {
+        for (name, mut animation) in animations {
+            let label = format!("{prefix}{}", animation_label(&name));
+            animation.pages = self.pages.clone();
+            let handle = load_context.add_labeled_asset(label, animation);
+            self.animations.insert(name, handle);
+        }
+    }
+
+    /// Retrieves the texture *section* index of the given `texture` path, within the layout of its page.
     pub fn texture_index<'a>(&self, texture: impl Into<AssetPath<'a>>) -> Option<usize> {
         self.paths.texture_index(texture.into())
     }
 
-    /// Returns the [`AssetPath`] associated with the given index, if any.
+    /// Retrieves the [`AtlasLocation`] of the given `texture` path.
+    pub fn texture_location<'a>(&self, texture: impl Into<AssetPath<'a>>) -> Option<AtlasLocation> {
+        self.paths.texture_location(texture.into())
+    }
+
+    /// Returns the [`AssetPath`] associated with the given index on the first page, if any.
     pub fn texture_path(&self, index: usize) -> Option<&AssetPath<'static>> {
         self.paths.texture_path(index)
     }
 
+    /// Retrieves the texture *section* index of the texture with the given `name`, within the layout of its page.
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.paths.index_of(name)
+    }
+
+    /// Retrieves the [`AtlasLocation`] of the texture with the given `name`.
+    pub fn location_of(&self, name: &str) -> Option<AtlasLocation> {
+        self.paths.location_of(name)
+    }
+
+    /// Returns the name associated with the given index on the first page, if any.
+    pub fn texture_name(&self, index: usize) -> Option<&str> {
+        self.paths.texture_name(index)
+    }
+
+    /// Returns the [`TextureInfo`] of the texture with the given `name`.
+    pub fn info_of(&self, name: &str) -> Option<&TextureInfo> {
+        self.paths.info_of(name)
+    }
+
+    /// Creates a [`Sprite`] displaying the texture identified by `texture`, which may be its name, index, location, or
+    /// path (see [`TextureKey`]), using the [`Anchor`] given by [`TextureInfo::anchor`] so that the texture's pivot and
+    /// trimming are honored. Returns `None` if the texture is unknown or its page's layout is not loaded.
+    ///
+    /// Rotated textures (see [`TextureInfo::rotated`]) must also be rotated by [`TextureInfo::rotation`] to be
+    /// displayed upright.
+    pub fn sprite<'a>(
+        &self,
+        layouts: &Assets<TextureAtlasLayout>,
+        texture: impl Into<TextureKey<'a>>,
+    ) -> Option<Sprite> {
+        let location = self.paths.location_of_key(texture)?;
+        let page = self.page(location.page)?;
+        let rect = layouts
+            .get(&page.layout)?
+            .textures
+            .get(location.index)
+            .copied()?;
+        let info = self.paths.info_at(location)?;
+        Some(Sprite {
+            anchor: info.anchor(rect),
+            ..Sprite::from_atlas_image(page.texture.clone(), self.handle_from_location(location)?)
+        })
+    }
+
+    /// Creates a [`Sprite`] like [`TextureAtlasAsset::sprite`], but with the given `anchor` instead of the texture's
+    /// own pivot. Returns `None` if the texture is unknown or its page's layout is not loaded.
+    pub fn sprite_with_anchor<'a>(
+        &self,
+        layouts: &Assets<TextureAtlasLayout>,
+        texture: impl Into<TextureKey<'a>>,
+        anchor: Anchor,
+    ) -> Option<Sprite> {
+        Some(Sprite {
+            anchor,
+            ..self.sprite(layouts, texture)?
+        })
+    }
+
+    /// Deserializes the [`meta`](TextureInfo::meta) value of the texture with the given `name` into `T`. Returns `None`
+    /// if the name is unknown or the texture has no metadata.
+    pub fn meta_of<T: DeserializeOwned>(&self, name: &str) -> Option<Result<T, ron::Error>> {
+        self.info_of(name)?.meta_as()
+    }
+
+    /// Creates a [`TextureSlicer`] for the nine-slice borders of the texture with the given `name`. Returns `None` if
+    /// the name is unknown, the texture has no borders, or the page's layout is not loaded.
+    pub fn slicer(
+        &self,
+        layouts: &Assets<TextureAtlasLayout>,
+        name: &str,
+    ) -> Option<TextureSlicer> {
+        let (_, rect) = self.rect_of(layouts, name)?;
+        self.info_of(name)?.slicer(rect)
+    }
+
+    /// Creates a [`Sprite`] like [`TextureAtlasAsset::sprite`], which is nine-sliced to the given `size` using the
+    /// texture's borders. Returns `None` if the name is unknown, the texture has no borders, or the page's layout is
+    /// not loaded.
+    pub fn sliced_sprite(
+        &self,
+        layouts: &Assets<TextureAtlasLayout>,
+        name: &str,
+        size: Vec2,
+    ) -> Option<Sprite> {
+        Some(Sprite {
+            custom_size: Some(size),
+            image_mode: SpriteImageMode::Sliced(self.slicer(layouts, name)?),
+            ..self.sprite(layouts, name)?
+        })
+    }
+
+    /// Retrieves the texture *section* rectangle of the texture with the given `name`, along with the index of the
+    /// page that contains it. Returns `None` if the name is unknown or the page's layout is not loaded.
+    pub fn rect_of(
+        &self,
+        layouts: &Assets<TextureAtlasLayout>,
+        name: &str,
+    ) -> Option<(usize, URect)> {
+        let location = self.location_of(name)?;
+        let layout = layouts.get(&self.pages.get(location.page)?.layout)?;
+        Some((location.page, layout.textures.get(location.index).copied()?))
+    }
+
+    /// Returns the array texture layer containing the texture at the given [`AtlasLocation`]. Returns `None` if the
+    /// atlas is not an array texture.
+    pub fn layer(&self, location: AtlasLocation) -> Option<u32> {
+        array_layer(self.array, location)
+    }
+
+    /// Returns the array texture layer containing the texture with the given `name`. Returns `None` if the name is
+    /// unknown or the atlas is not an array texture.
+    pub fn layer_of(&self, name: &str) -> Option<u32> {
+        self.layer(self.location_of(name)?)
+    }
+
+    /// Returns the array texture layer containing the given `texture` path. Returns `None` if the path is unknown or
+    /// the atlas is not an array texture.
+    pub fn texture_layer<'a>(&self, texture: impl Into<AssetPath<'a>>) -> Option<u32> {
+        self.layer(self.texture_location(texture)?)
+    }
+
+    /// Returns the handle of the [`AtlasAnimation`] with the given `name`, if it exists.
+    pub fn animation(&self, name: &str) -> Option<&Handle<AtlasAnimation>> {
+        self.animations.get(name)
+    }
+
+    /// Returns the handle of the `#sprite/NAME` [`AtlasRegion`] of the texture with the given `name`, if it exists.
+    pub fn sprite_region(&self, name: &str) -> Option<&Handle<AtlasRegion>> {
+        self.sprite_regions.get(name)
+    }
+
+    /// Returns the handle of the `#sprite/NAME` [`Image`] of the texture with the given `name`, if it exists.
+    pub fn sprite_image(&self, name: &str) -> Option<&Handle<Image>> {
+        self.sprite_images.get(name)
+    }
+
+    /// Returns the handle of the named atlas defined alongside this atlas with the given `name`, if it exists.
+    pub fn atlas(&self, name: &str) -> Option<&Handle<TextureAtlasAsset>> {
+        self.atlases.get(name)
+    }
+
+    /// Returns the handle of the variant of an atlas built with `resolutions` that best matches a window's
+    /// `scale_factor`, such as [`Window::scale_factor`]. This is the smallest variant made for at least that scale
+    /// factor, or the largest variant if there is none, and `None` if the atlas has no variants.
+    pub fn resolution(&self, scale_factor: f32) -> Option<&Handle<TextureAtlasAsset>> {
+        let mut variants: Vec<(f32, &Handle<TextureAtlasAsset>)> = self
+            .atlases
+            .iter()
+            .filter_map(|(name, handle)| {
+                let resolution = name.strip_prefix('@')?.strip_suffix('x')?.parse().ok()?;
+                Some((resolution, handle))
+            })
+            .collect();
+        variants.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        variants
+            .iter()
+            .find(|(resolution, _)| *resolution >= scale_factor)
+            .or(variants.last())
+            .map(|(_, handle)| *handle)
+    }
+
+    /// Returns the page with the given index, if it exists.
+    pub fn page(&self, page: usize) -> Option<&AtlasPage> {
+        self.pages.get(page)
+    }
+
+    /// Returns the image of the given page at a level of detail, where level 0 is the page's full size texture and
+    /// each level after it is half the size of the last, if the atlas was built with that many `lods`. The page's
+    /// layout can be used with any of its levels.
+    pub fn lod(&self, page: usize, level: usize) -> Option<&Handle<Image>> {
+        let page = self.pages.get(page)?;
+        match level {
+            0 => Some(&page.texture),
+            level => page.lods.get(level - 1),
+        }
+    }
+
+    /// Returns the image of the given page in the channel `name`, if the atlas was built with that channel. It shares
+    /// the page's layout.
+    pub fn channel(&self, page: usize, name: &str) -> Option<&Handle<Image>> {
+        self.pages.get(page)?.channels.get(name)
+    }
+
+    /// Returns the normal map of the given page, which is its image in the `normal` channel, if the atlas has one.
+    pub fn normal_map(&self, page: usize) -> Option<&Handle<Image>> {
+        self.channel(page, "normal")
+    }
+
     /// Creates a [`TextureAtlas`] handle for the given `texture` path.
     pub fn handle<'a>(&self, path: impl Into<AssetPath<'a>>) -> Option<TextureAtlas> {
-        self.paths.handle(self.layout.clone(), path.into())
+        self.handle_from_location(self.texture_location(path)?)
+    }
+
+    /// Creates a [`TextureAtlas`] handle for the texture with the given `name`.
+    pub fn handle_from_name(&self, name: &str) -> Option<TextureAtlas> {
+        self.handle_from_location(self.location_of(name)?)
     }
 
-    /// Creates a [`TextureAtlas`] handle for the given index.
+    /// Creates a [`TextureAtlas`] handle for the given index on the first page.
     pub fn handle_from_index(&self, index: usize) -> TextureAtlas {
         TextureAtlas {
             layout: self.layout.clone(),
             index,
         }
     }
+
+    /// Creates a [`TextureAtlas`] handle for the given [`AtlasLocation`]. Returns `None` if the page does not exist.
+    pub fn handle_from_location(&self, location: AtlasLocation) -> Option<TextureAtlas> {
+        Some(TextureAtlas {
+            layout: self.page(location.page)?.layout.clone(),
+            index: location.index,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 32x16 image trimmed to the 20x10 area at (4, 2), packed into `rect`.
+    fn trimmed(rotated: bool) -> (TextureInfo, URect) {
+        let info = TextureInfo {
+            rotated,
+            trim: Some(TextureTrim {
+                offset: UVec2::new(4, 2),
+                source_size: UVec2::new(32, 16),
+            }),
+            ..default()
+        };
+        let rect = match rotated {
+            true => URect::new(0, 0, 10, 20),
+            false => URect::new(0, 0, 20, 10),
+        };
+        (info, rect)
+    }
+
+    fn assert_anchor(anchor: Anchor, expected: Vec2) {
+        assert!(
+            anchor.as_vec().abs_diff_eq(expected, 1e-5),
+            "{anchor:?} != {expected}"
+        );
+    }
+
+    #[test]
+    fn offsets_trimmed_textures() {
+        for rotated in [false, true] {
+            let (info, rect) = trimmed(rotated);
+            assert_eq!(info.trim_offset(rect), Vec2::new(-2.0, 1.0));
+            assert_eq!(info.source_size(rect), UVec2::new(32, 16));
+        }
+        let untrimmed = TextureInfo::default();
+        assert_eq!(untrimmed.trim_offset(URect::new(0, 0, 8, 8)), Vec2::ZERO);
+    }
+
+    #[test]
+    fn anchors_trimmed_textures() {
+        let (info, rect) = trimmed(false);
+        assert_anchor(info.anchor(rect), Vec2::new(0.1, -0.1));
+
+        // Rotating the sprite upright by `rotation` must bring the anchor back to the same place
+        let (info, rect) = trimmed(true);
+        assert_anchor(info.anchor(rect), Vec2::new(-0.1, -0.1));
+        let anchor = info.anchor(rect).as_vec() * rect.size().as_vec2();
+        let upright = info.rotation() * anchor.extend(0.0);
+        assert!(upright.truncate().abs_diff_eq(Vec2::new(2.0, -1.0), 1e-4));
+    }
+
+    #[test]
+    fn anchors_custom_pivots() {
+        let info = TextureInfo {
+            pivot: Some(Vec2::new(0.25, -0.5)),
+            ..default()
+        };
+        assert_anchor(info.anchor(URect::new(0, 0, 16, 8)), Vec2::new(0.25, -0.5));
+
+        let rotated = TextureInfo {
+            rotated: true,
+            ..info.clone()
+        };
+        assert_anchor(
+            rotated.anchor(URect::new(0, 0, 8, 16)),
+            Vec2::new(-0.5, -0.25),
+        );
+
+        // The pivot is relative to the original image, not the trimmed texture
+        let (mut trimmed, rect) = trimmed(false);
+        trimmed.pivot = Some(Vec2::new(0.5, 0.5));
+        assert_anchor(trimmed.anchor(rect), Vec2::new(0.9, 0.7));
+
+        let centered = TextureInfo {
+            pivot: Some(Vec2::ZERO),
+            ..default()
+        };
+        assert_eq!(centered.anchor(URect::new(0, 0, 8, 8)), Anchor::Center);
+    }
+
+    #[test]
+    fn adjusts_slice_borders() {
+        let slices = SliceBorder {
+            left: 6,
+            right: 8,
+            top: 3,
+            bottom: 5,
+        };
+        let (mut info, rect) = trimmed(false);
+        assert_eq!(info.slice_border(rect), None);
+
+        info.slices = Some(slices);
+        let border = SliceBorder {
+            left: 2,
+            right: 0,
+            top: 1,
+            bottom: 1,
+        };
+        assert_eq!(info.slice_border(rect), Some(border));
+
+        let (mut info, rect) = trimmed(true);
+        info.slices = Some(slices);
+        let border = SliceBorder {
+            left: 1,
+            right: 1,
+            top: 2,
+            bottom: 0,
+        };
+        assert_eq!(info.slice_border(rect), Some(border));
+    }
 }
@@ -0,0 +1,550 @@
+//! Loading of `.atlas` files written by the [libGDX](https://libgdx.com/) texture packer.
+
+use std::{collections::BTreeMap, time::Duration};
+
+use bevy::{
+    asset::{
+        io::Reader, AssetLoader, AsyncReadExt, LoadContext, LoadDirectError, ParseAssetPathError,
+    },
+    prelude::*,
+    render::{
+        render_resource::TextureFormat,
+        texture::{
+            ImageAddressMode, ImageFilterMode, ImageLoaderSettings, ImageSampler,
+            ImageSamplerDescriptor,
+        },
+    },
+};
+use thiserror::Error;
+
+use super::{
+    animation::{AnimationFrame, AtlasAnimation},
+    packer, AtlasLocation, SliceBorder, TextureAtlasAsset, TextureAtlasPaths, TextureInfo,
+    TextureTrim,
+};
+
+/// Errors encountered by [`LibGdxLoader`].
+#[derive(Debug, Error)]
+pub enum LibGdxLoaderError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Parse(#[from] LibGdxParseError),
+    #[error(transparent)]
+    ParseAssetPath(#[from] ParseAssetPathError),
+    #[error(transparent)]
+    LoadDirect(#[from] LoadDirectError),
+    #[error("Unable to un-rotate regions of a {0:?} atlas page")]
+    UnsupportedRotationFormat(TextureFormat),
+    #[error("The rect of region \"{name}\" ({rect:?}) is outside of its {size} page")]
+    RegionOutOfBounds {
+        name: String,
+        rect: URect,
+        size: UVec2,
+    },
+}
+
+/// A syntax error in a libGDX `.atlas` file.
+#[derive(Debug, Error)]
+#[error("Invalid libGDX atlas on line {line}: {message}")]
+pub struct LibGdxParseError {
+    /// The line number of the error, starting from 1.
+    pub line: usize,
+    /// A description of the error.
+    pub message: &'static str,
+}
+
+/// An [`AssetLoader`] that loads a [`TextureAtlasAsset`] from a libGDX `.atlas` file, in either the current format or
+/// the legacy format written before libGDX 1.10. Each page of the file becomes a page of the atlas, with its image
+/// loaded from a path relative to the `.atlas` file.
+///
+/// Each region is named after its name in the file, and its whitespace stripping offsets, rotation and nine-patch
+/// splits are kept in its [`TextureInfo`]. Regions that share a name but have an `index` are named `{name}_{index}`
+/// instead, and are also added as an [`AtlasAnimation`] named `name` that shows each of them in order for 0.1
+/// seconds. A page's `filter` and `repeat` settings are applied to the sampler of its image.
+///
+/// libGDX rotates regions 90° counterclockwise, so the pixels of rotated regions are rotated by 180° when the atlas
+/// is loaded to match [`TextureInfo::rotated`].
+pub struct LibGdxLoader;
+
+impl AssetLoader for LibGdxLoader {
+    type Asset = TextureAtlasAsset;
+    type Settings = ();
+    type Error = LibGdxLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        &(): &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        debug!("Loading libGDX atlas from {:?}", load_context.path());
+        let gdx_pages = {
+            let mut text = String::new();
+            reader.read_to_string(&mut text).await?;
+            parse(&text)?
+        };
+
+        let mut paths = TextureAtlasPaths::default();
+        let mut pages = Vec::with_capacity(gdx_pages.len());
+        let mut sequences: BTreeMap<String, Vec<(i32, AtlasLocation)>> = BTreeMap::new();
+        for (page, gdx_page) in gdx_pages.into_iter().enumerate() {
+            if page > 0 {
+                paths.start_page();
+            }
+
+            let image_path = load_context.asset_path().resolve_embed(&gdx_page.image)?;
+            trace!("Loading libGDX atlas page {page} from: {image_path}");
+            let sampler = gdx_page.sampler();
+            let mut texture = load_context
+                .loader()
+                .immediate()
+                .with_settings(move |settings: &mut ImageLoaderSettings| {
+                    if let Some(sampler) = sampler.clone() {
+                        settings.sampler = sampler;
+                    }
+                })
+                .load::<Image>(image_path)
+                .await?;
+            let size = texture.get().size();
+            if let Some(region) = gdx_page
+                .regions
+                .iter()
+                .find(|region| region.rect.max.cmpgt(size).any())
+            {
+                return Err(LibGdxLoaderError::RegionOutOfBounds {
+                    name: region.name.clone(),
+                    rect: region.rect,
+                    size,
+                });
+            }
+
+            if gdx_page.regions.iter().any(|region| region.rotated) {
+                let mut image = texture.take();
+                let format = image.texture_descriptor.format;
+                if format.is_compressed() {
+                    return Err(LibGdxLoaderError::UnsupportedRotationFormat(format));
+                }
+                for region in gdx_page.regions.iter().filter(|region| region.rotated) {
+                    packer::rotate_half(&mut image, region.rect);
+                }
+                texture = image.into();
+            }
+
+            let mut textures = Vec::with_capacity(gdx_page.regions.len());
+            for region in gdx_page.regions {
+                let location = AtlasLocation {
+                    page,
+                    index: textures.len(),
+                };
+                let name = if region.index >= 0 {
+                    let sequence = sequences.entry(region.name.clone()).or_default();
+                    sequence.push((region.index, location));
+                    format!("{}_{}", region.name, region.index)
+                } else {
+                    region.name.clone()
+                };
+                paths.add(None, Some(name), region.info());
+                textures.push(region.rect);
+            }
+
+            let layout = TextureAtlasLayout {
+                size: texture.get().size(),
+                textures,
+            };
+            pages.push((layout, texture));
+        }
+
+        let animations: Vec<(String, AtlasAnimation)> = sequences
+            .into_iter()
+            .map(|(name, mut sequence)| {
+                sequence.sort_by_key(|&(index, _)| index);
+                let frames = sequence
+                    .into_iter()
+                    .map(|(_, location)| AnimationFrame {
+                        location,
+                        duration: Duration::from_millis(100),
+                    })
+                    .collect();
+                (
+                    name,
+                    AtlasAnimation {
+                        frames,
+                        looping: true,
+                        pages: Vec::new(),
+                    },
+                )
+            })
+            .collect();
+
+        debug!(
+            "Loaded libGDX atlas containing {} regions on {} pages",
+            paths.path_indices.len(),
+            paths.page_count(),
+        );
+        let mut atlas = TextureAtlasAsset::from_pages(load_context, pages, paths);
+        atlas.add_animations(load_context, animations);
+        Ok(atlas)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["atlas"]
+    }
+}
+
+/// A page of a libGDX atlas.
+struct GdxPage {
+    /// The path of the page image, relative to the `.atlas` file.
+    image: String,
+    /// The minification and magnification filters of the page image.
+    filter: Option<(ImageFilterMode, ImageFilterMode)>,
+    /// Whether the page image repeats horizontally and vertically.
+    repeat: Option<(bool, bool)>,
+    regions: Vec<GdxRegion>,
+}
+
+impl GdxPage {
+    /// Returns the sampler described by the page's filter and repeat settings, if it has any.
+    fn sampler(&self) -> Option<ImageSampler> {
+        if self.filter.is_none() && self.repeat.is_none() {
+            return None;
+        }
+        let (min_filter, mag_filter) = self.filter.unwrap_or_default();
+        let (repeat_x, repeat_y) = self.repeat.unwrap_or_default();
+        let address_mode = |repeat: bool| {
+            if repeat {
+                ImageAddressMode::Repeat
+            } else {
+                ImageAddressMode::ClampToEdge
+            }
+        };
+        Some(ImageSampler::Descriptor(ImageSamplerDescriptor {
+            min_filter,
+            mag_filter,
+            address_mode_u: address_mode(repeat_x),
+            address_mode_v: address_mode(repeat_y),
+            ..default()
+        }))
+    }
+}
+
+/// A region of a libGDX atlas page.
+struct GdxRegion {
+    name: String,
+    /// The position of the region within its sequence, or `-1` if it is not part of one.
+    index: i32,
+    /// The area covered by the region within the page image.
+    rect: URect,
+    /// Whether the region is rotated 90° counterclockwise within the page image.
+    rotated: bool,
+    /// The whitespace stripping offset from the bottom left corner of the original image, and its size.
+    offsets: Option<(UVec2, UVec2)>,
+    /// The nine-patch splits of the region, as left, right, top and bottom borders.
+    split: Option<[u32; 4]>,
+}
+
+impl GdxRegion {
+    /// Returns the [`TextureInfo`] describing the region's rotation, trim, and nine-patch splits.
+    fn info(&self) -> TextureInfo {
+        let size = if self.rotated {
+            self.rect.size().yx()
+        } else {
+            self.rect.size()
+        };
+        TextureInfo {
+            rotated: self.rotated,
+            // libGDX measures offsets up from the bottom of the original image
+            trim: self
+                .offsets
+                .filter(|&(offset, source_size)| offset != UVec2::ZERO || source_size != size)
+                .map(|(offset, source_size)| TextureTrim {
+                    offset: UVec2::new(offset.x, source_size.y.saturating_sub(offset.y + size.y)),
+                    source_size,
+                }),
+            slices: self.split.map(|[left, right, top, bottom]| SliceBorder {
+                left,
+                right,
+                top,
+                bottom,
+            }),
+            ..default()
+        }
+    }
+}
+
+/// A region whose fields are still being read.
+#[derive(Default)]
+struct RegionBuilder {
+    name: String,
+    line: usize,
+    index: Option<i32>,
+    position: Option<UVec2>,
+    size: Option<UVec2>,
+    rotated: bool,
+    orig: Option<UVec2>,
+    offset: Option<UVec2>,
+    split: Option<[u32; 4]>,
+}
+
+impl RegionBuilder {
+    fn build(self) -> Result<GdxRegion, LibGdxParseError> {
+        let (Some(position), Some(size)) = (self.position, self.size) else {
+            return Err(LibGdxParseError {
+                line: self.line,
+                message: "region is missing its bounds",
+            });
+        };
+        // The size of a rotated region is given before it was rotated
+        let packed_size = if self.rotated { size.yx() } else { size };
+        Ok(GdxRegion {
+            name: self.name,
+            index: self.index.unwrap_or(-1),
+            rect: URect::from_corners(position, position + packed_size),
+            rotated: self.rotated,
+            offsets: match (self.offset, self.orig) {
+                (None, None) => None,
+                (offset, orig) => Some((offset.unwrap_or_default(), orig.unwrap_or(size))),
+            },
+            split: self.split,
+        })
+    }
+}
+
+/// Parses the pages of a libGDX `.atlas` file.
+fn parse(text: &str) -> Result<Vec<GdxPage>, LibGdxParseError> {
+    let mut pages: Vec<GdxPage> = Vec::new();
+    let mut region: Option<RegionBuilder> = None;
+    let mut in_page = false;
+
+    for (line, content) in text.lines().enumerate() {
+        let line = line + 1;
+        let content = content.trim();
+        let error = |message| LibGdxParseError { line, message };
+
+        // Pages are separated by blank lines
+        if content.is_empty() {
+            if let (Some(region), Some(page)) = (region.take(), pages.last_mut()) {
+                page.regions.push(region.build()?);
+            }
+            in_page = false;
+            continue;
+        }
+
+        // The first line of each page is the path of its image
+        let Some(page) = pages.last_mut().filter(|_| in_page) else {
+            pages.push(GdxPage {
+                image: content.to_owned(),
+                filter: None,
+                repeat: None,
+                regions: Vec::new(),
+            });
+            in_page = true;
+            continue;
+        };
+
+        let Some((key, value)) = content.split_once(':') else {
+            // Any line without a field starts a new region
+            if let Some(region) = region.take() {
+                page.regions.push(region.build()?);
+            }
+            region = Some(RegionBuilder {
+                name: content.to_owned(),
+                line,
+                ..default()
+            });
+            continue;
+        };
+        let (key, value) = (key.trim(), value.trim());
+        let numbers = || -> Result<Vec<u32>, LibGdxParseError> {
+            value
+                .split(',')
+                .map(|number| number.trim().parse())
+                .collect::<Result<_, _>>()
+                .map_err(|_| error("expected a list of numbers"))
+        };
+        let pair = || match numbers()?[..] {
+            [x, y] => Ok(UVec2::new(x, y)),
+            _ => Err(error("expected two numbers")),
+        };
+
+        match region.as_mut() {
+            // Fields before the first region belong to the page
+            None => match key {
+                "filter" => {
+                    let filter = |name: &str| {
+                        if name.trim() == "Nearest" || name.trim().starts_with("MipMapNearest") {
+                            ImageFilterMode::Nearest
+                        } else {
+                            ImageFilterMode::Linear
+                        }
+                    };
+                    let (min, mag) = value
+                        .split_once(',')
+                        .ok_or_else(|| error("expected two filters"))?;
+                    page.filter = Some((filter(min), filter(mag)));
+                }
+                "repeat" => {
+                    page.repeat = Some(match value {
+                        "x" => (true, false),
+                        "y" => (false, true),
+                        "xy" => (true, true),
+                        _ => (false, false),
+                    });
+                }
+                // The size, format, and premultiplied alpha of a page are taken from its image
+                _ => {}
+            },
+            Some(region) => match key {
+                "xy" => region.position = Some(pair()?),
+                "size" => region.size = Some(pair()?),
+                "bounds" => match numbers()?[..] {
+                    [x, y, width, height] => {
+                        region.position = Some(UVec2::new(x, y));
+                        region.size = Some(UVec2::new(width, height));
+                    }
+                    _ => return Err(error("expected four numbers")),
+                },
+                "rotate" => {
+                    region.rotated = match value {
+                        "true" | "90" => true,
+                        "false" | "0" => false,
+                        _ => return Err(error("unsupported rotation")),
+                    };
+                }
+                "orig" => region.orig = Some(pair()?),
+                "offset" => region.offset = Some(pair()?),
+                "offsets" => match numbers()?[..] {
+                    [x, y, width, height] => {
+                        region.offset = Some(UVec2::new(x, y));
+                        region.orig = Some(UVec2::new(width, height));
+                    }
+                    _ => return Err(error("expected four numbers")),
+                },
+                "split" => match numbers()?[..] {
+                    [left, right, top, bottom] => region.split = Some([left, right, top, bottom]),
+                    _ => return Err(error("expected four numbers")),
+                },
+                "index" => {
+                    region.index = Some(value.parse().map_err(|_| error("expected a number"))?);
+                }
+                // Padding and custom values are not used
+                _ => {}
+            },
+        }
+    }
+
+    if let (Some(region), Some(page)) = (region, pages.last_mut()) {
+        page.regions.push(region.build()?);
+    }
+    Ok(pages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_the_legacy_format() {
+        let pages = parse(
+            "
+sheet.png
+size: 64, 64
+format: RGBA8888
+filter: Nearest, Nearest
+repeat: none
+hero
+  rotate: false
+  xy: 2, 4
+  size: 16, 32
+  orig: 20, 36
+  offset: 1, 3
+  index: -1
+door
+  rotate: true
+  xy: 20, 0
+  size: 8, 12
+  orig: 8, 12
+  offset: 0, 0
+  split: 1, 2, 3, 4
+  index: 0
+",
+        )
+        .unwrap();
+        assert_eq!(pages.len(), 1);
+        let page = &pages[0];
+        assert_eq!(page.image, "sheet.png");
+        assert!(matches!(
+            page.filter,
+            Some((ImageFilterMode::Nearest, ImageFilterMode::Nearest))
+        ));
+        assert_eq!(page.repeat, Some((false, false)));
+
+        let [hero, door] = &page.regions[..] else {
+            panic!("expected two regions");
+        };
+        assert_eq!(hero.name, "hero");
+        assert_eq!(hero.index, -1);
+        assert_eq!(hero.rect, URect::new(2, 4, 18, 36));
+        // The offset is measured from the bottom, so the image has 1 pixel of padding below and 3 above
+        let trim = hero.info().trim.unwrap();
+        assert_eq!(trim.offset, UVec2::new(1, 1));
+        assert_eq!(trim.source_size, UVec2::new(20, 36));
+
+        // Rotated regions list their size before they were rotated
+        assert!(door.rotated);
+        assert_eq!(door.index, 0);
+        assert_eq!(door.rect, URect::new(20, 0, 32, 8));
+        assert_eq!(door.split, Some([1, 2, 3, 4]));
+        assert!(door.info().trim.is_none());
+    }
+
+    #[test]
+    fn parses_the_current_format() {
+        let pages = parse(
+            "sheet.png
+size:64,64
+filter:Linear,MipMapNearestNearest
+repeat:xy
+hero
+bounds:2,4,16,32
+offsets:1,3,20,36
+rotate:90
+
+second.png
+size:32,32
+coin
+bounds:0,0,8,8
+",
+        )
+        .unwrap();
+        assert_eq!(pages.len(), 2);
+        assert!(matches!(
+            pages[0].filter,
+            Some((ImageFilterMode::Linear, ImageFilterMode::Nearest))
+        ));
+        assert_eq!(pages[0].repeat, Some((true, true)));
+        let hero = &pages[0].regions[0];
+        assert!(hero.rotated);
+        assert_eq!(hero.rect, URect::new(2, 4, 34, 20));
+        assert_eq!(hero.offsets, Some((UVec2::new(1, 3), UVec2::new(20, 36))));
+
+        assert_eq!(pages[1].image, "second.png");
+        assert_eq!(pages[1].regions[0].name, "coin");
+        assert_eq!(pages[1].regions[0].rect, URect::new(0, 0, 8, 8));
+    }
+
+    #[test]
+    fn reports_the_line_of_errors() {
+        let error = parse("sheet.png\nhero\n  xy: 1, 2\n\n").err().unwrap();
+        assert_eq!(error.line, 2);
+        assert_eq!(error.message, "region is missing its bounds");
+
+        let error = parse("sheet.png\nhero\n  bounds: 1, 2, 3\n").err().unwrap();
+        assert_eq!(error.line, 3);
+
+        let error = parse("sheet.png\nhero\n  xy: 1, 2\n  size: 3, 4\n  rotate: 45\n")
+            .err()
+            .unwrap();
+        assert_eq!(error.message, "unsupported rotation");
+    }
+}
@@ -1,19 +1,61 @@
-use std::path::{Path, PathBuf};
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    fmt,
+    future::{poll_fn, Future},
+    io::Write,
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::Poll,
+    time::Duration,
+};
 
+use base64::{prelude::BASE64_STANDARD, Engine};
 use bevy::{
     asset::{
-        io::{Reader, Writer},
+        io::{AssetReaderError, AssetSourceId, MissingAssetSourceError, Reader, VecReader, Writer},
         saver::{AssetSaver, SavedAsset},
-        AssetLoader, AssetPath, AsyncWriteExt, LoadContext, LoadDirectError,
+        AssetLoader, AssetPath, AsyncWriteExt, LoadContext, LoadDirectError, LoadedAsset,
+        ParseAssetPathError, ReadAssetBytesError,
     },
     prelude::*,
-    render::texture::{ImageFormat, ImageFormatSetting, ImageLoaderSettings},
-    sprite::TextureAtlasBuilderError,
+    render::render_asset::RenderAssetUsages,
+    render::render_resource::{TextureFormat, TextureUsages},
+    render::texture::{
+        ImageAddressMode, ImageFilterMode, ImageFormat, ImageFormatSetting, ImageLoaderSettings,
+        ImageSampler, ImageSamplerDescriptor, TextureFormatPixelInfo,
+    },
+    sprite::Anchor,
+    utils::{HashMap, HashSet},
+};
+use image::{imageops::FilterType, ExtendedColorType};
+use png::text_metadata::ITXtChunk;
+use serde::{
+    de::{self, value::MapAccessDeserializer, MapAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
 };
-use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use super::{TextureAtlasAsset, TextureAtlasPaths};
+#[cfg(feature = "dds")]
+use super::dds;
+#[cfg(feature = "ktx2")]
+use super::ktx2;
+#[cfg(feature = "svg")]
+use super::svg;
+use super::{
+    animation::{animation_label, AnimationFrame, AtlasAnimation},
+    archive::Archive,
+    atlas_png, atlas_prefix,
+    cache::{self, BuildCache, CachedBuild, CachedEncoding},
+    glob::{self, SourceReaders},
+    gpu::{GpuComposition, GpuCompositions},
+    packer::{self, PackSettings, PackingAlgorithm},
+    page_label, resolution_name, sdf, ArrayLayers, AtlasLocation, DistanceField, SliceBorder,
+    SpriteAssets, TextureAtlasAsset, TextureAtlasPaths, TextureInfo, TextureTrim,
+};
+#[cfg(feature = "font")]
+use super::{bmfont, font};
 
 /// Errors encountered by [`TextureAtlasLoader`].
 #[derive(Debug, Error)]
@@ -24,6 +66,37 @@ pub enum LoaderError {
     LoadDirect(#[from] LoadDirectError),
     #[error("The sub-asset image loader for \"{0}\" did not return an `Image` handle")]
     ImageTypeMismatch(AssetPath<'static>),
+    #[error("Unable to split a {0:?} atlas image into pages")]
+    UnsupportedPageFormat(TextureFormat),
+    #[error("The atlas pages do not fit within the {0} atlas image")]
+    InvalidPages(UVec2),
+    #[error("Unable to split a {0} atlas image into {1} array layers")]
+    InvalidLayers(UVec2, u32),
+    #[error("The named atlas \"{0}\" has no `size`")]
+    MissingAtlasSize(String),
+    #[error("Atlases with `channels` must give the size of each page in `pages`")]
+    MissingChannelPages,
+    #[error(transparent)]
+    Layout(#[from] LayoutError),
+}
+
+/// An error in the textures of [`LoaderSettings`] that would give them invalid UVs.
+#[derive(Debug, Error)]
+pub enum LayoutError {
+    #[error("The rect of texture {index} ({rect:?}) has its `min` corner beyond its `max` corner")]
+    InvalidRect { index: usize, rect: URect },
+    #[error("The rect of texture {index} ({rect:?}) is outside of its {size} page")]
+    TextureOutOfBounds {
+        index: usize,
+        rect: URect,
+        size: UVec2,
+    },
+    #[error("Texture {index} is on page {page}, but the atlas only has {count} pages")]
+    InvalidTexturePage {
+        index: usize,
+        page: usize,
+        count: usize,
+    },
 }
 
 /// Configures the [`TextureAtlasLoader`].
@@ -31,14 +104,201 @@ pub enum LoaderError {
 /// Note that `LoaderSettings` implements [`From<TextureAtlasLayout>`].
 ///
 /// [`From<TextureAtlasLayout>`]: TextureAtlasLayout
-#[derive(Default, Serialize, Deserialize)]
+#[derive(Default, Serialize, Deserialize, Reflect)]
+#[reflect(no_field_bounds)]
 pub struct LoaderSettings {
     /// The format of the atlas image. If this is `None` the format will be auto-detected based on the image's file
     /// extension.
+    #[reflect(ignore)]
     pub format: Option<ImageFormat>,
-    /// The list of textures in the atlas's [`TextureAtlasLayout`]. Textures with an associated [`AssetPath`] can be
-    /// looked up in [`TextureAtlasAsset::paths`]
-    pub textures: Vec<(Option<AssetPath<'static>>, URect)>,
+    /// The list of textures in the atlas's [`TextureAtlasLayout`]. Textures with an associated [`AssetPath`] or name
+    /// can be looked up in [`TextureAtlasAsset::paths`]. Entries may also be written as `(path, rect)` tuples, as
+    /// they were before [`LoaderTexture`] was added.
+    #[serde(deserialize_with = "deserialize_textures")]
+    pub textures: Vec<LoaderTexture>,
+    /// The sizes of each page of a multi-page atlas. Pages are stacked vertically in the atlas image, aligned to its
+    /// left edge. If this is empty, the whole image is a single page.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pages: Vec<UVec2>,
+    /// If set, the atlas image contains the layers of a 2D array texture stacked vertically. There is one layer per
+    /// texture for [`ArrayLayers::Textures`], or one per page (or only one if `pages` is empty) for
+    /// [`ArrayLayers::Pages`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub array: Option<ArrayLayers>,
+    /// The animations to add to the atlas, by name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub animations: BTreeMap<String, AtlasAnimation>,
+    /// If set, each named texture is also added as a `#sprite/NAME` sub-asset. See [`TextureAtlasAsset`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sprite_assets: Option<SpriteAssets>,
+    /// The size of the area of the image containing this atlas, which is required for atlases in `atlases`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<UVec2>,
+    /// Named atlases to load as labeled sub-assets, instead of this atlas's own textures. Each atlas is stacked
+    /// vertically in the atlas image in order of its name, aligned to the image's left edge, and its `format` is
+    /// ignored.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub atlases: BTreeMap<String, LoaderSettings>,
+    /// Whether to warn about textures with no area, and textures whose rects overlap without being identical, when
+    /// the atlas is loaded. Textures with rects outside of their page are always an error.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub validate: bool,
+    /// Where the atlas image is kept once it is loaded. Setting this to [`RenderAssetUsages::RENDER_WORLD`] drops the
+    /// image's pixels from the main world once it is uploaded to the GPU, after which they can't be read on the CPU.
+    /// Defaults to both worlds, and named `atlases` default to the setting of their parent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asset_usage: Option<RenderAssetUsages>,
+    /// Whether the atlas image is in the sRGB color space. Atlases of data such as normal maps or masks set this to
+    /// `false` so that their pixels are sampled linearly. Defaults to `true`, and named `atlases` default to the
+    /// setting of their parent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_srgb: Option<bool>,
+    /// The sampler of the atlas image, which overrides the default sampler of the `ImagePlugin`. Named `atlases`
+    /// default to the sampler of their parent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[reflect(ignore)]
+    pub sampler: Option<ImageSamplerDescriptor>,
+    /// Whether a full chain of mip levels is generated for the atlas image once it is loaded, which only the first
+    /// level of is saved. Defaults to `false`, and named `atlases` default to the setting of their parent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mipmaps: Option<bool>,
+    /// Whether the colors of the atlas image are already premultiplied by their alpha, as recorded by atlases built
+    /// with `premultiply_alpha`. This doesn't change the image, but sets [`TextureAtlasAsset::premultiplied_alpha`] and
+    /// keeps generated mips from weighting colors by alpha again. Defaults to `false`, and named `atlases` default to
+    /// the setting of their parent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub premultiplied_alpha: Option<bool>,
+    /// The number of downscaled copies of the atlas image generated once it is loaded, at 1/2, 1/4, and so on of its
+    /// size, which aren't saved. Defaults to none, and named `atlases` default to the setting of their parent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lods: Option<u32>,
+    /// How the textures of the atlas were converted into distance fields, as recorded by atlases built with
+    /// `distance_field`. This doesn't change the image, but sets [`TextureAtlasAsset::distance_field`]. Named `atlases`
+    /// default to the setting of their parent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub distance_field: Option<DistanceField>,
+    /// The channels that the atlas image also contains an image of each page in, as saved for atlases built with
+    /// `channels`. The pages of each channel are stacked below the atlas's own pages in order, with the same sizes, and
+    /// `pages` is required even for a single page. Each image is loaded into the [`AtlasPage::channels`] of its page.
+    ///
+    /// [`AtlasPage::channels`]: crate::AtlasPage::channels
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub channels: Vec<LoaderChannel>,
+}
+
+/// A channel of the atlas image in [`LoaderSettings::channels`].
+#[derive(Debug, Clone, Serialize, Deserialize, Reflect)]
+pub struct LoaderChannel {
+    /// The name of the channel, such as `normal`, which its images are labeled with.
+    pub name: String,
+    /// Whether the channel's images are in the sRGB color space, rather than linear.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub is_srgb: bool,
+}
+
+/// A single sub-texture entry in [`LoaderSettings::textures`].
+///
+/// Note that `LoaderTexture` implements [`From<URect>`], and `From<(Option<AssetPath>, URect)>` for the tuples that
+/// `LoaderSettings::textures` used to contain.
+///
+/// [`From<URect>`]: URect
+#[derive(Debug, Clone, Serialize, Deserialize, Reflect)]
+pub struct LoaderTexture {
+    /// The area of the atlas image covered by this texture.
+    pub rect: URect,
+    /// The path that this texture was originally loaded from, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<AssetPath<'static>>,
+    /// The name that this texture can be looked up by, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The index of the page containing this texture. The texture's `rect` is relative to the page.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub page: usize,
+    /// Whether the texture was rotated 90° clockwise when it was packed. See [`TextureInfo::rotated`].
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub rotated: bool,
+    /// How the texture was trimmed before it was packed, if at all. See [`TextureInfo::trim`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trim: Option<TextureTrim>,
+    /// The pivot point of the texture, if any. See [`TextureInfo::pivot`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pivot: Option<Vec2>,
+    /// The nine-slice borders of the texture, if any. See [`TextureInfo::slices`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slices: Option<SliceBorder>,
+    /// Arbitrary user data attached to the texture, if any. See [`TextureInfo::meta`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[reflect(ignore)]
+    pub meta: Option<ron::Value>,
+}
+
+fn is_zero(value: &usize) -> bool {
+    *value == 0
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+impl LoaderTexture {
+    /// Returns the [`TextureInfo`] described by this entry.
+    fn info(&self) -> TextureInfo {
+        TextureInfo {
+            rotated: self.rotated,
+            trim: self.trim,
+            pivot: self.pivot,
+            slices: self.slices,
+            meta: self.meta.clone(),
+        }
+    }
+}
+
+impl From<URect> for LoaderTexture {
+    fn from(rect: URect) -> Self {
+        Self {
+            rect,
+            path: None,
+            name: None,
+            page: 0,
+            rotated: false,
+            trim: None,
+            pivot: None,
+            slices: None,
+            meta: None,
+        }
+    }
+}
+
+impl From<(Option<AssetPath<'static>>, URect)> for LoaderTexture {
+    fn from((path, rect): (Option<AssetPath<'static>>, URect)) -> Self {
+        Self {
+            path,
+            ..Self::from(rect)
+        }
+    }
+}
+
+/// A [`LoaderTexture`], or the `(path, rect)` tuple that [`LoaderSettings::textures`] used to contain.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LoaderTextureEntry {
+    Texture(LoaderTexture),
+    Tuple(Option<AssetPath<'static>>, URect),
+}
+
+/// Deserializes [`LoaderSettings::textures`], accepting both [`LoaderTexture`]s and `(path, rect)` tuples.
+fn deserialize_textures<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<LoaderTexture>, D::Error> {
+    let entries = Vec::<LoaderTextureEntry>::deserialize(deserializer)?;
+    Ok(entries
+        .into_iter()
+        .map(|entry| match entry {
+            LoaderTextureEntry::Texture(texture) => texture,
+            LoaderTextureEntry::Tuple(path, rect) => LoaderTexture::from((path, rect)),
+        })
+        .collect())
 }
 
 impl LoaderSettings {
@@ -46,8 +306,68 @@ impl LoaderSettings {
     pub fn with_format(self, format: ImageFormat) -> Self {
         Self {
             format: Some(format),
-            textures: self.textures,
+            ..self
+        }
+    }
+
+    /// Checks that every texture is on one of the atlas's pages, and lies within the size of its page. If `validate`
+    /// is set, textures with no area and overlapping textures are also reported as warnings.
+    fn check_textures(
+        &self,
+        page_sizes: &[UVec2],
+        asset_path: &AssetPath,
+    ) -> Result<(), LayoutError> {
+        for (index, entry) in self.textures.iter().enumerate() {
+            let rect = entry.rect;
+            let size =
+                page_sizes
+                    .get(entry.page)
+                    .copied()
+                    .ok_or(LayoutError::InvalidTexturePage {
+                        index,
+                        page: entry.page,
+                        count: page_sizes.len(),
+                    })?;
+            if rect.min.cmpgt(rect.max).any() {
+                return Err(LayoutError::InvalidRect { index, rect });
+            }
+            if rect.max.cmpgt(size).any() {
+                return Err(LayoutError::TextureOutOfBounds { index, rect, size });
+            }
+        }
+        if !self.validate {
+            return Ok(());
+        }
+
+        let describe = |index: usize| match self.textures[index].name.as_ref() {
+            Some(name) => format!("texture {index} (\"{name}\")"),
+            None => format!("texture {index}"),
+        };
+        for (index, entry) in self.textures.iter().enumerate() {
+            if entry.rect.is_empty() {
+                warn!(
+                    "The atlas \"{asset_path}\" has no area for {}",
+                    describe(index)
+                );
+            }
+            // Deduplicated textures share identical rects, so only partial overlaps are reported
+            let overlap = self.textures[index + 1..]
+                .iter()
+                .enumerate()
+                .find(|(_, other)| {
+                    other.page == entry.page
+                        && other.rect != entry.rect
+                        && !other.rect.intersect(entry.rect).is_empty()
+                });
+            if let Some((offset, _)) = overlap {
+                warn!(
+                    "The atlas \"{asset_path}\" has overlapping rects for {} and {}",
+                    describe(index),
+                    describe(index + 1 + offset),
+                );
+            }
         }
+        Ok(())
     }
 }
 
@@ -58,8 +378,23 @@ impl From<TextureAtlasLayout> for LoaderSettings {
             textures: layout
                 .textures
                 .into_iter()
-                .map(|rect| (None, rect))
+                .map(LoaderTexture::from)
                 .collect(),
+            pages: Vec::new(),
+            array: None,
+            animations: BTreeMap::new(),
+            sprite_assets: None,
+            size: None,
+            atlases: BTreeMap::new(),
+            validate: false,
+            asset_usage: None,
+            is_srgb: None,
+            sampler: None,
+            mipmaps: None,
+            premultiplied_alpha: None,
+            lods: None,
+            distance_field: None,
+            channels: Vec::new(),
         }
     }
 }
@@ -79,18 +414,37 @@ impl AssetLoader for TextureAtlasLoader {
         settings: &Self::Settings,
         load_context: &mut LoadContext<'_>,
     ) -> Result<Self::Asset, Self::Error> {
+        let mut atlas = Self::load_atlas(reader, settings, load_context).await?;
+        atlas.add_animations(load_context, settings.animations.clone());
+        Ok(atlas)
+    }
+}
+
+impl TextureAtlasLoader {
+    /// Loads the atlas image and builds the layout of each page.
+    async fn load_atlas(
+        reader: &mut dyn Reader,
+        settings: &LoaderSettings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<TextureAtlasAsset, LoaderError> {
         let asset_path = load_context.asset_path().clone();
-        let internal_asset_path = AssetPath::from(
-            asset_path.path().to_path_buf().with_extension(
-                settings
-                    .format
-                    .map_or("bin", |format| format.to_file_extensions()[0]),
-            ),
+        // The image extension is appended rather than replacing the atlas's own extension, so that a path like
+        // `foo.atlas.png` can't select an atlas loader again
+        let mut internal_path = asset_path.path().as_os_str().to_owned();
+        internal_path.push(".");
+        internal_path.push(
+            settings
+                .format
+                .map_or("bin", |format| format.to_file_extensions()[0]),
         );
+        let internal_asset_path = AssetPath::from(PathBuf::from(internal_path));
         debug!("Loading atlas with texture: {asset_path}");
         trace!("Loading atlas texture with path: {internal_asset_path}");
 
         let format = settings.format;
+        let asset_usage = settings.asset_usage;
+        let is_srgb = settings.is_srgb;
+        let sampler = settings.sampler.clone();
         let erased_texture = load_context
             .loader()
             .immediate()
@@ -102,42 +456,289 @@ impl AssetLoader for TextureAtlasLoader {
                     trace!("Override image format: {format:?}");
                     image_settings.format = ImageFormatSetting::Format(format);
                 }
+                if let Some(asset_usage) = asset_usage {
+                    image_settings.asset_usage = asset_usage;
+                }
+                if let Some(is_srgb) = is_srgb {
+                    image_settings.is_srgb = is_srgb;
+                }
+                if let Some(sampler) = sampler.clone() {
+                    image_settings.sampler = ImageSampler::Descriptor(sampler);
+                }
             })
             .load(&internal_asset_path)
             .await?;
         let texture = erased_texture
             .downcast::<Image>()
             .map_err(|_| LoaderError::ImageTypeMismatch(internal_asset_path))?;
+        if !settings.atlases.is_empty() {
+            return Self::load_atlases(settings, texture, load_context).await;
+        }
+        let mipmaps = settings.mipmaps.unwrap_or(false);
+        let premultiplied = settings.premultiplied_alpha.unwrap_or(false);
+        let lods = settings.lods.unwrap_or(0);
+        let mut atlas = Self::build_atlas(
+            settings,
+            texture,
+            "",
+            mipmaps,
+            premultiplied,
+            lods,
+            load_context,
+        )
+        .await?;
+        atlas.distance_field = settings.distance_field;
+        Ok(atlas)
+    }
+
+    /// Splits an atlas image into the named atlases in [`LoaderSettings::atlases`], and builds each of them.
+    async fn load_atlases(
+        settings: &LoaderSettings,
+        texture: LoadedAsset<Image>,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<TextureAtlasAsset, LoaderError> {
+        let image = texture.get();
+        let format = image.texture_descriptor.format;
+        if format.is_compressed() {
+            return Err(LoaderError::UnsupportedPageFormat(format));
+        }
+
+        let mut atlases = HashMap::new();
+        let mut min = UVec2::ZERO;
+        for (name, atlas_settings) in settings.atlases.iter() {
+            let size = atlas_settings
+                .size
+                .ok_or_else(|| LoaderError::MissingAtlasSize(name.clone()))?;
+            let rect = URect::from_corners(min, min + size);
+            if rect.max.cmpgt(image.size()).any() {
+                return Err(LoaderError::InvalidPages(image.size()));
+            }
+            trace!("Loading named atlas {name:?} from {rect:?}");
+            let prefix = atlas_prefix(Some(name));
+            let mut texture = packer::crop(image, rect);
+            texture.asset_usage = atlas_settings.asset_usage.unwrap_or(texture.asset_usage);
+            if let Some(is_srgb) = atlas_settings.is_srgb {
+                let format = texture.texture_descriptor.format;
+                texture.texture_descriptor.format = packer::with_srgb(format, is_srgb);
+            }
+            if let Some(sampler) = atlas_settings.sampler.clone() {
+                texture.sampler = ImageSampler::Descriptor(sampler);
+            }
+            let texture = LoadedAsset::from(texture);
+            let mipmaps = atlas_settings.mipmaps.or(settings.mipmaps).unwrap_or(false);
+            let premultiplied = atlas_settings
+                .premultiplied_alpha
+                .or(settings.premultiplied_alpha)
+                .unwrap_or(false);
+            let lods = atlas_settings.lods.or(settings.lods).unwrap_or(0);
+            let mut atlas = Self::build_atlas(
+                atlas_settings,
+                texture,
+                &prefix,
+                mipmaps,
+                premultiplied,
+                lods,
+                load_context,
+            )
+            .await?;
+            atlas.distance_field = atlas_settings.distance_field.or(settings.distance_field);
+            atlas.add_animations_with_prefix(
+                load_context,
+                &prefix,
+                atlas_settings.animations.clone(),
+            );
+            let handle = load_context.add_labeled_asset(name.clone(), atlas);
+            atlases.insert(name.clone(), handle);
+            min.y += size.y;
+        }
+        debug!("Loaded {} named texture atlases", atlases.len());
+        Ok(TextureAtlasAsset::from_atlases(atlases))
+    }
 
+    /// Builds the layout of each page of an atlas from its loaded image, with the labels of its sub-assets prefixed by
+    /// `prefix`. If `mipmaps` is set, mip levels are generated for each page image, whose colors are `premultiplied`
+    /// by their alpha if that is set, and `lods` downscaled copies of each page image are added alongside it.
+    async fn build_atlas(
+        settings: &LoaderSettings,
+        texture: LoadedAsset<Image>,
+        prefix: &str,
+        mipmaps: bool,
+        premultiplied: bool,
+        lods: u32,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<TextureAtlasAsset, LoaderError> {
         trace!(
             "Building texture atlas layout with {} textures",
             settings.textures.len()
         );
+        if let Some(array) = settings.array {
+            let (page_count, layers) = match array {
+                ArrayLayers::Textures => (1, settings.textures.len().max(1)),
+                ArrayLayers::Pages => (settings.pages.len().max(1), settings.pages.len().max(1)),
+            };
+            let layers = layers as u32;
+
+            let mut image = texture.take();
+            if !image.height().is_multiple_of(layers) {
+                return Err(LoaderError::InvalidLayers(image.size(), layers));
+            }
+            let layer_size = UVec2::new(image.width(), image.height() / layers);
+            settings.check_textures(&vec![layer_size; page_count], load_context.asset_path())?;
+            packer::into_array(&mut image, layers);
+            if mipmaps {
+                generate_mipmaps(&mut image, premultiplied);
+            }
+            let lods = generate_lods(&image, lods, premultiplied, mipmaps);
+            let mut atlas =
+                Self::load_array(settings, array, image, page_count, prefix, load_context);
+            atlas.add_lods(load_context, prefix, [lods]);
+            atlas.premultiplied_alpha = premultiplied;
+            return Ok(atlas);
+        }
+        let (page_textures, channel_pages) =
+            if settings.pages.len() > 1 || !settings.channels.is_empty() {
+                let image = texture.get();
+                let format = image.texture_descriptor.format;
+                if format.is_compressed() {
+                    return Err(LoaderError::UnsupportedPageFormat(format));
+                }
+                if settings.pages.is_empty() {
+                    return Err(LoaderError::MissingChannelPages);
+                }
+                // The pages of each channel are stacked below the atlas's own pages, with the same sizes
+                let page_count = settings.pages.len();
+                let stacked = page_count * (settings.channels.len() + 1);
+                let mut images = Vec::with_capacity(stacked);
+                let mut min = UVec2::ZERO;
+                for &size in settings.pages.iter().cycle().take(stacked) {
+                    let rect = URect::from_corners(min, min + size);
+                    if rect.max.cmpgt(image.size()).any() {
+                        return Err(LoaderError::InvalidPages(image.size()));
+                    }
+                    images.push(packer::crop(image, rect));
+                    min.y += size.y;
+                }
+                let mut images = images.into_iter();
+                let page_textures = images
+                    .by_ref()
+                    .take(page_count)
+                    .map(LoadedAsset::from)
+                    .collect();
+                let channel_pages: Vec<Vec<Image>> = settings
+                    .channels
+                    .iter()
+                    .map(|_| images.by_ref().take(page_count).collect())
+                    .collect();
+                (page_textures, channel_pages)
+            } else {
+                (vec![texture], Vec::new())
+            };
+        let page_sizes: Vec<UVec2> = page_textures
+            .iter()
+            .map(|texture| texture.get().size())
+            .collect();
+        settings.check_textures(&page_sizes, load_context.asset_path())?;
+
         let mut paths = TextureAtlasPaths::default();
-        let mut textures = Vec::with_capacity(settings.textures.len());
-        for (path, rect) in settings.textures.iter() {
-            trace!(
-                "Adding sub-texture to layout with index {}: {path:?}",
-                paths.path_indices.len()
-            );
-            paths.add(path.clone());
-            textures.push(*rect);
+        let mut pages = Vec::with_capacity(page_textures.len());
+        let mut page_lods = Vec::with_capacity(page_textures.len());
+        for (page, mut texture) in page_textures.into_iter().enumerate() {
+            if page > 0 {
+                paths.start_page();
+            }
+            if mipmaps {
+                let mut image = texture.take();
+                generate_mipmaps(&mut image, premultiplied);
+                texture = image.into();
+            }
+            page_lods.push(generate_lods(texture.get(), lods, premultiplied, mipmaps));
+            let mut textures = Vec::new();
+            for entry in settings.textures.iter().filter(|entry| entry.page == page) {
+                trace!(
+                    "Adding sub-texture to layout with index {} on page {page}: {:?} ({:?})",
+                    textures.len(),
+                    entry.path,
+                    entry.name,
+                );
+                paths.add(entry.path.clone(), entry.name.clone(), entry.info());
+                textures.push(entry.rect);
+            }
+            let layout = TextureAtlasLayout {
+                size: texture.get().size(),
+                textures,
+            };
+            pages.push((layout, texture));
         }
 
-        let layout = TextureAtlasLayout {
-            size: texture.get().size(),
-            textures,
-        };
+        debug!(
+            "Loaded texture atlas containing {} sub-textures on {} pages",
+            paths.path_indices.len(),
+            paths.page_count(),
+        );
+        let mut atlas = TextureAtlasAsset::from_pages_with_prefix(
+            load_context,
+            prefix,
+            pages,
+            paths,
+            settings.sprite_assets,
+        );
+        atlas.add_lods(load_context, prefix, page_lods);
+        for (channel, mut images) in settings.channels.iter().zip(channel_pages) {
+            for image in images.iter_mut() {
+                let format = image.texture_descriptor.format;
+                image.texture_descriptor.format = packer::with_srgb(format, channel.is_srgb);
+                if mipmaps {
+                    generate_mipmaps(image, false);
+                }
+            }
+            atlas.add_channel(load_context, prefix, &channel.name, images);
+        }
+        atlas.premultiplied_alpha = premultiplied;
+        Ok(atlas)
+    }
+
+    /// Builds the layout of each page of a loaded atlas image that was reinterpreted as a 2D array texture.
+    fn load_array(
+        settings: &LoaderSettings,
+        array: ArrayLayers,
+        image: Image,
+        page_count: usize,
+        prefix: &str,
+        load_context: &mut LoadContext<'_>,
+    ) -> TextureAtlasAsset {
+        let layer_size = image.size();
+        let layers = image.texture_descriptor.size.depth_or_array_layers;
+
+        let mut paths = TextureAtlasPaths::default();
+        let mut layouts = Vec::with_capacity(page_count);
+        for page in 0..page_count {
+            if page > 0 {
+                paths.start_page();
+            }
+            let mut textures = Vec::new();
+            for entry in settings.textures.iter().filter(|entry| entry.page == page) {
+                paths.add(entry.path.clone(), entry.name.clone(), entry.info());
+                textures.push(entry.rect);
+            }
+            layouts.push(TextureAtlasLayout {
+                size: layer_size,
+                textures,
+            });
+        }
 
         debug!(
-            "Loaded texture atlas containing {} sub-textures",
-            paths.path_indices.len()
+            "Loaded texture array atlas containing {} sub-textures in {layers} layers",
+            paths.path_indices.len(),
         );
-        Ok(TextureAtlasAsset {
-            layout: load_context.add_labeled_asset("layout".into(), layout),
-            texture: load_context.add_loaded_labeled_asset("texture", texture),
+        TextureAtlasAsset::from_array(
+            load_context,
+            prefix,
+            layouts,
+            image.into(),
+            array,
             paths,
-        })
+            settings.sprite_assets,
+        )
     }
 }
 
@@ -146,171 +747,4333 @@ pub enum BuildLoaderError {
     #[error(transparent)]
     Io(#[from] std::io::Error),
     #[error(transparent)]
-    RonSpannedError(#[from] ron::error::SpannedError),
+    InvalidConfig(#[from] Box<InvalidConfig>),
     #[error(transparent)]
     LoadDirect(#[from] LoadDirectError),
     #[error(transparent)]
-    TextureAtlasBuilder(#[from] TextureAtlasBuilderError),
+    ParseAssetPath(#[from] ParseAssetPathError),
+    #[error(transparent)]
+    ReadAssetBytes(#[from] ReadAssetBytesError),
+    #[error("The texture \"{path}\" ({size}) is larger than the maximum atlas size ({max_size})")]
+    TextureTooLarge {
+        path: AssetPath<'static>,
+        size: UVec2,
+        max_size: UVec2,
+    },
+    #[error("Unable to fit \"{path}\" into an atlas of at most {max_size} pixels")]
+    NotEnoughSpace {
+        path: AssetPath<'static>,
+        max_size: UVec2,
+    },
+    #[error("Unable to convert the texture \"{0}\" from {1:?} to the atlas format")]
+    TextureFormat(AssetPath<'static>, TextureFormat),
+    #[error(transparent)]
+    AssetReader(#[from] AssetReaderError),
+    #[error(transparent)]
+    MissingAssetSource(#[from] MissingAssetSourceError),
+    #[error("An atlas config may not specify `textures`, `directory`, or `font` alongside `grid`")]
+    ConflictingSources,
+    #[error("The `{0}` option cannot be used with `grid`")]
+    UnsupportedGridOption(&'static str),
+    #[error("The animation \"{animation}\" refers to unknown frame {frame}")]
+    UnknownAnimationFrame { animation: String, frame: String },
+    #[error("The animation \"{animation}\" has a frame with the invalid duration {duration}")]
+    InvalidFrameDuration { animation: String, duration: f32 },
+    #[error("The sub-asset image loader for \"{0}\" did not return an `Image` handle")]
+    ImageTypeMismatch(AssetPath<'static>),
+    #[error("The inline image data of the texture \"{0}\" is invalid: {1}")]
+    InlineData(String, String),
+    #[error(transparent)]
+    Zip(#[from] zip::result::ZipError),
+    #[error(
+        "The archive does not contain an `.atlas.ron`, `.atlas.json`, or `.atlas.toml` config"
+    )]
+    MissingArchiveConfig,
+    #[error("An atlas config inside an archive may not specify another `archive`")]
+    NestedArchive,
+    #[error("An atlas config may not specify `textures`, `include`, `directory`, `grid`, `font`, `archive`, or `animations` alongside `atlases`")]
+    ConflictingAtlases,
+    #[error("The named atlas \"{0}\" may not define more `atlases`")]
+    NestedAtlases(String),
+    #[error("An atlas config may not specify `resolutions` alongside `atlases`, or within a named atlas")]
+    ConflictingResolutions,
+    #[error("The resolution {0} is not a positive number")]
+    InvalidResolution(f32),
+    #[error("The `range` of the distance field ({0}) is not a positive number")]
+    InvalidDistanceRange(f32),
+    #[error("Unable to generate a distance field of a texture with the format {0:?}")]
+    DistanceFieldFormat(TextureFormat),
+    #[error("The atlas \"{atlas}\" has no texture named \"{name}\" to copy")]
+    UnknownCopiedTexture {
+        atlas: AssetPath<'static>,
+        name: String,
+    },
+    #[error("The rect of the copied texture \"{name}\" ({rect:?}) in layer {layer} is outside of the {size} image of the atlas \"{atlas}\"")]
+    CopiedTextureOutOfBounds {
+        atlas: AssetPath<'static>,
+        name: String,
+        rect: URect,
+        layer: usize,
+        size: UVec2,
+    },
+    #[error("The atlas \"{0}\" cannot copy textures from itself")]
+    CopiedFromSelf(AssetPath<'static>),
+    #[error("The `{0}` option cannot be used with `archive`")]
+    UnsupportedArchiveOption(&'static str),
+    #[error("The `{0}` option cannot be used with `gpu_composite`")]
+    UnsupportedGpuOption(&'static str),
+    #[error("The atlas config \"{0}\" includes itself")]
+    IncludeCycle(AssetPath<'static>),
+    #[error("The texture path {0:?} contains an invalid numbered range, expected `{{start..end}}` with start <= end, expanding into at most 65536 paths")]
+    InvalidRange(PathBuf),
+    #[error("The atlas has several textures with the same {}", .0.join(", "))]
+    DuplicateTextures(Vec<String>),
+    #[error("Unable to load texture {index} (\"{name}\") of the atlas: {error}")]
+    Texture {
+        index: usize,
+        name: String,
+        error: Box<BuildLoaderError>,
+    },
+    #[error("Unable to load {} textures of the atlas:{}", .0.len(), list_errors(.0))]
+    Textures(Vec<BuildLoaderError>),
+    #[error("Unable to load the placeholder texture of the atlas: {0}")]
+    Placeholder(Box<BuildLoaderError>),
+    #[error(transparent)]
+    BuildMemory(#[from] Box<BuildMemoryError>),
+    #[error(transparent)]
+    Sampler(#[from] SamplerError),
+    #[cfg(feature = "font")]
+    #[error("Unable to parse the font {0:?}: {1}")]
+    Font(PathBuf, ttf_parser::FaceParsingError),
+    #[cfg(feature = "svg")]
+    #[error("Unable to rasterize the SVG image {0:?}: {1}")]
+    Svg(PathBuf, svg::SvgError),
+    #[error("Atlas configs that set `font` require the `font` feature")]
+    UnsupportedFont,
+    #[error("The `{0}` option cannot be used with `channels`")]
+    UnsupportedChannelOption(&'static str),
+    #[error("The channel name \"{0}\" is empty, contains a `/`, or is the label of another sub-asset of each page")]
+    InvalidChannelName(String),
+    #[error("The packed map name \"{0}\" is also the name of a channel or of another packed map")]
+    DuplicatePackedMap(String),
+    #[error(
+        "Unable to load the {channel} image of texture {index} (\"{name}\") of the atlas: {error}"
+    )]
+    Channel {
+        channel: String,
+        index: usize,
+        name: String,
+        error: Box<BuildLoaderError>,
+    },
 }
 
-pub struct TextureAtlasBuildLoader;
+/// Formats each error on its own indented line.
+fn list_errors(errors: &[BuildLoaderError]) -> String {
+    errors.iter().map(|error| format!("\n  {error}")).collect()
+}
 
-#[derive(Debug, Deserialize)]
-struct BuildLoaderConfig {
-    textures: Vec<PathBuf>,
+/// An atlas build that was estimated to take more memory than its config's `max_build_memory`.
+#[derive(Debug, Error)]
+#[error(
+    "Building the atlas \"{config}\" would take about {} of memory, more than its `max_build_memory` of {}. Its page images take {}, and its largest textures are:{}",
+    format_bytes(*.estimate),
+    format_bytes(*.budget),
+    format_bytes(*.pages),
+    list_textures(.largest)
+)]
+pub struct BuildMemoryError {
+    /// The path of the config.
+    pub config: AssetPath<'static>,
+    /// The estimated number of bytes taken by the build.
+    pub estimate: u64,
+    /// The config's `max_build_memory`.
+    pub budget: u64,
+    /// The estimated number of bytes taken by the atlas's page images, which is 0 if they weren't estimated yet.
+    pub pages: u64,
+    /// The path and size in bytes of the largest textures of the atlas, largest first.
+    pub largest: Vec<(AssetPath<'static>, u64)>,
 }
 
-impl BuildLoaderConfig {
-    pub fn iter_paths(&self) -> impl Iterator<Item = &'_ Path> {
-        self.textures.iter().map(PathBuf::as_path)
-    }
+/// An error in the `sampler` of an atlas build config.
+#[derive(Debug, Error)]
+pub enum SamplerError {
+    #[error("The `anisotropy` of the atlas's sampler requires every filter to be `Linear`")]
+    AnisotropicNearest,
 }
 
-impl AssetLoader for TextureAtlasBuildLoader {
-    type Asset = TextureAtlasAsset;
-    type Settings = ();
-    type Error = BuildLoaderError;
+/// Formats each texture and its size in memory on its own indented line.
+fn list_textures(textures: &[(AssetPath<'static>, u64)]) -> String {
+    textures
+        .iter()
+        .map(|(path, bytes)| format!("\n  \"{path}\" ({})", format_bytes(*bytes)))
+        .collect()
+}
 
-    async fn load(
-        &self,
-        reader: &mut dyn Reader,
-        &(): &Self::Settings,
-        load_context: &mut LoadContext<'_>,
-    ) -> Result<Self::Asset, Self::Error> {
-        // Read the configuration .ron file
-        debug!("Building texture atlas from {:?}", load_context.path());
-        let config = {
-            let mut bytes = Vec::new();
-            reader.read_to_end(&mut bytes).await?;
-            ron::de::from_bytes::<BuildLoaderConfig>(&bytes)?
-        };
-        trace!(
-            "Building texture atlas with {} textures",
-            config.textures.len()
-        );
+/// Formats a number of bytes in the largest binary unit that it is at least one of.
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 3] = ["KiB", "MiB", "GiB"];
+    if bytes < 1024 {
+        return format!("{bytes} bytes");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
 
-        let mut texture_assets = Vec::with_capacity(config.textures.len());
-        for path in config.iter_paths() {
-            trace!("Loading atlas sub-texture from: {path:?}");
-            texture_assets.push(
-                load_context
-                    .loader()
-                    .immediate()
-                    .load::<Image>(path)
-                    .await?,
-            );
+/// An error in the syntax or contents of an atlas build config.
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error(transparent)]
+    Ron(#[from] ron::error::SpannedError),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[cfg(feature = "toml")]
+    #[error(transparent)]
+    Toml(#[from] toml_edit::de::Error),
+    #[error("Atlas configs in `.atlas.toml` files require the `toml` feature")]
+    UnsupportedToml,
+    #[error("The atlas config is not valid UTF-8")]
+    InvalidText(#[from] std::str::Utf8Error),
+}
+
+impl ConfigError {
+    /// Returns the line and column within `bytes` that the error refers to, starting from 1, if it is known.
+    fn position(&self, bytes: &[u8]) -> Option<(usize, usize)> {
+        match self {
+            Self::Ron(err) => Some((err.position.line, err.position.col)),
+            Self::Json(err) => (err.line() > 0).then(|| (err.line(), err.column())),
+            #[cfg(feature = "toml")]
+            Self::Toml(err) => err.span().map(|span| text_position(bytes, span.start)),
+            Self::UnsupportedToml => None,
+            Self::InvalidText(err) => Some(text_position(bytes, err.valid_up_to())),
         }
+    }
 
-        let mut paths = TextureAtlasPaths::default();
-        let mut builder = TextureAtlasBuilder::default();
-        for (path, texture) in config.iter_paths().zip(texture_assets.iter()) {
-            trace!(
-                "Adding sub-texture with index {}: {path:?}",
-                paths.path_indices.len()
-            );
-            paths.add(Some(AssetPath::from(path).into_owned()));
-            builder.add_texture(None, texture.get());
+    /// Returns the description of the error, without its position.
+    fn message(&self) -> String {
+        match self {
+            Self::Ron(err) => err.code.to_string(),
+            Self::Json(err) => {
+                let message = err.to_string();
+                let position = format!(" at line {} column {}", err.line(), err.column());
+                message
+                    .strip_suffix(&position)
+                    .unwrap_or(&message)
+                    .to_owned()
+            }
+            #[cfg(feature = "toml")]
+            Self::Toml(err) => err.message().to_owned(),
+            _ => self.to_string(),
         }
+    }
+}
 
-        trace!("Finalizing atlas");
-        let (layout, _, texture) = builder.build()?;
+/// Returns the line and column of the byte at `offset` within `bytes`, starting from 1.
+fn text_position(bytes: &[u8], offset: usize) -> (usize, usize) {
+    let before = &bytes[..offset.min(bytes.len())];
+    let line_start = before
+        .iter()
+        .rposition(|&b| b == b'\n')
+        .map_or(0, |i| i + 1);
+    let line = before.iter().filter(|&&b| b == b'\n').count() + 1;
+    let column = String::from_utf8_lossy(&before[line_start..])
+        .chars()
+        .count()
+        + 1;
+    (line, column)
+}
 
-        debug!(
-            "Built texture atlas containing {} sub-textures",
-            paths.path_indices.len()
-        );
-        Ok(TextureAtlasAsset {
-            layout: load_context.add_labeled_asset("layout".into(), layout),
-            texture: load_context.add_labeled_asset("texture".into(), texture),
-            paths,
+/// An atlas build config that could not be parsed, with the position of the error within the config.
+#[derive(Debug, Error)]
+pub struct InvalidConfig {
+    /// The path of the config.
+    pub path: AssetPath<'static>,
+    /// The line and column of the error, starting from 1, if it is known.
+    pub position: Option<(usize, usize)>,
+    /// The line of the config containing the error, marked below the column of the error.
+    pub snippet: Option<String>,
+    #[source]
+    pub error: ConfigError,
+}
+
+impl InvalidConfig {
+    fn new(path: AssetPath<'static>, bytes: &[u8], error: ConfigError) -> Box<Self> {
+        let position = error.position(bytes);
+        let snippet = position.and_then(|(line, column)| {
+            let text = String::from_utf8_lossy(bytes);
+            let source = text.split('\n').nth(line - 1)?.trim_end_matches('\r');
+            // Keep tabs so that the marker lines up with the source line
+            let indent: String = source
+                .chars()
+                .take(column.saturating_sub(1))
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+            let gutter = " ".repeat(line.to_string().len());
+            Some(format!(
+                "{gutter} |\n{line} | {source}\n{gutter} | {indent}^"
+            ))
+        });
+        Box::new(Self {
+            path,
+            position,
+            snippet,
+            error,
         })
     }
+}
 
-    fn extensions(&self) -> &[&str] {
-        &["atlas.ron"]
+impl fmt::Display for InvalidConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some((line, column)) = self.position else {
+            return write!(f, "Invalid atlas config \"{}\": {}", self.path, self.error);
+        };
+        write!(
+            f,
+            "Invalid atlas config \"{}\" at line {line}, column {column}: {}",
+            self.path,
+            self.error.message()
+        )?;
+        if let Some(snippet) = &self.snippet {
+            write!(f, "\n{snippet}")?;
+        }
+        Ok(())
     }
 }
 
-#[derive(Debug, Error)]
-pub enum SaverError {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
-    #[error(transparent)]
-    IntoDynamicImage(#[from] IntoDynamicImageError),
-    #[error("Unable to save image with format: {0:?}")]
-    InvalidImageFormat(ImageFormat),
-    #[error(transparent)]
-    Image(std::io::Error),
-    #[error("Unable to get `TextureAtlasLayout` sub-asset.")]
-    MissingLayout,
-    #[error("Unable to get `Image` sub-asset.")]
-    MissingTexture,
+/// The current version of the atlas build config schema. Configs written for an older `version` are migrated to this
+/// version when they are loaded.
+pub const CONFIG_VERSION: u32 = 2;
+
+/// The extensions of the atlas build configs loaded by [`TextureAtlasBuildLoader`].
+const CONFIG_EXTENSIONS: [&str; 3] = ["atlas.ron", "atlas.json", "atlas.toml"];
+
+/// The texture format of atlases built by [`TextureAtlasBuildLoader`]. Sub-textures are converted to this format
+/// before they are packed.
+pub(crate) const ATLAS_FORMAT: TextureFormat = TextureFormat::Rgba8UnormSrgb;
+
+/// The most sub-texture images that [`TextureAtlasBuildLoader`] loads at once, unless the loader or config sets its
+/// own `max_concurrent_loads`.
+pub const DEFAULT_MAX_CONCURRENT_LOADS: usize = 16;
+
+/// An [`AssetLoader`] that builds a [`TextureAtlasAsset`] from a `.atlas.ron` file.
+///
+/// The images of the config's textures are loaded concurrently, up to [`DEFAULT_MAX_CONCURRENT_LOADS`] at a time
+/// unless the loader or the config sets its own `max_concurrent_loads`, except for those read from an `archive`. While
+/// the atlas is being processed or the asset server watches for changes, they are loaded one at a time instead, so that
+/// each image is recorded as a dependency of the atlas without being read twice.
+pub struct TextureAtlasBuildLoader {
+    readers: SourceReaders,
+    on_error: ErrorPolicy,
+    max_concurrent_loads: usize,
+    /// The queue of pages to composite on the GPU, if there is a render app to composite them.
+    gpu_compositions: Option<GpuCompositions>,
+    /// The last build of each atlas of configs with `incremental`.
+    cache: BuildCache,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct SaverSettings {
-    pub format: ImageFormat,
+impl FromWorld for TextureAtlasBuildLoader {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            readers: SourceReaders::from_world(world),
+            on_error: ErrorPolicy::default(),
+            max_concurrent_loads: DEFAULT_MAX_CONCURRENT_LOADS,
+            gpu_compositions: None,
+            cache: BuildCache::default(),
+        }
+    }
 }
 
-impl Default for SaverSettings {
-    fn default() -> Self {
+impl TextureAtlasBuildLoader {
+    /// Sets the [`ErrorPolicy`] of configs that don't set their own `on_error`.
+    pub fn with_on_error(self, on_error: ErrorPolicy) -> Self {
+        Self { on_error, ..self }
+    }
+
+    /// Sets the most sub-texture images loaded at once by configs that don't set their own `max_concurrent_loads`.
+    pub fn with_max_concurrent_loads(self, max_concurrent_loads: usize) -> Self {
         Self {
-            format: ImageFormat::Png,
+            max_concurrent_loads,
+            ..self
+        }
+    }
+
+    /// Sets the queue that pages of configs with `gpu_composite` are sent to.
+    pub(crate) fn with_gpu_compositions(self, gpu_compositions: Option<GpuCompositions>) -> Self {
+        Self {
+            gpu_compositions,
+            ..self
         }
     }
+
+    /// Sets the cache that the builds of configs with `incremental` are kept in, which is shared with the saver.
+    pub(crate) fn with_cache(self, cache: BuildCache) -> Self {
+        Self { cache, ..self }
+    }
 }
 
-pub struct TextureAtlasSaver;
+/// How [`TextureAtlasBuildLoader`] handles textures that fail to load or decode, or that are too large to ever fit in
+/// the atlas.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Reflect)]
+pub enum ErrorPolicy {
+    /// Fails to build the atlas, reporting every texture that failed to load.
+    #[default]
+    Fail,
+    /// Leaves the texture out of the atlas with a warning. The indices of the following textures are shifted down.
+    Skip,
+    /// Packs the config's `placeholder` texture in place of the texture with a warning, or a built-in checkerboard if
+    /// there is none.
+    Placeholder,
+}
 
-impl AssetSaver for TextureAtlasSaver {
-    type Asset = TextureAtlasAsset;
-    type Settings = SaverSettings;
-    type OutputLoader = TextureAtlasLoader;
-    type Error = SaverError;
+#[derive(Debug, Clone, Deserialize)]
+struct BuildLoaderConfig {
+    /// The version of the config schema that the config was written for. Configs without a version are migrated from
+    /// version 1 with a warning, and named `atlases` default to the version of their parent.
+    #[serde(default)]
+    version: Option<u32>,
+    #[serde(default)]
+    textures: Vec<BuildTextureEntry>,
+    /// Other `.atlas.ron` files whose `textures` are added before this config's own.
+    #[serde(default)]
+    include: Vec<PathBuf>,
+    #[serde(default)]
+    grid: Option<GridConfig>,
+    /// A font whose glyphs are rasterized and added to the atlas after its `textures`, which requires the `font`
+    /// feature.
+    #[cfg(feature = "font")]
+    #[serde(default)]
+    font: Option<FontConfig>,
+    #[cfg(not(feature = "font"))]
+    #[serde(default)]
+    font: Option<de::IgnoredAny>,
+    /// A `.zip` archive to read textures from. If set, texture paths, glob patterns, `directory`, the `grid` image,
+    /// and the `font` are all found within the archive rather than the asset source.
+    #[serde(default)]
+    archive: Option<PathBuf>,
+    /// Whether paths are relative to the directory containing the config, rather than the asset root. Defaults to
+    /// `false` for version 1 configs and `true` otherwise. Paths starting with `/` are always relative to the asset
+    /// root. Paths within an `archive` are relative to its root.
+    #[serde(default)]
+    relative_paths: Option<bool>,
+    /// A directory that each path in `textures` is relative to, so that it doesn't have to be repeated on every
+    /// entry. This is itself resolved like any other path.
+    #[serde(default)]
+    base_dir: Option<PathBuf>,
+    /// A directory to recursively collect textures from. Textures found this way are added after any listed in
+    /// `textures`.
+    #[serde(default)]
+    directory: Option<PathBuf>,
+    /// The file extensions collected from `directory`. Defaults to all extensions recognized by [`ImageFormat`].
+    #[serde(default)]
+    extensions: Option<Vec<String>>,
+    /// Glob patterns of files that glob pattern entries and `directory` should not collect, such as `*_source.psd` or
+    /// `**/wip/**`. Patterns are matched against the end of each path.
+    #[serde(default)]
+    exclude: Vec<PathBuf>,
+    /// The algorithm used to pack textures into the atlas.
+    #[serde(default)]
+    packing: PackingAlgorithm,
+    /// The number of pixels of spacing to add to the right and bottom of each packed texture.
+    #[serde(default)]
+    padding: UVec2,
+    /// The number of pixels to extrude each texture's edge pixels outward by. Extruded pixels are placed around the
+    /// texture in addition to `padding`.
+    #[serde(default)]
+    extrude: u32,
+    /// The multiple of pixels that each texture's position is aligned to, such as `4` for block compressed formats.
+    /// The space taken up by each texture is rounded up to a multiple as well, so that no two textures share a block.
+    #[serde(default)]
+    align_to: Option<u32>,
+    /// The number of mip levels that textures stay separate at, which raises `padding` and `align_to` to keep each
+    /// level's pixels from covering more than one texture. A pixel of level `n` covers `2^n` pixels of the atlas in
+    /// each direction, so textures are aligned and padded to at least as many pixels.
+    #[serde(default)]
+    mip_safe_padding: Option<u32>,
+    /// The size of the first atlas that packing is attempted in. The atlas is doubled in size until all textures fit.
+    #[serde(default)]
+    initial_size: Option<UVec2>,
+    /// The largest atlas that packing is attempted in.
+    #[serde(default)]
+    max_size: Option<UVec2>,
+    /// Whether the atlas dimensions must be powers of two.
+    #[serde(default)]
+    force_power_of_two: bool,
+    /// Whether textures that don't fit within `max_size` are placed on additional atlas pages, rather than failing.
+    #[serde(default)]
+    multi_page: bool,
+    /// Whether textures may be rotated 90° clockwise to fit into the atlas.
+    #[serde(default)]
+    allow_rotation: bool,
+    /// Whether pixel-identical textures are packed once, with all of their entries sharing the same rect.
+    #[serde(default)]
+    deduplicate: bool,
+    /// How textures that fail to load or are too large for the atlas are handled. Defaults to the loader's
+    /// [`ErrorPolicy`], or to [`ErrorPolicy::Placeholder`] if `placeholder` is set.
+    #[serde(default)]
+    on_error: Option<ErrorPolicy>,
+    /// The most sub-texture images loaded at once. Defaults to the loader's limit, and named `atlases` default to the
+    /// limit of their parent.
+    #[serde(default)]
+    max_concurrent_loads: Option<usize>,
+    /// The most memory, in bytes, that the decoded textures and page images of the atlas may be estimated to take
+    /// while it is built. If the estimate is larger, the build fails with an error naming the largest textures before
+    /// any page images are created. Named `atlases` default to the limit of their parent, which applies to each of them separately.
+    #[serde(default)]
+    max_build_memory: Option<u64>,
+    /// A texture to pack in place of any texture that fails to load, so that the atlas can still be built while some of
+    /// its textures are missing. This is either the path of an image, or `true` for a built-in checkerboard.
+    #[serde(default)]
+    placeholder: Option<PlaceholderConfig>,
+    /// Whether several textures may share the same name or path, which makes looking them up ambiguous. If set, these
+    /// textures are only warned about rather than failing the build.
+    #[serde(default)]
+    allow_duplicates: bool,
+    /// A color, such as `"#ff00ff"`, whose pixels are made fully transparent in each texture before it is trimmed and
+    /// packed, for sprite sheets that mark their background with a key color rather than alpha.
+    #[serde(default, deserialize_with = "deserialize_color_key")]
+    color_key: Option<[u8; 3]>,
+    /// Whether fully transparent borders are cropped from each texture before it is packed.
+    #[serde(default)]
+    trim: bool,
+    /// Whether textures are copied into the atlas pages on the GPU once they are uploaded, rather than on the CPU while
+    /// the atlas loads. The page images are left blank on the CPU, so atlases are still composited on the CPU in apps
+    /// that run the asset processor.
+    #[serde(default)]
+    gpu_composite: bool,
+    /// Whether the layout and page images of the atlas are kept after it is built, so that rebuilding it with the same
+    /// packing settings and texture sizes reuses the layout and only copies the textures that changed. Named `atlases`
+    /// default to the setting of their parent.
+    #[serde(default)]
+    incremental: bool,
+    /// Builds the atlas as a 2D array texture, with either each texture or each page in its own layer.
+    #[serde(default)]
+    array: Option<ArrayLayers>,
+    /// Where the atlas's page images are kept once they are loaded. Defaults to both the main world and the render
+    /// world, and named `atlases` default to the setting of their parent.
+    #[serde(default)]
+    asset_usage: Option<RenderAssetUsages>,
+    /// Whether the atlas's page images are in the sRGB color space. If `false`, the pixels of every texture are
+    /// copied into linear page images as they are, for atlases of data such as normal maps or masks. Defaults to
+    /// `true`, and named `atlases` default to the setting of their parent.
+    #[serde(default)]
+    is_srgb: Option<bool>,
+    /// The format of the pixels of the atlas's page images. Defaults to [`PixelFormat::Rgba8`], and named `atlases`
+    /// default to the format of their parent.
+    #[serde(default)]
+    pixel_format: Option<PixelFormat>,
+    /// Whether textures with 16-bit or float pixels are dithered as they are converted to an 8-bit atlas, which hides
+    /// the banding of smooth gradients. Otherwise each channel is rounded to the nearest 8-bit value. Defaults to
+    /// `false`, and named `atlases` default to the setting of their parent.
+    #[serde(default)]
+    dither: Option<bool>,
+    /// The sampler of the atlas's page images, which overrides the default sampler of the `ImagePlugin`. Named
+    /// `atlases` default to the sampler of their parent.
+    #[serde(default)]
+    sampler: Option<SamplerConfig>,
+    /// Whether a full chain of mip levels is generated for each of the atlas's page images, so that it doesn't shimmer
+    /// when it is drawn smaller than its size, such as in 3D. Textures need enough `padding` or `extrude` between them
+    /// to keep their neighbors from bleeding into the smaller levels. Named `atlases` default to the setting of their
+    /// parent.
+    #[serde(default)]
+    mipmaps: Option<bool>,
+    /// Whether the colors of the atlas's page images are multiplied by their alpha once the atlas is packed, for blend
+    /// modes that expect premultiplied alpha. Named `atlases` default to the setting of their parent.
+    #[serde(default)]
+    premultiply_alpha: Option<bool>,
+    /// The number of downscaled copies of each of the atlas's page images added alongside it, at 1/2, 1/4, and so on
+    /// of its size, for swapping in less detailed images when the atlas is drawn smaller, such as when zooming out.
+    /// Each copy shares the layout of its page. Named `atlases` default to the setting of their parent.
+    #[serde(default)]
+    lods: Option<u32>,
+    /// Converts each texture into a distance field of its alpha before it is packed, for shaders that draw crisp
+    /// edges at any scale. Glyphs rasterized from `font` are converted from their outlines instead. Page images are
+    /// linear unless `is_srgb` is set, and named `atlases` default to the setting of their parent.
+    #[serde(default)]
+    distance_field: Option<DistanceField>,
+    /// A suffix, such as `"_n"`, added to the file stem of each texture's path to find its normal map, so that
+    /// `hero.png` uses `hero_n.png` if it exists. This is short for a `normal` channel in `channels` with this
+    /// `suffix`, and named `atlases` default to the setting of their parent.
+    #[serde(default)]
+    normal_suffix: Option<String>,
+    /// Channels such as `normal`, `emissive`, or `mask` to build alongside the atlas, by name. Each channel has an
+    /// image of each page with the same layout, which every texture's own image in the channel is packed into in the
+    /// same place as the texture. Channels that entries give images in are added even if they aren't listed here, and
+    /// named `atlases` default to the channels of their parent.
+    #[serde(default)]
+    channels: BTreeMap<String, ChannelConfig>,
+    /// Named animations made up of the atlas's textures.
+    #[serde(default)]
+    animations: BTreeMap<String, AnimationConfig>,
+    /// If set, each named texture is also added as a `#sprite/NAME` sub-asset. Named `atlases` default to the setting
+    /// of their parent.
+    #[serde(default)]
+    sprite_assets: Option<SpriteAssets>,
+    /// Named atlases to build as labeled sub-assets, each configured like a standalone `.atlas.ron` file.
+    #[serde(default)]
+    atlases: BTreeMap<String, BuildLoaderConfig>,
+    /// A factor to resize every texture by before it is packed, on top of the `scale` or `size` of its entry.
+    #[serde(default)]
+    scale: Option<f32>,
+    /// The window scale factors to build variants of the atlas for, such as `[1, 2]`. Each variant is built as a named
+    /// atlas called `@1x`, `@2x`, and so on, with its textures scaled from `source_resolution`.
+    #[serde(default)]
+    resolutions: Vec<f32>,
+    /// The scale factor that the source textures were made for, which defaults to the largest of `resolutions`.
+    #[serde(default)]
+    source_resolution: Option<f32>,
+}
 
-    async fn save(
-        &self,
-        writer: &mut Writer,
-        asset: SavedAsset<'_, Self::Asset>,
-        settings: &Self::Settings,
-    ) -> Result<<Self::OutputLoader as AssetLoader>::Settings, Self::Error> {
-        debug!("Exporting texture atlas");
-        let paths = &asset.get().paths;
-        let layout = asset
-            .get_labeled::<TextureAtlasLayout, _>("layout")
-            .ok_or(SaverError::MissingLayout)?;
-        let texture = asset
-            .get_labeled::<Image, _>("texture")
-            .ok_or(SaverError::MissingTexture)?;
+/// Deserializes the `fill` of a channel from a hex color string with an optional alpha, as accepted by [`Srgba::hex`].
+fn deserialize_fill<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<[u8; 4]>, D::Error> {
+    let Some(hex) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    let color = Srgba::hex(&hex)
+        .map_err(|error| de::Error::custom(format!("invalid fill color \"{hex}\": {error}")))?;
+    Ok(Some(color.to_u8_array()))
+}
 
-        trace!(
-            "Writing atlas image to buffer ({:?} format)",
-            settings.format
+/// Deserializes a `color_key` from a hex color string, as accepted by [`Srgba::hex`].
+fn deserialize_color_key<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<[u8; 3]>, D::Error> {
+    let Some(hex) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    let color = Srgba::hex(&hex)
+        .map_err(|error| de::Error::custom(format!("invalid color key \"{hex}\": {error}")))?;
+    Ok(Some(color.to_u8_array_no_alpha()))
+}
+
+/// The most mip levels that `mip_safe_padding` keeps textures separate at, which pads them by 32768 pixels.
+const MAX_MIP_SAFE_LEVELS: u32 = 15;
+
+/// Returns the greatest common divisor of two numbers.
+fn gcd(a: u32, b: u32) -> u32 {
+    match b {
+        0 => a,
+        b => gcd(b, a % b),
+    }
+}
+
+/// The number of largest textures listed when an atlas build is estimated to exceed its `max_build_memory`.
+const LARGEST_TEXTURES: usize = 5;
+
+/// Returns the number of bytes taken by an image of the given size and format.
+fn image_memory(size: UVec2, format: TextureFormat) -> u64 {
+    size.x as u64 * size.y as u64 * format.pixel_size() as u64
+}
+
+/// Fails with a [`BuildMemoryError`] if the textures of an atlas, along with `pages` bytes of page images,
+/// are estimated to take more than `budget` bytes of memory. `textures` lists the path and size of each texture, and is
+/// only called if there is a budget.
+fn check_build_memory(
+    budget: Option<u64>,
+    config_path: &AssetPath<'static>,
+    textures: impl FnOnce() -> Vec<(AssetPath<'static>, u64)>,
+    pages: u64,
+) -> Result<(), Box<BuildMemoryError>> {
+    let Some(budget) = budget else {
+        return Ok(());
+    };
+    let mut textures = textures();
+    let estimate = pages + textures.iter().map(|(_, bytes)| bytes).sum::<u64>();
+    trace!(
+        "Estimated atlas build memory: {} of {}",
+        format_bytes(estimate),
+        format_bytes(budget)
+    );
+    if estimate <= budget {
+        return Ok(());
+    }
+    textures.sort_by(|(_, a), (_, b)| b.cmp(a));
+    textures.truncate(LARGEST_TEXTURES);
+    Err(Box::new(BuildMemoryError {
+        config: config_path.clone(),
+        estimate,
+        budget,
+        pages,
+        largest: textures,
+    }))
+}
+
+/// Returns the path of the image in a channel of the texture at `path` by the channel's `suffix`, which is added to its
+/// file stem.
+fn channel_image_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_stem().unwrap_or_default().to_owned();
+    name.push(suffix);
+    if let Some(extension) = path.extension() {
+        name.push(".");
+        name.push(extension);
+    }
+    path.with_file_name(name)
+}
+
+/// Returns `true` if `name` can be used as the name of a channel, which labels its images in each page.
+fn is_valid_channel_name(name: &str) -> bool {
+    let reserved = ["texture", "layout"].contains(&name)
+        || name
+            .strip_prefix("lod")
+            .or_else(|| name.strip_prefix("source"))
+            .is_some_and(|level| level.parse::<u32>().is_ok());
+    !name.is_empty() && !name.contains('/') && !reserved
+}
+
+/// Returns `true` if the file at `path` is the image in a channel with the given `suffix` of another texture.
+fn is_channel_image(path: &Path, suffix: &str) -> bool {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .is_some_and(|stem| stem.ends_with(suffix))
+}
+
+/// Splits a path from a config into its asset source and the path within that source. Paths may be prefixed with the
+/// name of an asset source, as in `embedded://icons/save.png`, and are otherwise in the default source.
+fn split_source(path: &Path) -> (AssetSourceId<'static>, PathBuf) {
+    match path.to_string_lossy().split_once("://") {
+        Some((source, path)) => (AssetSourceId::from(source.to_owned()), PathBuf::from(path)),
+        None => (AssetSourceId::Default, path.to_path_buf()),
+    }
+}
+
+/// Prefixes a path within `source` with the name of the source, as it would be written in a config.
+fn join_source(source: &AssetSourceId, path: PathBuf) -> PathBuf {
+    match source {
+        AssetSourceId::Default => path,
+        AssetSourceId::Name(name) => PathBuf::from(format!("{name}://{}", path.display())),
+    }
+}
+
+/// Returns the [`AssetPath`] of a path from a config, including its asset source.
+fn config_asset_path(path: &Path) -> AssetPath<'static> {
+    let (source, path) = split_source(path);
+    AssetPath::from(path).with_source(source)
+}
+
+/// Polls `futures` concurrently with at most `limit` of them in progress at once, and returns their outputs in order.
+async fn join_bounded<F: Future>(
+    futures: impl IntoIterator<Item = F>,
+    limit: usize,
+) -> Vec<F::Output> {
+    let mut queued = futures.into_iter().map(Box::pin).enumerate();
+    let mut running: Vec<(usize, Pin<Box<F>>)> = Vec::new();
+    let mut outputs = Vec::new();
+    poll_fn(|cx| loop {
+        running.extend(queued.by_ref().take(limit.max(1) - running.len()));
+        let count = running.len();
+        running.retain_mut(|(index, future)| match future.as_mut().poll(cx) {
+            Poll::Ready(output) => {
+                outputs.push((*index, output));
+                false
+            }
+            Poll::Pending => true,
+        });
+        if running.is_empty() {
+            return Poll::Ready(());
+        }
+        // Futures that finished make room for queued ones, which must be polled before waiting
+        if running.len() == count {
+            return Poll::Pending;
+        }
+    })
+    .await;
+    outputs.sort_by_key(|&(index, _)| index);
+    outputs.into_iter().map(|(_, output)| output).collect()
+}
+
+/// Records the file at `path` as a dependency of the asset being loaded, so that the asset is reprocessed when the
+/// file changes. Files read through [`SourceReaders`] aren't otherwise known to the asset processor.
+async fn add_dependency(path: &AssetPath<'static>, load_context: &mut LoadContext<'_>) {
+    if let Err(error) = load_context.read_asset_bytes(path.clone()).await {
+        debug!(
+            "Unable to track {path} as a dependency of {}: {error}",
+            load_context.asset_path()
         );
-        let mut png_buf = Vec::<u8>::new();
-        let dyn_image = texture.get().clone().try_into_dynamic()?;
-        dyn_image
-            .write_to(
-                &mut std::io::Cursor::new(&mut png_buf),
-                settings
-                    .format
-                    .as_image_crate_format()
-                    .ok_or(SaverError::InvalidImageFormat(settings.format))?,
-            )
-            .map_err(|err| {
-                SaverError::Image(std::io::Error::new(std::io::ErrorKind::Other, err))
-            })?;
-        writer.write_all(&png_buf).await?;
+    }
+}
 
-        debug!("Exported texture atlas");
-        Ok(LoaderSettings {
+impl BuildLoaderConfig {
+    /// Parses a config from the contents of the file at `path`, which is read as JSON or TOML if it has a `.json` or
+    /// `.toml` extension, and as RON otherwise.
+    fn parse(bytes: &[u8], path: &Path) -> Result<Self, ConfigError> {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => Ok(serde_json::from_slice(bytes)?),
+            #[cfg(feature = "toml")]
+            Some("toml") => Ok(toml_edit::de::from_str(std::str::from_utf8(bytes)?)?),
+            #[cfg(not(feature = "toml"))]
+            Some("toml") => Err(ConfigError::UnsupportedToml),
+            // Allow optional fields to be written without wrapping them in `Some(...)`
+            _ => Ok(ron::Options::default()
+                .with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME)
+                .from_bytes(bytes)?),
+        }
+    }
+
+    /// Migrates a config loaded from `path` to the current schema version, warning if it was written for a different
+    /// version or has no version. Configs without a version are migrated from version 1.
+    fn migrate(&mut self, path: &AssetPath) {
+        let version = self.version.unwrap_or(1);
+        if self.version.is_none() {
+            warn!(
+                "The atlas config \"{path}\" has no `version`, so it is migrated from version 1 of the config schema \
+                and its paths are resolved from the root of the asset source. Migrate it to `version: {CONFIG_VERSION}`, \
+                with paths relative to its directory, to silence this warning."
+            );
+        } else if version > CONFIG_VERSION {
+            warn!(
+                "The atlas config \"{path}\" was written for version {version} of the config schema, which is newer \
+                than the supported version {CONFIG_VERSION}. Unknown options are ignored."
+            );
+        } else if version < CONFIG_VERSION {
+            warn!(
+                "Migrating the atlas config \"{path}\" from version {version} to version {CONFIG_VERSION} of the \
+                config schema. Update the config and its `version` to silence this warning."
+            );
+        }
+        self.upgrade(version);
+    }
+
+    /// Applies each migration between `version` and the current schema version.
+    fn upgrade(&mut self, version: u32) {
+        // Version 1 resolved every path from the root of the asset source
+        self.relative_paths.get_or_insert(version >= 2);
+        for atlas in self.atlases.values_mut() {
+            atlas.upgrade(atlas.version.unwrap_or(version));
+        }
+    }
+
+    /// Returns the texture to pack in place of textures that fail to load, if any.
+    fn placeholder(&self) -> Option<&PlaceholderConfig> {
+        self.placeholder
+            .as_ref()
+            .filter(|placeholder| !matches!(placeholder, PlaceholderConfig::Builtin(false)))
+    }
+
+    /// Describes each name and path shared by more than one entry in `textures`, along with the indices of the entries.
+    fn duplicate_textures(&self) -> Vec<String> {
+        let mut names = BTreeMap::<String, Vec<usize>>::new();
+        let mut paths = BTreeMap::<String, Vec<usize>>::new();
+        for (index, entry) in self.textures.iter().enumerate() {
+            if let Some(name) = entry.name() {
+                names.entry(name).or_default().push(index);
+            }
+            if let Some(path) = entry.path() {
+                paths
+                    .entry(config_asset_path(path).to_string())
+                    .or_default()
+                    .push(index);
+            }
+        }
+
+        let describe = |indices: &[usize]| match indices {
+            [rest @ .., last] => {
+                let rest: Vec<String> = rest.iter().map(usize::to_string).collect();
+                format!("textures {} and {last}", rest.join(", "))
+            }
+            [] => String::new(),
+        };
+        let names = names
+            .into_iter()
+            .filter(|(_, indices)| indices.len() > 1)
+            .map(|(name, indices)| format!("name \"{name}\" ({})", describe(&indices)));
+        let paths = paths
+            .into_iter()
+            .filter(|(_, indices)| indices.len() > 1)
+            .map(|(path, indices)| format!("path \"{path}\" ({})", describe(&indices)));
+        names.chain(paths).collect()
+    }
+
+    /// Returns the paths of each entry in `textures` that has one, along with the paths of their images in each channel.
+    fn texture_paths_mut(&mut self) -> impl Iterator<Item = &mut PathBuf> {
+        self.textures.iter_mut().flat_map(|entry| match entry {
+            BuildTextureEntry::Path(path) => vec![path],
+            BuildTextureEntry::Named(entry) => entry
+                .path
+                .iter_mut()
+                .chain(entry.channels.values_mut())
+                .chain(entry.normal.iter_mut())
+                .collect(),
+        })
+    }
+
+    /// Moves `normal_suffix` and the `normal` of each entry into the `normal` channel, and adds each channel that an
+    /// entry has an image in to `channels`, unless it is a packed map of another channel.
+    fn merge_channels(&mut self) {
+        if let Some(suffix) = self.normal_suffix.take() {
+            let channel = self.channels.entry(NORMAL_CHANNEL.to_owned()).or_default();
+            channel.suffix.get_or_insert(suffix);
+        }
+        let mut names = Vec::new();
+        for entry in self.textures.iter_mut() {
+            let BuildTextureEntry::Named(entry) = entry else {
+                continue;
+            };
+            if let Some(normal) = entry.normal.take() {
+                entry
+                    .channels
+                    .entry(NORMAL_CHANNEL.to_owned())
+                    .or_insert(normal);
+            }
+            names.extend(entry.channels.keys().cloned());
+        }
+        for name in names {
+            if !self.channels.contains_key(&name) && !self.is_packed_map(&name) {
+                self.channels.insert(name, ChannelConfig::default());
+            }
+        }
+    }
+
+    /// Returns `true` if `name` is the name of a map packed into the components of one of the channels.
+    fn is_packed_map(&self, name: &str) -> bool {
+        self.channels
+            .values()
+            .flat_map(|channel| channel.pack.maps())
+            .any(|(_, map)| map.name == name)
+    }
+
+    /// Returns the name of a packed map that is also the name of a channel or of another packed map, if there is one.
+    fn duplicate_packed_map(&self) -> Option<&str> {
+        let mut names = HashSet::new();
+        self.channels
+            .values()
+            .flat_map(|channel| channel.pack.maps())
+            .map(|(_, map)| map.name.as_str())
+            .find(|name| self.channels.contains_key(*name) || !names.insert(*name))
+    }
+
+    /// Prefixes each path in `textures` with `base_dir`, if it is set.
+    fn apply_base_dir(&mut self) {
+        let Some(base_dir) = self.base_dir.take() else {
+            return;
+        };
+        for path in self.texture_paths_mut() {
+            // Paths in another asset source are left as they are
+            if split_source(path).0 == AssetSourceId::Default {
+                *path = base_dir.join(&*path);
+            }
+        }
+    }
+
+    /// Resolves each path in the config against `config_path`, so that they are relative to the asset root. Only the
+    /// `archive` itself is resolved if textures are read from an archive.
+    fn resolve_paths(
+        &mut self,
+        config_path: &AssetPath,
+        archived: bool,
+    ) -> Result<(), ParseAssetPathError> {
+        let resolve = |path: &mut PathBuf| -> Result<(), ParseAssetPathError> {
+            let resolved = config_path.resolve_embed(&path.to_string_lossy())?;
+            *path = PathBuf::from(resolved.to_string());
+            Ok(())
+        };
+        if let Some(archive) = self.archive.as_mut() {
+            resolve(archive)?;
+        }
+        if archived || self.archive.is_some() {
+            return Ok(());
+        }
+
+        for path in self.texture_paths_mut() {
+            resolve(path)?;
+        }
+        for path in self.include.iter_mut() {
+            resolve(path)?;
+        }
+        for entry in self.textures.iter_mut() {
+            if let BuildTextureEntry::Named(entry) = entry {
+                if let Some(atlas) = entry.atlas.as_mut() {
+                    resolve(atlas)?;
+                }
+            }
+        }
+        if let Some(directory) = self.directory.as_mut() {
+            resolve(directory)?;
+        }
+        if let Some(grid) = self.grid.as_mut() {
+            resolve(&mut grid.path)?;
+        }
+        #[cfg(feature = "font")]
+        if let Some(font) = self.font.as_mut() {
+            resolve(&mut font.path)?;
+        }
+        if let Some(PlaceholderConfig::Path(path)) = self.placeholder.as_mut() {
+            resolve(path)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the options applied to each page image of the built atlas.
+    fn page_options(&self) -> Result<PageOptions, SamplerError> {
+        let sampler = match self.sampler.as_ref() {
+            Some(sampler) => Some(ImageSampler::Descriptor(sampler.descriptor()?)),
+            None => None,
+        };
+        Ok(PageOptions {
+            asset_usage: self.asset_usage,
+            sampler,
+            mipmaps: self.mipmaps.unwrap_or(false),
+            premultiply_alpha: self.premultiply_alpha.unwrap_or(false),
+            lods: self.lods.unwrap_or(0),
+        })
+    }
+
+    /// Returns the [`PackSettings`] described by this config.
+    fn pack_settings(&self) -> PackSettings {
+        let defaults = PackSettings::default();
+        let mut padding = self.padding;
+        let mut align = self.align_to.unwrap_or(1).max(1);
+        if let Some(levels) = self.mip_safe_padding {
+            // A pixel of mip level `levels` covers a block of this many pixels of the first level, so textures are
+            // aligned to whole blocks and kept at least one block apart
+            let block = 1 << levels.min(MAX_MIP_SAFE_LEVELS);
+            padding = padding.max(UVec2::splat(block));
+            align = align / gcd(align, block) * block;
+        }
+        PackSettings {
+            algorithm: self.packing,
+            padding,
+            extrude: self.extrude,
+            align,
+            initial_size: self.initial_size.unwrap_or(defaults.initial_size),
+            max_size: self.max_size.unwrap_or(defaults.max_size),
+            power_of_two: self.force_power_of_two,
+            multi_page: self.multi_page,
+            allow_rotation: self.allow_rotation,
+        }
+    }
+
+    /// Replaces all bare path entries containing numbered ranges or glob patterns with the paths that they match, then
+    /// adds any textures found in `directory`. Paths are searched for in `archive` if it is given. Paths matching `exclude` are skipped.
+    pub async fn collect_textures(
+        &mut self,
+        readers: &SourceReaders,
+        archive: Option<&Archive>,
+    ) -> Result<(), BuildLoaderError> {
+        let included = |path: &PathBuf| {
+            let excluded = self
+                .exclude
+                .iter()
+                .any(|pattern| glob::matches_suffix(pattern, path))
+                || self
+                    .channels
+                    .values()
+                    .flat_map(ChannelConfig::suffixes)
+                    .any(|suffix| is_channel_image(path, suffix));
+            if excluded {
+                trace!("Excluding {path:?} from the atlas");
+            }
+            !excluded
+        };
+        let mut entries = Vec::with_capacity(self.textures.len());
+        for entry in self.textures.drain(..) {
+            match entry {
+                BuildTextureEntry::Path(path) => {
+                    let paths = glob::expand_ranges(&path)
+                        .ok_or_else(|| BuildLoaderError::InvalidRange(path.clone()))?;
+                    entries.extend(paths.into_iter().map(BuildTextureEntry::Path));
+                }
+                entry => entries.push(entry),
+            }
+        }
+
+        let mut textures = Vec::with_capacity(entries.len());
+        for entry in entries {
+            match entry {
+                BuildTextureEntry::Path(pattern) if glob::is_pattern(&pattern) => {
+                    let matches = match archive {
+                        Some(archive) => archive.expand(&pattern),
+                        None => {
+                            let (source, pattern) = split_source(&pattern);
+                            let reader = readers.reader(source.clone())?;
+                            let matches = glob::expand(reader, &pattern).await?;
+                            matches
+                                .into_iter()
+                                .map(|path| join_source(&source, path))
+                                .collect()
+                        }
+                    };
+                    let matches: Vec<PathBuf> = matches.into_iter().filter(included).collect();
+                    trace!("Expanded {pattern:?} to {} textures", matches.len());
+                    textures.extend(matches.into_iter().map(BuildTextureEntry::Path));
+                }
+                entry => textures.push(entry),
+            }
+        }
+
+        if let Some(directory) = self.directory.as_ref() {
+            let files = match archive {
+                Some(archive) => archive.walk(directory),
+                None => {
+                    let (source, directory) = split_source(directory);
+                    let files =
+                        glob::walk(readers.reader(source.clone())?, &directory, None).await?;
+                    files
+                        .into_iter()
+                        .map(|path| join_source(&source, path))
+                        .collect()
+                }
+            };
+            let len = textures.len();
+            textures.extend(
+                files
+                    .into_iter()
+                    .filter(|path| self.has_image_extension(path))
+                    .filter(included)
+                    .map(BuildTextureEntry::Path),
+            );
+            trace!(
+                "Collected {} textures from {directory:?}",
+                textures.len() - len
+            );
+        }
+
+        self.textures = textures;
+        Ok(())
+    }
+
+    /// Returns `true` if the given path should be collected when walking `directory`.
+    fn has_image_extension(&self, path: &Path) -> bool {
+        let Some(extension) = path.extension().and_then(|ext| ext.to_str()) else {
+            return false;
+        };
+        match self.extensions.as_ref() {
+            Some(extensions) => extensions
+                .iter()
+                .any(|ext| ext.eq_ignore_ascii_case(extension)),
+            None => ImageFormat::from_extension(extension).is_some(),
+        }
+    }
+}
+
+/// A single texture entry in a `.atlas.ron` file. This may either be a bare path, or a [`NamedTextureEntry`] struct
+/// to give the texture a name other than its file stem, or other properties. Bare paths may also be glob patterns (see
+/// [`glob`](crate::glob)), in which case they are expanded to every matching file.
+#[derive(Debug, Clone)]
+enum BuildTextureEntry {
+    Path(PathBuf),
+    Named(Box<NamedTextureEntry>),
+}
+
+/// A `(path: "...", ...)` texture entry in a `.atlas.ron` file.
+#[derive(Debug, Clone, Default, Deserialize)]
+struct NamedTextureEntry {
+    #[serde(default)]
+    path: Option<PathBuf>,
+    /// Inline image data encoded as base64, used instead of `path`. This may also be a `data:` URI such as
+    /// `data:image/png;base64,...`, which gives the image's format explicitly. Whitespace is ignored.
+    #[serde(default)]
+    data: Option<String>,
+    /// Another atlas to copy the texture called `name` from, used instead of `path`.
+    #[serde(default)]
+    atlas: Option<PathBuf>,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    pivot: Option<PivotConfig>,
+    /// Nine-slice borders, in pixels of the original image. They are scaled along with the texture if it is resized.
+    #[serde(default)]
+    slices: Option<SliceBorder>,
+    /// Arbitrary user data, which is preserved on the loaded asset.
+    #[serde(default)]
+    meta: Option<ron::Value>,
+    /// A factor to resize the texture by before it is packed, such as `0.25` for art exported at 4x.
+    #[serde(default)]
+    scale: Option<f32>,
+    /// The size to resize the texture to before it is packed, used instead of `scale`.
+    #[serde(default)]
+    size: Option<UVec2>,
+    /// The filter used when the texture is resized.
+    #[serde(default)]
+    filter: ResizeFilter,
+    /// The image of this texture in each of the atlas's `channels`, by the name of the channel, which overrides the
+    /// channel's `suffix`. The images of maps packed into a channel are given by the name of the map instead. Each
+    /// image is resized to the texture's size if they differ.
+    #[serde(default)]
+    channels: BTreeMap<String, PathBuf>,
+    /// The normal map of this texture, which is short for its image in the `normal` channel.
+    #[serde(default)]
+    normal: Option<PathBuf>,
+}
+
+/// The name of the channel of normal maps, whose textures default to a flat normal rather than transparent black.
+const NORMAL_CHANNEL: &str = "normal";
+
+/// A channel of an atlas in a `.atlas.ron` file.
+#[derive(Debug, Clone, Default, Deserialize)]
+struct ChannelConfig {
+    /// A suffix, such as `"_e"`, added to the file stem of each texture's path to find its image in the channel, so
+    /// that `hero.png` uses `hero_e.png` if it exists. Files with the suffix are left out of glob patterns and
+    /// `directory`.
+    #[serde(default)]
+    suffix: Option<String>,
+    /// Whether the channel's images are in the sRGB color space, for colors such as emissive light. Otherwise their
+    /// pixels are copied as they are into linear images, for data such as normals or masks.
+    #[serde(default)]
+    is_srgb: bool,
+    /// The color, such as `"#000000ff"`, of the textures that have no image in the channel. Defaults to a flat normal
+    /// for the `normal` channel, and to transparent black for others.
+    #[serde(default, deserialize_with = "deserialize_fill")]
+    fill: Option<[u8; 4]>,
+    /// Separate grayscale maps, such as metallic, roughness, and ambient occlusion, packed into the red, green, blue,
+    /// and alpha components of the channel's images. Components without a map keep the channel's own image or fill.
+    #[serde(default)]
+    pack: PackConfig,
+}
+
+impl ChannelConfig {
+    /// Returns the color of textures without an image in the channel called `name`.
+    fn fill(&self, name: &str) -> [u8; 4] {
+        match (self.fill, name) {
+            (Some(fill), _) => fill,
+            (None, NORMAL_CHANNEL) => [128, 128, 255, 255],
+            (None, _) => [0, 0, 0, 0],
+        }
+    }
+
+    /// Returns the suffixes of the channel's own images and of each of its packed maps.
+    fn suffixes(&self) -> impl Iterator<Item = &str> {
+        self.suffix.as_deref().into_iter().chain(
+            self.pack
+                .maps()
+                .filter_map(|(_, map)| map.suffix.as_deref()),
+        )
+    }
+}
+
+/// The grayscale maps packed into each component of a channel in a `.atlas.ron` file.
+#[derive(Debug, Clone, Default, Deserialize)]
+struct PackConfig {
+    #[serde(default)]
+    r: Option<PackedMap>,
+    #[serde(default)]
+    g: Option<PackedMap>,
+    #[serde(default)]
+    b: Option<PackedMap>,
+    #[serde(default)]
+    a: Option<PackedMap>,
+}
+
+impl PackConfig {
+    /// Returns each map along with the index of the component it is packed into.
+    fn maps(&self) -> impl Iterator<Item = (usize, &PackedMap)> {
+        [&self.r, &self.g, &self.b, &self.a]
+            .into_iter()
+            .enumerate()
+            .filter_map(|(component, map)| Some((component, map.as_ref()?)))
+    }
+}
+
+/// A grayscale map packed into a component of a channel in a `.atlas.ron` file.
+#[derive(Debug, Clone, Deserialize)]
+struct PackedMap {
+    /// The name of the map, such as `metallic`, which entries give their own image of it by in their `channels`.
+    name: String,
+    /// A suffix, such as `"_m"`, added to the file stem of each texture's path to find its image of the map, like the
+    /// `suffix` of a channel.
+    #[serde(default)]
+    suffix: Option<String>,
+}
+
+/// The formats that the page images of a built atlas can have.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+enum PixelFormat {
+    /// 8-bit RGBA pixels, which are sRGB unless the atlas sets `is_srgb: false`.
+    #[default]
+    Rgba8,
+    /// 16-bit RGBA pixels, for textures such as heightmaps or 16-bit PNGs whose depth shouldn't be lost. The channels of
+    /// each texture are copied as they are, like atlases that set `is_srgb: false`.
+    Rgba16,
+    /// 16-bit float RGBA pixels in linear space, for HDR textures such as emissive sprites or lightmaps.
+    Rgba16Float,
+    /// 32-bit float RGBA pixels in linear space, for HDR textures that need more precision than
+    /// [`PixelFormat::Rgba16Float`].
+    Rgba32Float,
+}
+
+impl PixelFormat {
+    /// Returns the texture format of atlas images with this pixel format, which is sRGB if `is_srgb` is set and the
+    /// format has an sRGB variant.
+    fn texture_format(self, is_srgb: bool) -> TextureFormat {
+        match self {
+            Self::Rgba8 => packer::with_srgb(ATLAS_FORMAT, is_srgb),
+            Self::Rgba16 => TextureFormat::Rgba16Unorm,
+            Self::Rgba16Float => TextureFormat::Rgba16Float,
+            Self::Rgba32Float => TextureFormat::Rgba32Float,
+        }
+    }
+}
+
+/// The filters that a texture entry can be resized with. These match the filters of [`FilterType`].
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+enum ResizeFilter {
+    Nearest,
+    #[default]
+    Triangle,
+    CatmullRom,
+    Gaussian,
+    Lanczos3,
+}
+
+impl From<ResizeFilter> for FilterType {
+    fn from(filter: ResizeFilter) -> Self {
+        match filter {
+            ResizeFilter::Nearest => Self::Nearest,
+            ResizeFilter::Triangle => Self::Triangle,
+            ResizeFilter::CatmullRom => Self::CatmullRom,
+            ResizeFilter::Gaussian => Self::Gaussian,
+            ResizeFilter::Lanczos3 => Self::Lanczos3,
+        }
+    }
+}
+
+// This is equivalent to `#[serde(untagged)]`, but untagged enums buffer their contents before deserializing them, which
+// loses information that RON needs to deserialize nested enums such as `pivot`.
+impl<'de> Deserialize<'de> for BuildTextureEntry {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct EntryVisitor;
+
+        impl<'de> Visitor<'de> for EntryVisitor {
+            type Value = BuildTextureEntry;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a texture path or a `(path: ...)` texture entry")
+            }
+
+            fn visit_str<E: de::Error>(self, path: &str) -> Result<Self::Value, E> {
+                Ok(BuildTextureEntry::Path(path.into()))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+                let entry = NamedTextureEntry::deserialize(MapAccessDeserializer::new(map))?;
+                if entry.scale.is_some() && entry.size.is_some() {
+                    return Err(de::Error::custom(
+                        "a texture entry may not have both `scale` and `size`",
+                    ));
+                }
+                match (&entry.path, &entry.data, &entry.atlas, &entry.name) {
+                    (Some(_), None, None, _)
+                    | (None, Some(_), None, Some(_))
+                    | (None, None, Some(_), Some(_)) => {
+                        Ok(BuildTextureEntry::Named(Box::new(entry)))
+                    }
+                    (None, Some(_), None, None) => Err(de::Error::custom(
+                        "a texture entry with inline `data` must have a `name`",
+                    )),
+                    (None, None, Some(_), None) => Err(de::Error::custom(
+                        "a texture entry copied from an `atlas` must have a `name`",
+                    )),
+                    _ => Err(de::Error::custom(
+                        "a texture entry must have exactly one of `path`, `data`, or `atlas`",
+                    )),
+                }
+            }
+        }
+
+        deserializer.deserialize_any(EntryVisitor)
+    }
+}
+
+/// The pivot point of a texture entry. These match the variants of [`Anchor`].
+#[derive(Debug, Clone, Copy, Deserialize)]
+enum PivotConfig {
+    Center,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+    CenterLeft,
+    CenterRight,
+    TopLeft,
+    TopCenter,
+    TopRight,
+    Custom(Vec2),
+}
+
+impl From<PivotConfig> for Anchor {
+    fn from(pivot: PivotConfig) -> Self {
+        match pivot {
+            PivotConfig::Center => Anchor::Center,
+            PivotConfig::BottomLeft => Anchor::BottomLeft,
+            PivotConfig::BottomCenter => Anchor::BottomCenter,
+            PivotConfig::BottomRight => Anchor::BottomRight,
+            PivotConfig::CenterLeft => Anchor::CenterLeft,
+            PivotConfig::CenterRight => Anchor::CenterRight,
+            PivotConfig::TopLeft => Anchor::TopLeft,
+            PivotConfig::TopCenter => Anchor::TopCenter,
+            PivotConfig::TopRight => Anchor::TopRight,
+            PivotConfig::Custom(point) => Anchor::Custom(point),
+        }
+    }
+}
+
+impl BuildTextureEntry {
+    /// Returns the path of this texture's image, or `None` if its image data is inline.
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Self::Path(path) => Some(path),
+            Self::Named(entry) => entry.path.as_deref(),
+        }
+    }
+
+    /// Returns the inline image data of this texture, if any.
+    pub fn data(&self) -> Option<&str> {
+        match self {
+            Self::Path(_) => None,
+            Self::Named(entry) => entry.data.as_deref(),
+        }
+    }
+
+    /// Returns the path of the atlas that this texture is copied from, if any.
+    pub fn atlas(&self) -> Option<&Path> {
+        match self {
+            Self::Path(_) => None,
+            Self::Named(entry) => entry.atlas.as_deref(),
+        }
+    }
+
+    /// Returns the path of this texture's image in the channel `name`, if it sets one.
+    pub fn channel(&self, name: &str) -> Option<&Path> {
+        match self {
+            Self::Path(_) => None,
+            Self::Named(entry) => entry.channels.get(name).map(PathBuf::as_path),
+        }
+    }
+
+    /// Returns the [`TextureInfo`] configured for this texture. Pivots are converted to the coordinates used by
+    /// [`Anchor::Custom`].
+    pub fn info(&self) -> TextureInfo {
+        match self {
+            Self::Named(entry) => TextureInfo {
+                pivot: entry.pivot.map(|pivot| Anchor::from(pivot).as_vec()),
+                slices: entry.slices,
+                meta: entry.meta.clone(),
+                ..default()
+            },
+            Self::Path(_) => TextureInfo::default(),
+        }
+    }
+
+    /// Returns the size that a texture of the given size is resized to before it is packed, after scaling it by the
+    /// `scale` of the whole config, along with the filter it is resized with. Returns `None` if it keeps its size.
+    pub fn resize(&self, size: UVec2, scale: f32) -> Option<(UVec2, ResizeFilter)> {
+        let (resized, filter) = match self {
+            Self::Named(entry) => match (entry.size, entry.scale) {
+                (Some(resized), _) => (resized.as_vec2(), entry.filter),
+                (None, Some(entry_scale)) => (size.as_vec2() * entry_scale, entry.filter),
+                (None, None) => (size.as_vec2(), entry.filter),
+            },
+            Self::Path(_) => (size.as_vec2(), ResizeFilter::default()),
+        };
+        let resized = (resized * scale).round().as_uvec2().max(UVec2::ONE);
+        (resized != size).then_some((resized, filter))
+    }
+
+    /// Returns the explicitly configured name of this texture, falling back to its file stem.
+    pub fn name(&self) -> Option<String> {
+        match self {
+            Self::Named(entry) if entry.name.is_some() => entry.name.clone(),
+            _ => self
+                .path()
+                .and_then(Path::file_stem)
+                .and_then(|stem| stem.to_str())
+                .map(str::to_owned),
+        }
+    }
+}
+
+/// A named animation in a `.atlas.ron` file.
+#[derive(Debug, Clone, Deserialize)]
+struct AnimationConfig {
+    frames: Vec<FrameConfig>,
+    /// The duration of each frame that doesn't specify its own, in seconds.
+    #[serde(default = "AnimationConfig::default_duration")]
+    duration: f32,
+    #[serde(default = "AnimationConfig::default_looping")]
+    looping: bool,
+}
+
+/// A single frame of an [`AnimationConfig`]. This may either be a bare [`FrameRef`], or a `(frame: ..., duration: ...)`
+/// struct to override the frame's duration.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum FrameConfig {
+    Texture(FrameRef),
+    Timed { frame: FrameRef, duration: f32 },
+}
+
+/// Refers to a texture in the atlas either by its name or by its index. Indices count every texture in the order they
+/// were added, across all pages.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum FrameRef {
+    Index(usize),
+    Name(String),
+}
+
+/// The reason an [`AnimationConfig`] could not be resolved.
+enum FrameError {
+    /// A description of a frame that is not in the atlas.
+    Unknown(String),
+    /// A frame duration that is negative, not finite, or too large.
+    InvalidDuration(f32),
+}
+
+impl AnimationConfig {
+    fn default_duration() -> f32 {
+        0.1
+    }
+
+    fn default_looping() -> bool {
+        true
+    }
+
+    /// Looks up the location of each frame in `paths`, failing if a frame cannot be found or has an invalid duration.
+    fn resolve(self, paths: &TextureAtlasPaths) -> Result<AtlasAnimation, FrameError> {
+        let frames = self
+            .frames
+            .into_iter()
+            .map(|frame| {
+                let (frame, duration) = match frame {
+                    FrameConfig::Texture(frame) => (frame, self.duration),
+                    FrameConfig::Timed { frame, duration } => (frame, duration),
+                };
+                let location = match &frame {
+                    FrameRef::Index(index) => {
+                        (*index < paths.path_indices.len()).then(|| paths.location(*index))
+                    }
+                    FrameRef::Name(name) => paths.location_of(name),
+                };
+                let location = location.ok_or_else(|| match frame {
+                    FrameRef::Index(index) => FrameError::Unknown(index.to_string()),
+                    FrameRef::Name(name) => FrameError::Unknown(format!("\"{name}\"")),
+                })?;
+                let duration = Duration::try_from_secs_f32(duration)
+                    .map_err(|_| FrameError::InvalidDuration(duration))?;
+                Ok(AnimationFrame { location, duration })
+            })
+            .collect::<Result<_, FrameError>>()?;
+        Ok(AtlasAnimation {
+            frames,
+            looping: self.looping,
+            pages: Vec::new(),
+        })
+    }
+}
+
+/// The texture packed in place of textures that fail to load.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum PlaceholderConfig {
+    /// `true` for a magenta and black checkerboard, or `false` for no placeholder.
+    Builtin(bool),
+    /// The path of the placeholder image.
+    Path(PathBuf),
+}
+
+/// The sampler of a built atlas's page images. Options that aren't set keep their values from
+/// [`ImageSamplerDescriptor::default`].
+#[derive(Debug, Clone, Deserialize)]
+struct SamplerConfig {
+    /// The filter used when the atlas is magnified or minified, unless `mag_filter` or `min_filter` are set. `Nearest`
+    /// keeps the pixels of pixel art sharp.
+    #[serde(default)]
+    filter: Option<ImageFilterMode>,
+    #[serde(default)]
+    mag_filter: Option<ImageFilterMode>,
+    #[serde(default)]
+    min_filter: Option<ImageFilterMode>,
+    /// How the atlas is sampled beyond its edges in both directions, unless `address_mode_u` or `address_mode_v` are
+    /// set.
+    #[serde(default)]
+    address_mode: Option<ImageAddressMode>,
+    #[serde(default)]
+    address_mode_u: Option<ImageAddressMode>,
+    #[serde(default)]
+    address_mode_v: Option<ImageAddressMode>,
+    /// The filter used between the mip levels of the atlas.
+    #[serde(default)]
+    mipmap_filter: Option<ImageFilterMode>,
+    /// The most samples taken by anisotropic filtering, which keeps textures sharp when they are viewed at oblique
+    /// angles, clamped between 1 and 16. Filters that aren't set default to `Linear` when this is above 1, as
+    /// anisotropic filtering requires every filter to be linear.
+    #[serde(default)]
+    anisotropy: Option<u16>,
+}
+
+impl SamplerConfig {
+    /// Returns the sampler descriptor with the options that are set, or an error if `anisotropy` is used with a
+    /// `Nearest` filter.
+    fn descriptor(&self) -> Result<ImageSamplerDescriptor, SamplerError> {
+        let defaults = ImageSamplerDescriptor::default();
+        let anisotropy = self.anisotropy.unwrap_or(1).clamp(1, 16);
+        let default_filter = |default| match anisotropy {
+            1 => default,
+            _ => ImageFilterMode::Linear,
+        };
+        let filter = |filter: Option<ImageFilterMode>, default| {
+            filter
+                .or(self.filter)
+                .unwrap_or_else(|| default_filter(default))
+        };
+        let address_mode =
+            |mode: Option<ImageAddressMode>, default| mode.or(self.address_mode).unwrap_or(default);
+        let descriptor = ImageSamplerDescriptor {
+            mag_filter: filter(self.mag_filter, defaults.mag_filter),
+            min_filter: filter(self.min_filter, defaults.min_filter),
+            mipmap_filter: self
+                .mipmap_filter
+                .unwrap_or_else(|| default_filter(defaults.mipmap_filter)),
+            address_mode_u: address_mode(self.address_mode_u, defaults.address_mode_u),
+            address_mode_v: address_mode(self.address_mode_v, defaults.address_mode_v),
+            anisotropy_clamp: anisotropy,
+            ..defaults
+        };
+        let filters = [
+            descriptor.mag_filter,
+            descriptor.min_filter,
+            descriptor.mipmap_filter,
+        ];
+        if anisotropy > 1
+            && filters
+                .iter()
+                .any(|filter| matches!(filter, ImageFilterMode::Nearest))
+        {
+            return Err(SamplerError::AnisotropicNearest);
+        }
+        Ok(descriptor)
+    }
+}
+
+/// The images of each texture of an atlas being built in one of its channels, which are packed into page images of
+/// their own with the same layouts as the atlas's pages.
+struct ChannelTextures {
+    name: String,
+    format: TextureFormat,
+    fill: [u8; 4],
+    textures: Vec<Image>,
+}
+
+impl ChannelTextures {
+    /// Creates the channel called `name`, with no textures yet, whose images are the channel's variant of
+    /// `atlas_format`.
+    fn new(name: &str, config: &ChannelConfig, atlas_format: TextureFormat) -> Self {
+        Self {
+            name: name.to_owned(),
+            format: packer::with_srgb(atlas_format, config.is_srgb),
+            fill: config.fill(name),
+            textures: Vec::new(),
+        }
+    }
+}
+
+/// The options applied to each page image of a built atlas, which keep the values the image was created with unless
+/// they are set.
+#[derive(Default, Clone)]
+struct PageOptions {
+    asset_usage: Option<RenderAssetUsages>,
+    sampler: Option<ImageSampler>,
+    mipmaps: bool,
+    premultiply_alpha: bool,
+    lods: u32,
+}
+
+impl PageOptions {
+    /// Returns `true` if none of the options are set.
+    fn is_empty(&self) -> bool {
+        self.asset_usage.is_none()
+            && self.sampler.is_none()
+            && !self.mipmaps
+            && !self.premultiply_alpha
+            && self.lods == 0
+    }
+
+    /// Applies the options that are set to a page image.
+    fn apply(&self, image: &mut Image) {
+        if let Some(asset_usage) = self.asset_usage {
+            image.asset_usage = asset_usage;
+        }
+        if let Some(sampler) = self.sampler.as_ref() {
+            image.sampler = sampler.clone();
+        }
+        // Mips are averaged from the premultiplied colors
+        if self.premultiply_alpha && !packer::premultiply_alpha(image) {
+            warn!(
+                "Unable to premultiply the alpha of an atlas image with the format {:?}",
+                image.texture_descriptor.format
+            );
+        }
+        if self.mipmaps {
+            generate_mipmaps(image, self.premultiply_alpha);
+        }
+    }
+
+    /// Returns the downscaled copies of a page image that the options were applied to.
+    fn lods(&self, image: &Image) -> Vec<Image> {
+        generate_lods(image, self.lods, self.premultiply_alpha, self.mipmaps)
+    }
+}
+
+/// Returns `levels` copies of an atlas image at 1/2, 1/4, and so on of its size, whose colors may be `premultiplied` by
+/// their alpha, each with its own mip levels if `mipmaps` is set. Warns and returns no copies if the image's format
+/// doesn't support it.
+fn generate_lods(image: &Image, levels: u32, premultiplied: bool, mipmaps: bool) -> Vec<Image> {
+    let mut lods: Vec<Image> = Vec::with_capacity(levels as usize);
+    for _ in 0..levels {
+        let Some(lod) = packer::half_size(lods.last().unwrap_or(image), premultiplied) else {
+            warn!(
+                "Unable to generate levels of detail for an atlas image with the format {:?}",
+                image.texture_descriptor.format
+            );
+            return Vec::new();
+        };
+        lods.push(lod);
+    }
+    if mipmaps {
+        for lod in lods.iter_mut() {
+            generate_mipmaps(lod, premultiplied);
+        }
+    }
+    lods
+}
+
+/// Generates mip levels for an atlas image whose colors may be `premultiplied` by their alpha, warning if its format
+/// doesn't support it.
+fn generate_mipmaps(image: &mut Image, premultiplied: bool) {
+    if !packer::generate_mipmaps(image, premultiplied) {
+        warn!(
+            "Unable to generate mipmaps for an atlas image with the format {:?}",
+            image.texture_descriptor.format
+        );
+    }
+}
+
+/// Slices a single sprite sheet image into a grid of sub-textures, rather than packing individual images. See
+/// [`TextureAtlasLayout::from_grid`] for details on how the grid is laid out.
+#[derive(Debug, Clone, Deserialize)]
+struct GridConfig {
+    path: PathBuf,
+    tile_size: UVec2,
+    columns: u32,
+    rows: u32,
+    #[serde(default)]
+    padding: Option<UVec2>,
+    #[serde(default)]
+    offset: Option<UVec2>,
+    /// Optional names for the grid's tiles, in row-major order.
+    #[serde(default)]
+    names: Vec<String>,
+}
+
+/// Rasterizes the glyphs of a TrueType or OpenType font into textures of the atlas. Each glyph is named and described
+/// like the glyphs of a BMFont (see [`BmFontGlyph`](crate::bmfont::BmFontGlyph)).
+#[cfg(feature = "font")]
+#[derive(Debug, Clone, Deserialize)]
+struct FontConfig {
+    path: PathBuf,
+    /// The size of the font in pixels per em.
+    size: f32,
+    /// The characters to rasterize, which default to printable ASCII if neither these nor any `ranges` are given.
+    #[serde(default)]
+    characters: Option<String>,
+    /// Inclusive ranges of characters to rasterize alongside `characters`, such as `('\u{400}', '\u{4ff}')`.
+    #[serde(default)]
+    ranges: Vec<(char, char)>,
+}
+
+#[cfg(feature = "font")]
+impl FontConfig {
+    /// Returns every character to rasterize, which may repeat.
+    fn characters(&self) -> impl Iterator<Item = char> + '_ {
+        let ascii = (self.characters.is_none() && self.ranges.is_empty()).then_some(' '..='~');
+        self.ranges
+            .iter()
+            .flat_map(|&(start, end)| start..=end)
+            .chain(
+                self.characters
+                    .iter()
+                    .flat_map(|characters| characters.chars()),
+            )
+            .chain(ascii.into_iter().flatten())
+    }
+}
+
+impl TextureAtlasBuildLoader {
+    /// Builds an atlas from a parsed `.atlas.ron` config, including its animations, with the labels of its sub-assets
+    /// prefixed by `prefix`.
+    async fn build_with_animations(
+        &self,
+        mut config: BuildLoaderConfig,
+        archive: Option<Archive>,
+        prefix: &str,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<TextureAtlasAsset, BuildLoaderError> {
+        let animations = std::mem::take(&mut config.animations);
+        let premultiplied_alpha = config.premultiply_alpha.unwrap_or(false);
+        let distance_field = config.distance_field;
+        let mut atlas = self.build(config, archive, prefix, load_context).await?;
+        atlas.premultiplied_alpha = premultiplied_alpha;
+        atlas.distance_field = distance_field;
+
+        let mut resolved = Vec::with_capacity(animations.len());
+        for (name, animation) in animations {
+            match animation.resolve(&atlas.paths) {
+                Ok(animation) => resolved.push((name, animation)),
+                Err(FrameError::Unknown(frame)) => {
+                    return Err(BuildLoaderError::UnknownAnimationFrame {
+                        animation: name,
+                        frame,
+                    })
+                }
+                Err(FrameError::InvalidDuration(duration)) => {
+                    return Err(BuildLoaderError::InvalidFrameDuration {
+                        animation: name,
+                        duration,
+                    })
+                }
+            }
+        }
+        trace!("Adding {} animations", resolved.len());
+        atlas.add_animations_with_prefix(load_context, prefix, resolved);
+        Ok(atlas)
+    }
+
+    /// Builds each of the named atlases in `config.atlases` as a labeled sub-asset of an atlas with no pages of its
+    /// own.
+    async fn build_atlases(
+        &self,
+        config: BuildLoaderConfig,
+        archive: Option<Archive>,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<TextureAtlasAsset, BuildLoaderError> {
+        if !config.textures.is_empty()
+            || !config.include.is_empty()
+            || config.directory.is_some()
+            || config.grid.is_some()
+            || config.font.is_some()
+            || config.archive.is_some()
+            || !config.animations.is_empty()
+        {
+            return Err(BuildLoaderError::ConflictingAtlases);
+        }
+
+        let mut atlases = HashMap::new();
+        for (name, mut atlas_config) in config.atlases {
+            if !atlas_config.atlases.is_empty() {
+                return Err(BuildLoaderError::NestedAtlases(name));
+            }
+            if !atlas_config.resolutions.is_empty() {
+                return Err(BuildLoaderError::ConflictingResolutions);
+            }
+            atlas_config.sprite_assets = atlas_config.sprite_assets.or(config.sprite_assets);
+            atlas_config.asset_usage = atlas_config.asset_usage.or(config.asset_usage);
+            atlas_config.is_srgb = atlas_config.is_srgb.or(config.is_srgb);
+            atlas_config.pixel_format = atlas_config.pixel_format.or(config.pixel_format);
+            atlas_config.dither = atlas_config.dither.or(config.dither);
+            atlas_config.mipmaps = atlas_config.mipmaps.or(config.mipmaps);
+            atlas_config.lods = atlas_config.lods.or(config.lods);
+            atlas_config.distance_field = atlas_config.distance_field.or(config.distance_field);
+            if atlas_config.normal_suffix.is_none() {
+                atlas_config.normal_suffix.clone_from(&config.normal_suffix);
+            }
+            if atlas_config.channels.is_empty() {
+                atlas_config.channels.clone_from(&config.channels);
+            }
+            atlas_config.premultiply_alpha =
+                atlas_config.premultiply_alpha.or(config.premultiply_alpha);
+            if atlas_config.sampler.is_none() {
+                atlas_config.sampler.clone_from(&config.sampler);
+            }
+            atlas_config.max_concurrent_loads = atlas_config
+                .max_concurrent_loads
+                .or(config.max_concurrent_loads);
+            atlas_config.max_build_memory =
+                atlas_config.max_build_memory.or(config.max_build_memory);
+            atlas_config.incremental |= config.incremental;
+            trace!("Building named atlas: {name:?}");
+            let prefix = atlas_prefix(Some(&name));
+            let atlas = self
+                .build_with_animations(atlas_config, archive.clone(), &prefix, load_context)
+                .await?;
+            let handle = load_context.add_labeled_asset(name.clone(), atlas);
+            atlases.insert(name, handle);
+        }
+        debug!("Built {} named texture atlases", atlases.len());
+        Ok(TextureAtlasAsset::from_atlases(atlases))
+    }
+
+    /// Builds a variant of the atlas for each of `config.resolutions` as a labeled sub-asset of an atlas with no pages
+    /// of its own, with the textures of each variant scaled from `config.source_resolution`.
+    async fn build_resolutions(
+        &self,
+        config: BuildLoaderConfig,
+        archive: Option<Archive>,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<TextureAtlasAsset, BuildLoaderError> {
+        if !config.atlases.is_empty() {
+            return Err(BuildLoaderError::ConflictingResolutions);
+        }
+        let source_resolution = config
+            .source_resolution
+            .unwrap_or_else(|| config.resolutions.iter().copied().fold(0.0, f32::max));
+        let invalid = config
+            .resolutions
+            .iter()
+            .chain([&source_resolution])
+            .find(|resolution| !resolution.is_finite() || **resolution <= 0.0);
+        if let Some(&resolution) = invalid {
+            return Err(BuildLoaderError::InvalidResolution(resolution));
+        }
+
+        let mut atlases = HashMap::new();
+        for &resolution in config.resolutions.iter() {
+            let name = resolution_name(resolution);
+            let mut variant_config = config.clone();
+            variant_config.resolutions.clear();
+            variant_config.scale =
+                Some(config.scale.unwrap_or(1.0) * resolution / source_resolution);
+            trace!("Building atlas resolution variant: {name:?}");
+            let prefix = atlas_prefix(Some(&name));
+            let atlas = self
+                .build_with_animations(variant_config, archive.clone(), &prefix, load_context)
+                .await?;
+            let handle = load_context.add_labeled_asset(name.clone(), atlas);
+            atlases.insert(name, handle);
+        }
+        debug!("Built {} atlas resolution variants", atlases.len());
+        Ok(TextureAtlasAsset::from_atlases(atlases))
+    }
+
+    /// Builds an atlas from a parsed `.atlas.ron` config, without its animations. If `archive` is given, textures
+    /// are read from it rather than the asset source or the config's own `archive`.
+    async fn build(
+        &self,
+        mut config: BuildLoaderConfig,
+        mut archive: Option<Archive>,
+        prefix: &str,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<TextureAtlasAsset, BuildLoaderError> {
+        config.apply_base_dir();
+        if config.relative_paths.unwrap_or(true) {
+            config.resolve_paths(load_context.asset_path(), archive.is_some())?;
+        }
+        if !config.include.is_empty() {
+            if archive.is_some() || config.archive.is_some() {
+                return Err(BuildLoaderError::UnsupportedArchiveOption("include"));
+            }
+            let mut stack = vec![load_context.asset_path().clone_owned()];
+            let mut included = Vec::new();
+            self.include_textures(&mut config, &mut stack, &mut included)
+                .await?;
+            // Dependencies are only recorded once all includes are read, as the processor waits for each one to be
+            // processed and would otherwise never finish an include cycle
+            for path in &included {
+                add_dependency(path, load_context).await;
+            }
+        }
+        if let Some(path) = config.archive.as_ref() {
+            if archive.is_some() {
+                return Err(BuildLoaderError::NestedArchive);
+            }
+            let path = config_asset_path(path);
+            archive = Some(Archive::open(&self.readers, path.clone()).await?);
+            add_dependency(&path, load_context).await;
+        }
+        if let Some(field) = config.distance_field {
+            if !field.range.is_finite() || field.range <= 0.0 {
+                return Err(BuildLoaderError::InvalidDistanceRange(field.range));
+            }
+        }
+        config.merge_channels();
+        if let Some(name) = config
+            .channels
+            .keys()
+            .find(|name| !is_valid_channel_name(name))
+        {
+            return Err(BuildLoaderError::InvalidChannelName(name.clone()));
+        }
+        if let Some(name) = config.duplicate_packed_map() {
+            return Err(BuildLoaderError::DuplicatePackedMap(name.to_owned()));
+        }
+        if !config.channels.is_empty() {
+            if config.distance_field.is_some() {
+                return Err(BuildLoaderError::UnsupportedChannelOption("distance_field"));
+            }
+            if config.array.is_some() {
+                return Err(BuildLoaderError::UnsupportedChannelOption("array"));
+            }
+            if config.gpu_composite {
+                return Err(BuildLoaderError::UnsupportedChannelOption("gpu_composite"));
+            }
+        }
+        if let Some(grid) = config.grid.as_ref() {
+            if !config.textures.is_empty() || config.directory.is_some() || config.font.is_some() {
+                return Err(BuildLoaderError::ConflictingSources);
+            }
+            if config.array.is_some() {
+                return Err(BuildLoaderError::UnsupportedGridOption("array"));
+            }
+            if config.distance_field.is_some() {
+                return Err(BuildLoaderError::UnsupportedGridOption("distance_field"));
+            }
+            if !config.channels.is_empty() {
+                return Err(BuildLoaderError::UnsupportedGridOption("channels"));
+            }
+            return Self::load_grid(
+                grid,
+                archive.as_mut(),
+                config.sprite_assets,
+                config.color_key,
+                &config.page_options()?,
+                prefix,
+                load_context,
+            )
+            .await;
+        }
+        let gpu_compositions = match (config.gpu_composite, self.gpu_compositions.as_ref()) {
+            (false, _) => None,
+            // Processed atlases are saved from their pages on the CPU, which are left blank by GPU compositing
+            (true, _) if self.readers.is_processor() => {
+                debug!(
+                    "Compositing the atlas \"{}\" on the CPU, as it may be processed",
+                    load_context.asset_path()
+                );
+                None
+            }
+            (true, Some(gpu_compositions)) => {
+                if config.extrude > 0 {
+                    return Err(BuildLoaderError::UnsupportedGpuOption("extrude"));
+                }
+                if config.array.is_some() {
+                    return Err(BuildLoaderError::UnsupportedGpuOption("array"));
+                }
+                if config.sprite_assets.is_some() {
+                    return Err(BuildLoaderError::UnsupportedGpuOption("sprite_assets"));
+                }
+                if config.incremental {
+                    return Err(BuildLoaderError::UnsupportedGpuOption("incremental"));
+                }
+                if config.mipmaps == Some(true) {
+                    return Err(BuildLoaderError::UnsupportedGpuOption("mipmaps"));
+                }
+                if config.premultiply_alpha == Some(true) {
+                    return Err(BuildLoaderError::UnsupportedGpuOption("premultiply_alpha"));
+                }
+                if config.lods.is_some_and(|lods| lods > 0) {
+                    return Err(BuildLoaderError::UnsupportedGpuOption("lods"));
+                }
+                Some(gpu_compositions)
+            }
+            (true, None) => {
+                warn!(
+                    "Compositing the atlas \"{}\" on the CPU, as there is no renderer",
+                    load_context.asset_path()
+                );
+                None
+            }
+        };
+        config
+            .collect_textures(&self.readers, archive.as_ref())
+            .await?;
+        trace!(
+            "Building texture atlas with {} textures",
+            config.textures.len()
+        );
+        // Glyphs are rasterized up front and added after the other textures, with their images already loaded
+        let glyphs: Vec<(BuildTextureEntry, Image)> = match config.font.as_ref() {
+            #[cfg(feature = "font")]
+            Some(font) => {
+                let field = config.distance_field;
+                Self::load_font(font, field, archive.as_mut(), load_context).await?
+            }
+            #[cfg(not(feature = "font"))]
+            Some(_) => return Err(BuildLoaderError::UnsupportedFont),
+            None => Vec::new(),
+        };
+        let glyph_count = glyphs.len();
+        let mut glyph_images = Vec::with_capacity(glyph_count);
+        for (entry, image) in glyphs {
+            glyph_images.push((config.textures.len(), Ok(LoadedAsset::from(image))));
+            config.textures.push(entry);
+        }
+        let duplicates = config.duplicate_textures();
+        if !duplicates.is_empty() {
+            if !config.allow_duplicates {
+                return Err(BuildLoaderError::DuplicateTextures(duplicates));
+            }
+            warn!(
+                "The atlas \"{}\" has several textures with the same {}",
+                load_context.asset_path(),
+                duplicates.join(", ")
+            );
+        }
+
+        let config_path = load_context.asset_path().clone_owned();
+        let on_error = match (config.on_error, config.placeholder()) {
+            (Some(on_error), _) => on_error,
+            (None, Some(_)) => ErrorPolicy::Placeholder,
+            (None, None) => self.on_error,
+        };
+        let atlas_format = config
+            .pixel_format
+            .unwrap_or_default()
+            .texture_format(config.is_srgb.unwrap_or(config.distance_field.is_none()));
+        let mut texture_assets = Vec::with_capacity(config.textures.len());
+        // The info of textures copied from other atlases, which are each only loaded once
+        let mut copied_infos = Vec::with_capacity(config.textures.len());
+        let mut source_atlases = HashMap::new();
+        // Every texture is loaded before failing, so that all missing textures are reported at once
+        let mut failures = Vec::new();
+        let mut skipped = Vec::new();
+        let mut placeholder = None;
+        let mut preloaded = match archive {
+            Some(_) => HashMap::new(),
+            None => {
+                let limit = config
+                    .max_concurrent_loads
+                    .unwrap_or(self.max_concurrent_loads);
+                let track = self.readers.tracks_dependencies();
+                Self::load_images(&config.textures, limit, track, load_context).await
+            }
+        };
+        preloaded.extend(glyph_images);
+        for (index, entry) in config.textures.iter().enumerate() {
+            let loaded = match preloaded.remove(&index) {
+                Some(loaded) => loaded.map(|texture| (texture, None)),
+                None => {
+                    Self::load_entry(entry, archive.as_mut(), &mut source_atlases, load_context)
+                        .await
+                }
+            };
+            let error = match loaded {
+                Ok((texture, copied)) => {
+                    copied_infos.push(copied);
+                    texture_assets.push(texture);
+                    continue;
+                }
+                Err(error) => error,
+            };
+            let name = entry.name().unwrap_or_default();
+            match on_error {
+                ErrorPolicy::Fail => failures.push(BuildLoaderError::Texture {
+                    index,
+                    name,
+                    error: Box::new(error),
+                }),
+                ErrorPolicy::Skip => {
+                    warn!("Skipping texture {index} ({name:?}) of {config_path}: {error}");
+                    skipped.push(index);
+                }
+                ErrorPolicy::Placeholder => {
+                    warn!("Using a placeholder for texture {index} ({name:?}) of {config_path}: {error}");
+                    let image = Self::placeholder(
+                        &mut placeholder,
+                        config.placeholder(),
+                        atlas_format,
+                        archive.as_mut(),
+                        load_context,
+                    )
+                    .await?;
+                    copied_infos.push(None);
+                    texture_assets.push(LoadedAsset::from(image));
+                }
+            }
+        }
+        if failures.len() > 1 {
+            return Err(BuildLoaderError::Textures(failures));
+        }
+        if let Some(failure) = failures.pop() {
+            return Err(failure);
+        }
+        // Skipped textures are left out of the atlas entirely
+        let mut index = 0;
+        config.textures.retain(|_| {
+            index += 1;
+            !skipped.contains(&(index - 1))
+        });
+
+        // Inline and copied textures have no path of their own, so they are referred to by their name within the config
+        let entry_path = |entry: &BuildTextureEntry| match entry.path() {
+            Some(path) => config_asset_path(path),
+            None => config_path
+                .clone()
+                .with_label(entry.name().unwrap_or_default()),
+        };
+        let mut entries = Vec::with_capacity(texture_assets.len());
+        let mut textures: Vec<Cow<Image>> = Vec::with_capacity(texture_assets.len());
+        for (entry, texture) in config.textures.iter().zip(texture_assets) {
+            let texture = texture.take();
+            let format = texture.texture_descriptor.format;
+            let texture = if format == atlas_format {
+                Cow::Owned(texture)
+            } else {
+                trace!("Converting sub-texture from {format:?} to {atlas_format:?}");
+                let dither = config.dither.unwrap_or(false);
+                Cow::Owned(
+                    packer::convert_dithered(texture, atlas_format, dither).ok_or_else(|| {
+                        BuildLoaderError::TextureFormat(entry_path(entry), format)
+                    })?,
+                )
+            };
+            let path = entry.path().map(config_asset_path);
+            entries.push((path, entry.name()));
+            textures.push(texture);
+        }
+        let texture_memory = |entries: &[BuildTextureEntry], textures: &[Cow<Image>]| {
+            entries
+                .iter()
+                .zip(textures.iter())
+                .map(|(entry, texture)| (entry_path(entry), texture.data.len() as u64))
+                .collect::<Vec<_>>()
+        };
+        check_build_memory(
+            config.max_build_memory,
+            &config_path,
+            || texture_memory(&config.textures, &textures),
+            0,
+        )?;
+
+        let mut infos: Vec<TextureInfo> = config
+            .textures
+            .iter()
+            .zip(copied_infos)
+            .map(|(entry, copied)| {
+                let info = entry.info();
+                match copied {
+                    // Copied textures keep the options of their original, unless they set their own
+                    Some(copied) => TextureInfo {
+                        rotated: false,
+                        trim: copied.trim,
+                        pivot: info.pivot.or(copied.pivot),
+                        slices: info.slices.or(copied.slices),
+                        meta: info.meta.or(copied.meta),
+                    },
+                    None => info,
+                }
+            })
+            .collect();
+        if let Some(key) = config.color_key {
+            if !packer::is_rgba8(atlas_format) {
+                warn!("Unable to apply the color key to an atlas with the format {atlas_format:?}");
+            } else {
+                for texture in textures.iter_mut() {
+                    if let Some(keyed) = packer::color_key(texture, key) {
+                        *texture = Cow::Owned(keyed);
+                    }
+                }
+            }
+        }
+        for ((texture, entry), info) in textures
+            .iter_mut()
+            .zip(config.textures.iter())
+            .zip(infos.iter_mut())
+        {
+            let source_size = texture.size();
+            let Some((size, filter)) = entry.resize(source_size, config.scale.unwrap_or(1.0))
+            else {
+                continue;
+            };
+            trace!(
+                "Resizing texture {:?} from {source_size} to {size}",
+                entry.path()
+            );
+            // SVG images are rasterized again at their new size rather than resized, so that they stay sharp
+            #[cfg(feature = "svg")]
+            let rasterized = match entry.path() {
+                Some(path) if svg::is_svg(path) => {
+                    let rasterized =
+                        Self::rasterize_svg(path, size, archive.as_mut(), load_context).await;
+                    rasterized
+                        .and_then(|image| packer::convert(image, atlas_format))
+                        .map(|image| match config.color_key {
+                            Some(key) => packer::color_key(&image, key).unwrap_or(image),
+                            None => image,
+                        })
+                }
+                _ => None,
+            };
+            #[cfg(not(feature = "svg"))]
+            let rasterized = None;
+            *texture = Cow::Owned(match rasterized {
+                Some(image) => image,
+                None => packer::resize(texture, size, filter.into()),
+            });
+            // Slices are given in pixels of the original image, so they are scaled along with it
+            if let Some(slices) = info.slices.as_mut() {
+                let scale = size.as_vec2() / source_size.as_vec2();
+                let resize = |value: u32, scale: f32| (value as f32 * scale).round() as u32;
+                slices.left = resize(slices.left, scale.x);
+                slices.right = resize(slices.right, scale.x);
+                slices.top = resize(slices.top, scale.y);
+                slices.bottom = resize(slices.bottom, scale.y);
+            }
+        }
+        // Channel images are loaded once their textures have their final size, and are then trimmed, rotated, and
+        // packed along with them
+        let mut channels: Vec<ChannelTextures> = config
+            .channels
+            .iter()
+            .map(|(name, channel)| ChannelTextures::new(name, channel, atlas_format))
+            .collect();
+        for channel in channels.iter_mut() {
+            self.load_channel(
+                &config,
+                channel,
+                &textures,
+                on_error,
+                archive.as_mut(),
+                load_context,
+            )
+            .await?;
+        }
+        if let Some(field) = config.distance_field {
+            // Glyphs are the last textures, and were already generated as distance fields from their outlines
+            let count = textures.len() - glyph_count;
+            let padding = field.padding();
+            for (texture, info) in textures.iter_mut().zip(infos.iter_mut()).take(count) {
+                *texture = Cow::Owned(sdf::from_image(texture, field).ok_or_else(|| {
+                    let format = texture.texture_descriptor.format;
+                    BuildLoaderError::DistanceFieldFormat(format)
+                })?);
+                if let Some(slices) = info.slices.as_mut() {
+                    slices.left += padding;
+                    slices.right += padding;
+                    slices.top += padding;
+                    slices.bottom += padding;
+                }
+            }
+        }
+        if config.trim {
+            for (index, (texture, info)) in textures.iter_mut().zip(infos.iter_mut()).enumerate() {
+                let source_size = texture.size();
+                // Fully transparent textures are trimmed down to a single pixel
+                let bounds = packer::opaque_bounds(texture).unwrap_or_else(|| {
+                    URect::from_corners(UVec2::ZERO, source_size.min(UVec2::ONE))
+                });
+                if bounds.size() != source_size {
+                    *texture = Cow::Owned(packer::crop(texture, bounds));
+                    for channel in channels.iter_mut() {
+                        channel.textures[index] = packer::crop(&channel.textures[index], bounds);
+                    }
+                    info.trim = Some(TextureTrim {
+                        offset: bounds.min,
+                        source_size,
+                    });
+                }
+            }
+        }
+
+        trace!("Packing atlas");
+        let settings = config.pack_settings();
+        let too_large = |size: UVec2| {
+            let rotated_fits = settings.allow_rotation && size.yx().cmple(settings.max_size).all();
+            size.cmpgt(settings.max_size).any() && !rotated_fits
+        };
+        // Textures that can never fit in the atlas are also handled by the error policy
+        let mut index = 0;
+        while index < textures.len() {
+            let size = textures[index].size();
+            if !too_large(size) {
+                index += 1;
+                continue;
+            }
+            let error = BuildLoaderError::TextureTooLarge {
+                path: entry_path(&config.textures[index]),
+                size,
+                max_size: settings.max_size,
+            };
+            let name = config.textures[index].name().unwrap_or_default();
+            match on_error {
+                ErrorPolicy::Fail => return Err(error),
+                ErrorPolicy::Skip => {
+                    warn!("Skipping texture {index} ({name:?}) of {config_path}: {error}");
+                    config.textures.remove(index);
+                    entries.remove(index);
+                    textures.remove(index);
+                    infos.remove(index);
+                    for channel in channels.iter_mut() {
+                        channel.textures.remove(index);
+                    }
+                }
+                ErrorPolicy::Placeholder => {
+                    warn!("Using a placeholder for texture {index} ({name:?}) of {config_path}: {error}");
+                    let image = Self::placeholder(
+                        &mut placeholder,
+                        config.placeholder(),
+                        atlas_format,
+                        archive.as_mut(),
+                        load_context,
+                    )
+                    .await?;
+                    if too_large(image.size()) {
+                        return Err(error);
+                    }
+                    for channel in channels.iter_mut() {
+                        channel.textures[index] =
+                            packer::filled(image.size(), channel.fill, channel.format);
+                    }
+                    textures[index] = Cow::Owned(image);
+                    infos[index] = TextureInfo::default();
+                    index += 1;
+                }
+            }
+        }
+        let sizes: Vec<UVec2> = textures.iter().map(|texture| texture.size()).collect();
+        let page_options = config.page_options()?;
+        let texture_path = |index: usize| entry_path(&config.textures[index]);
+        if config.array == Some(ArrayLayers::Textures) {
+            let layer_size = sizes
+                .iter()
+                .fold(UVec2::ONE, |size, &layer| size.max(layer));
+            check_build_memory(
+                config.max_build_memory,
+                &config_path,
+                || texture_memory(&config.textures, &textures),
+                textures.len() as u64 * image_memory(layer_size, atlas_format),
+            )?;
+            return Ok(Self::build_texture_array(
+                entries,
+                infos,
+                &textures,
+                config.sprite_assets,
+                &page_options,
+                prefix,
+                load_context,
+            ));
+        }
+
+        // Identical textures are only packed once, and each of their entries shares the packed rect. Their images in each
+        // channel must be identical as well
+        let originals: Vec<usize> = if config.deduplicate {
+            let mut seen = HashMap::new();
+            textures
+                .iter()
+                .enumerate()
+                .map(|(index, texture)| {
+                    let channel_data: Vec<&[u8]> = channels
+                        .iter()
+                        .map(|channel| channel.textures[index].data.as_slice())
+                        .collect();
+                    *seen
+                        .entry((texture.size(), texture.data.as_slice(), channel_data))
+                        .or_insert(index)
+                })
+                .collect()
+        } else {
+            (0..textures.len()).collect()
+        };
+        let unique: Vec<usize> = (0..textures.len())
+            .filter(|&index| originals[index] == index)
+            .collect();
+        if unique.len() < textures.len() {
+            trace!(
+                "Deduplicated {} identical sub-textures",
+                textures.len() - unique.len()
+            );
+        }
+        let unique_sizes: Vec<UVec2> = unique.iter().map(|&index| sizes[index]).collect();
+
+        // Incremental atlases reuse the layout of their last build if it was packed from the same sizes and settings,
+        // which always gives the same layout
+        let cache_key = config.incremental.then(|| format!("{config_path}{prefix}"));
+        let layout_hash = cache::hash((
+            format!("{settings:?}"),
+            &unique_sizes,
+            config.array,
+            atlas_format,
+        ));
+        let texture_hashes: Vec<u64> = match cache_key {
+            Some(_) => unique
+                .iter()
+                .map(|&index| cache::hash_image(&textures[index]))
+                .collect(),
+            None => Vec::new(),
+        };
+        let mut cached = cache_key
+            .as_ref()
+            .and_then(|key| self.cache.take(key))
+            .filter(|cached| cached.layout == layout_hash);
+        // Whether each unique texture differs from the one in the same place in the last build
+        let changed: Vec<bool> = match cached.as_ref() {
+            Some(cached) => texture_hashes
+                .iter()
+                .zip(cached.textures.iter())
+                .map(|(hash, cached)| hash != cached)
+                .collect(),
+            None => vec![true; unique.len()],
+        };
+        let packing = match cached.as_mut() {
+            Some(cached) => {
+                trace!(
+                    "Reusing the previous atlas layout, with {} changed sub-textures",
+                    changed.iter().filter(|&&changed| changed).count()
+                );
+                std::mem::take(&mut cached.packing)
+            }
+            None => packer::pack(&unique_sizes, &settings).map_err(|index| {
+                BuildLoaderError::NotEnoughSpace {
+                    path: texture_path(unique[index]),
+                    max_size: settings.max_size,
+                }
+            })?,
+        };
+        if config.max_build_memory.is_some() {
+            let page_memory: u64 = packing
+                .iter()
+                .map(|packing| image_memory(packing.size, atlas_format))
+                .sum();
+            // Array pages are copied into layers of the size of the largest page
+            let array_memory = match config.array {
+                Some(ArrayLayers::Pages) => {
+                    let layer_size = packing
+                        .iter()
+                        .fold(UVec2::ONE, |size, packing| size.max(packing.size));
+                    packing.len() as u64 * image_memory(layer_size, atlas_format)
+                }
+                _ => 0,
+            };
+            // Incremental atlases also keep a copy of their pages in the cache
+            let cached_memory = if config.incremental { page_memory } else { 0 };
+            let channel_memory = channels.len() as u64 * page_memory;
+            check_build_memory(
+                config.max_build_memory,
+                &config_path,
+                || texture_memory(&config.textures, &textures),
+                page_memory + array_memory + cached_memory + channel_memory,
+            )?;
+        }
+        // The record of this build, which is kept in the cache once the atlas is built
+        let mut build = cache_key.map(|key| {
+            let build = CachedBuild {
+                layout: layout_hash,
+                textures: texture_hashes,
+                packing: packing.clone(),
+                pages: Vec::new(),
+                images: Vec::new(),
+                encoded: cached.as_mut().and_then(|cached| cached.encoded.take()),
+            };
+            (key, build)
+        });
+
+        trace!("Finalizing atlas");
+        let mut paths = TextureAtlasPaths::default();
+        let mut pages = Vec::with_capacity(packing.len());
+        let mut page_copies = Vec::new();
+        let mut channel_pages = vec![Vec::new(); channels.len()];
+        for (page, packing) in packing.into_iter().enumerate() {
+            if page > 0 {
+                paths.start_page();
+            }
+            let mut placed = HashMap::new();
+            for ((&index, &rect), &rotated) in packing
+                .indices
+                .iter()
+                .zip(packing.rects.iter())
+                .zip(packing.rotated.iter())
+            {
+                let changed = changed[index];
+                let index = unique[index];
+                // Unchanged textures are already in the cached page
+                if rotated && changed {
+                    trace!("Rotating sub-texture {:?}", entries[index].0);
+                    textures[index] = Cow::Owned(packer::rotate(&textures[index]));
+                }
+                placed.insert(index, (rect, rotated));
+            }
+
+            let mut rects = Vec::with_capacity(placed.len());
+            for (index, original) in originals.iter().enumerate() {
+                let Some(&(rect, rotated)) = placed.get(original) else {
+                    continue;
+                };
+                let (path, name) = entries[index].clone();
+                trace!(
+                    "Adding sub-texture with index {} to page {page}: {path:?}",
+                    rects.len()
+                );
+                let info = TextureInfo {
+                    rotated,
+                    ..infos[index].clone()
+                };
+                paths.add(path, name, info);
+                rects.push(rect);
+            }
+
+            let texture = if gpu_compositions.is_some() {
+                // Each texture is moved into its own sub-asset, to be copied into the blank page once both are uploaded
+                let copies = packing
+                    .indices
+                    .iter()
+                    .zip(packing.rects.iter())
+                    .map(|(&index, rect)| {
+                        let index = unique[index];
+                        let mut texture = std::mem::take(&mut textures[index]).into_owned();
+                        texture.texture_descriptor.usage |= TextureUsages::COPY_SRC;
+                        let label = page_label(page, &format!("source{index}"));
+                        let texture =
+                            load_context.add_labeled_asset(format!("{prefix}{label}"), texture);
+                        (texture, rect.min)
+                    })
+                    .collect();
+                page_copies.push(copies);
+                packer::blank(packing.size, atlas_format)
+            } else if let Some(cached) = cached.as_mut() {
+                let mut texture = std::mem::take(&mut cached.pages[page]);
+                for (&index, &rect) in packing.indices.iter().zip(packing.rects.iter()) {
+                    if changed[index] {
+                        let texture_index = unique[index];
+                        packer::place(&mut texture, &textures[texture_index], rect, config.extrude);
+                    }
+                }
+                texture
+            } else {
+                let page_textures: Vec<&Image> = packing
+                    .indices
+                    .iter()
+                    .map(|&index| textures[unique[index]].as_ref())
+                    .collect();
+                packer::composite(&packing, atlas_format, &page_textures, config.extrude)
+            };
+            // Channels are always packed from scratch, as they aren't kept in the cache of incremental atlases
+            for (channel, channel_pages) in channels.iter().zip(channel_pages.iter_mut()) {
+                let page_textures: Vec<Cow<Image>> = packing
+                    .indices
+                    .iter()
+                    .zip(packing.rotated.iter())
+                    .map(|(&index, &rotated)| {
+                        let texture = &channel.textures[unique[index]];
+                        match rotated {
+                            true => Cow::Owned(packer::rotate(texture)),
+                            false => Cow::Borrowed(texture),
+                        }
+                    })
+                    .collect();
+                let page_textures: Vec<&Image> = page_textures.iter().map(AsRef::as_ref).collect();
+                channel_pages.push(packer::composite(
+                    &packing,
+                    channel.format,
+                    &page_textures,
+                    config.extrude,
+                ));
+            }
+            let layout = TextureAtlasLayout {
+                size: packing.size,
+                textures: rects,
+            };
+            pages.push((layout, texture));
+        }
+
+        debug!(
+            "Built texture atlas containing {} sub-textures on {} pages",
+            paths.path_indices.len(),
+            paths.page_count(),
+        );
+        if let Some((_, build)) = build.as_mut() {
+            build.pages = pages.iter().map(|(_, texture)| texture.clone()).collect();
+        }
+        if config.array == Some(ArrayLayers::Pages) {
+            // Every layer of an array texture has the same size, so smaller pages are padded out to the largest
+            let layer_size = pages
+                .iter()
+                .fold(UVec2::ONE, |size, (layout, _)| size.max(layout.size));
+            let images: Vec<&Image> = pages.iter().map(|(_, texture)| texture).collect();
+            let mut array = packer::layers(&images, layer_size)
+                .expect("atlas pages should share the atlas format");
+            page_options.apply(&mut array);
+            if let Some((key, mut build)) = build {
+                build.images = vec![cache::hash_image(&array)];
+                self.cache.insert(key, build);
+            }
+            let lods = page_options.lods(&array);
+            let layouts = pages.into_iter().map(|(layout, _)| TextureAtlasLayout {
+                size: layer_size,
+                ..layout
+            });
+            let mut atlas = TextureAtlasAsset::from_array(
+                load_context,
+                prefix,
+                layouts,
+                array.into(),
+                ArrayLayers::Pages,
+                paths,
+                config.sprite_assets,
+            );
+            atlas.add_lods(load_context, prefix, [lods]);
+            return Ok(atlas);
+        }
+        for (_, texture) in pages.iter_mut() {
+            page_options.apply(texture);
+        }
+        // The alpha of a channel isn't always coverage, so channels are never premultiplied, and have no downscaled
+        // copies
+        let channel_options = PageOptions {
+            premultiply_alpha: false,
+            lods: 0,
+            ..page_options.clone()
+        };
+        for texture in channel_pages.iter_mut().flatten() {
+            channel_options.apply(texture);
+        }
+        let lods: Vec<Vec<Image>> = pages
+            .iter()
+            .map(|(_, texture)| page_options.lods(texture))
+            .collect();
+        if let Some((key, mut build)) = build {
+            build.images = pages
+                .iter()
+                .map(|(_, texture)| texture)
+                .chain(channel_pages.iter().flatten())
+                .map(cache::hash_image)
+                .collect();
+            self.cache.insert(key, build);
+        }
+        let pages = pages
+            .into_iter()
+            .map(|(layout, texture)| (layout, texture.into()));
+        let mut atlas = TextureAtlasAsset::from_pages_with_prefix(
+            load_context,
+            prefix,
+            pages,
+            paths,
+            config.sprite_assets,
+        );
+        atlas.add_lods(load_context, prefix, lods);
+        for (channel, channel_pages) in channels.into_iter().zip(channel_pages) {
+            atlas.add_channel(load_context, prefix, &channel.name, channel_pages);
+        }
+        if let Some(gpu_compositions) = gpu_compositions {
+            for (page, copies) in atlas.pages.iter().zip(page_copies) {
+                gpu_compositions.push(GpuComposition {
+                    page: page.texture.clone(),
+                    copies,
+                });
+            }
+        }
+        Ok(atlas)
+    }
+
+    /// Builds a 2D array texture with each texture in the top left corner of its own layer, rather than packing them.
+    fn build_texture_array(
+        entries: Vec<(Option<AssetPath<'static>>, Option<String>)>,
+        infos: Vec<TextureInfo>,
+        textures: &[Cow<Image>],
+        sprites: Option<SpriteAssets>,
+        page_options: &PageOptions,
+        prefix: &str,
+        load_context: &mut LoadContext<'_>,
+    ) -> TextureAtlasAsset {
+        let layer_size = textures
+            .iter()
+            .fold(UVec2::ONE, |size, texture| size.max(texture.size()));
+        let layer_textures: Vec<&Image> = textures.iter().map(AsRef::as_ref).collect();
+        let mut array = match packer::layers(&layer_textures, layer_size) {
+            Some(array) => array,
+            // There are no textures, so the array gets a single empty layer
+            None => packer::layers(&[&Image::transparent()], layer_size)
+                .expect("a single layer should always be valid"),
+        };
+        page_options.apply(&mut array);
+
+        let mut paths = TextureAtlasPaths::default();
+        let mut rects = Vec::with_capacity(entries.len());
+        for (((path, name), info), texture) in entries.into_iter().zip(infos).zip(textures) {
+            trace!("Adding sub-texture to layer {}: {path:?}", rects.len());
+            paths.add(path, name, info);
+            rects.push(URect::from_corners(UVec2::ZERO, texture.size()));
+        }
+        let layout = TextureAtlasLayout {
+            size: layer_size,
+            textures: rects,
+        };
+
+        debug!(
+            "Built texture array atlas containing {} sub-textures",
+            paths.path_indices.len()
+        );
+        let lods = page_options.lods(&array);
+        let mut atlas = TextureAtlasAsset::from_array(
+            load_context,
+            prefix,
+            [layout],
+            array.into(),
+            ArrayLayers::Textures,
+            paths,
+            sprites,
+        );
+        atlas.add_lods(load_context, prefix, [lods]);
+        atlas
+    }
+
+    /// Loads the image of a texture entry, along with the [`TextureInfo`] of the original if it is copied from another
+    /// atlas.
+    async fn load_entry(
+        entry: &BuildTextureEntry,
+        archive: Option<&mut Archive>,
+        source_atlases: &mut HashMap<AssetPath<'static>, LoadedAsset<TextureAtlasAsset>>,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<(LoadedAsset<Image>, Option<TextureInfo>), BuildLoaderError> {
+        let texture = match (entry.path(), entry.data(), entry.atlas()) {
+            (None, None, Some(atlas)) => {
+                let name = entry.name().unwrap_or_default();
+                trace!("Copying atlas sub-texture {name:?} from: {atlas:?}");
+                let (texture, info) =
+                    Self::copy_texture(source_atlases, atlas, &name, load_context).await?;
+                return Ok((texture, Some(info)));
+            }
+            (Some(path), ..) => {
+                trace!("Loading atlas sub-texture from: {path:?}");
+                Self::load_image(path, archive, load_context).await?
+            }
+            (None, data, _) => {
+                let name = entry.name().unwrap_or_default();
+                trace!("Loading inline atlas sub-texture: {name:?}");
+                Self::load_inline(&name, data.unwrap_or_default(), load_context).await?
+            }
+        };
+        Ok((texture, None))
+    }
+    /// Loads the image of every texture entry with a path concurrently, at most `limit` at a time, returning
+    /// each result by the index of its entry. Each image is loaded in its own context so that they can be loaded at
+    /// the same time. Only loads made in `load_context` itself record their image as a dependency of the atlas, so if
+    /// `track` is set the images are loaded one at a time in it instead.
+    async fn load_images(
+        entries: &[BuildTextureEntry],
+        limit: usize,
+        track: bool,
+        load_context: &mut LoadContext<'_>,
+    ) -> HashMap<usize, Result<LoadedAsset<Image>, BuildLoaderError>> {
+        let paths: Vec<(usize, AssetPath<'static>)> = entries
+            .iter()
+            .enumerate()
+            .filter_map(|(index, entry)| Some((index, config_asset_path(entry.path()?))))
+            .collect();
+        // SVG images are rasterized by the build loader itself rather than loaded as assets, so they're left to
+        // `load_entry`
+        #[cfg(feature = "svg")]
+        let paths: Vec<_> = paths
+            .into_iter()
+            .filter(|(_, path)| !svg::is_svg(path.path()))
+            .collect();
+        if track {
+            let mut images = HashMap::new();
+            for (index, path) in paths {
+                trace!("Loading atlas sub-texture from: {path}");
+                let image = load_context.loader().immediate().load::<Image>(path).await;
+                images.insert(index, image.map_err(BuildLoaderError::from));
+            }
+            return images;
+        }
+
+        let context: &LoadContext = load_context;
+        let mut loads = Vec::with_capacity(paths.len());
+        for (index, path) in paths {
+            loads.push(async move {
+                trace!("Loading atlas sub-texture from: {path}");
+                let image = context
+                    .begin_labeled_asset()
+                    .loader()
+                    .immediate()
+                    .load::<Image>(path)
+                    .await;
+                (index, image.map_err(BuildLoaderError::from))
+            });
+        }
+        join_bounded(loads, limit).await.into_iter().collect()
+    }
+
+    /// Rasterizes the glyphs of `font`, or generates distance fields of them if `field` is given, read from `archive` if
+    /// there is one. Returns the texture entry that describes each glyph along with its image.
+    #[cfg(feature = "font")]
+    async fn load_font(
+        font: &FontConfig,
+        field: Option<DistanceField>,
+        archive: Option<&mut Archive>,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Vec<(BuildTextureEntry, Image)>, BuildLoaderError> {
+        trace!("Rasterizing atlas glyphs from: {:?}", font.path);
+        let bytes = match archive {
+            Some(archive) => archive.read_file(&font.path)?,
+            None => {
+                load_context
+                    .read_asset_bytes(config_asset_path(&font.path))
+                    .await?
+            }
+        };
+        let (glyphs, missing) = font::rasterize(&bytes, font.size, font.characters(), field)
+            .map_err(|error| BuildLoaderError::Font(font.path.clone(), error))?;
+        if !missing.is_empty() {
+            warn!(
+                "The font {:?} has no glyphs for {} of the characters of {}",
+                font.path,
+                missing.len(),
+                load_context.asset_path()
+            );
+        }
+
+        let entries = glyphs
+            .into_iter()
+            .map(|glyph| {
+                let entry = NamedTextureEntry {
+                    name: Some(bmfont::glyph_name(glyph.character)),
+                    meta: ron::to_string(&glyph.metrics)
+                        .ok()
+                        .and_then(|meta| ron::from_str(&meta).ok()),
+                    ..default()
+                };
+                (BuildTextureEntry::Named(Box::new(entry)), glyph.image)
+            })
+            .collect();
+        Ok(entries)
+    }
+
+    /// Loads the image in `channel` of each texture entry of `config`, from `archive` if there is one, converted to the
+    /// channel's format and resized to the size of the entry's image in `textures`. Entries without an image in the
+    /// channel are filled with the channel's `fill`, as are entries whose image fails to load unless `on_error` is
+    /// [`ErrorPolicy::Fail`]. The channel's packed maps are then copied into their components of each image.
+    async fn load_channel(
+        &self,
+        config: &BuildLoaderConfig,
+        channel: &mut ChannelTextures,
+        textures: &[Cow<'_, Image>],
+        on_error: ErrorPolicy,
+        mut archive: Option<&mut Archive>,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<(), BuildLoaderError> {
+        let ChannelTextures {
+            name,
+            format,
+            fill,
+            textures: channel_textures,
+        } = channel;
+        let (format, fill) = (*format, *fill);
+        let channel_config = &config.channels[name.as_str()];
+        channel_textures.reserve(textures.len());
+        for (index, (entry, texture)) in config.textures.iter().zip(textures).enumerate() {
+            let size = texture.size();
+            let path = self
+                .find_channel_image(
+                    entry,
+                    name,
+                    channel_config.suffix.as_deref(),
+                    archive.as_deref(),
+                )
+                .await?;
+            let mut image = match path {
+                Some(path) => {
+                    trace!("Loading {name} image of atlas sub-texture from: {path:?}");
+                    let image = Self::load_channel_image(
+                        &path,
+                        format,
+                        size,
+                        archive.as_deref_mut(),
+                        load_context,
+                    )
+                    .await;
+                    match (image, on_error) {
+                        (Ok(image), _) => image,
+                        (Err(error), ErrorPolicy::Fail) => {
+                            return Err(BuildLoaderError::Channel {
+                                channel: name.clone(),
+                                index,
+                                name: entry.name().unwrap_or_default(),
+                                error: Box::new(error),
+                            });
+                        }
+                        (Err(error), _) => {
+                            let entry_name = entry.name().unwrap_or_default();
+                            warn!(
+                                "Using the {name} fill for texture {index} ({entry_name:?}) of {}: {error}",
+                                load_context.asset_path()
+                            );
+                            packer::filled(size, fill, format)
+                        }
+                    }
+                }
+                None => packer::filled(size, fill, format),
+            };
+            for (component, map) in channel_config.pack.maps() {
+                let path = self
+                    .find_channel_image(entry, &map.name, map.suffix.as_deref(), archive.as_deref())
+                    .await?;
+                let Some(path) = path else {
+                    continue;
+                };
+
+                trace!(
+                    "Loading {} map of atlas sub-texture from: {path:?}",
+                    map.name
+                );
+                let map_image = Self::load_channel_image(
+                    &path,
+                    format,
+                    size,
+                    archive.as_deref_mut(),
+                    load_context,
+                )
+                .await;
+                match (map_image, on_error) {
+                    (Ok(map_image), _) => packer::copy_component(&mut image, component, &map_image),
+                    (Err(error), ErrorPolicy::Fail) => {
+                        return Err(BuildLoaderError::Channel {
+                            channel: map.name.clone(),
+                            index,
+                            name: entry.name().unwrap_or_default(),
+                            error: Box::new(error),
+                        });
+                    }
+                    (Err(error), _) => {
+                        let entry_name = entry.name().unwrap_or_default();
+                        warn!(
+                            "Leaving the {} map out of the {name} image of texture {index} ({entry_name:?}) of {}: {error}",
+                            map.name,
+                            load_context.asset_path()
+                        );
+                    }
+                }
+            }
+            channel_textures.push(image);
+        }
+        Ok(())
+    }
+
+    /// Returns the path of the image of `entry` in the channel or packed map called `name`, which is either given by the
+    /// entry or found next to its texture by `suffix`, if it has one.
+    async fn find_channel_image(
+        &self,
+        entry: &BuildTextureEntry,
+        name: &str,
+        suffix: Option<&str>,
+        archive: Option<&Archive>,
+    ) -> Result<Option<PathBuf>, BuildLoaderError> {
+        Ok(match (entry.channel(name), suffix, entry.path()) {
+            (Some(path), ..) => Some(path.to_owned()),
+            (None, Some(suffix), Some(path)) => {
+                let path = channel_image_path(path, suffix);
+                self.file_exists(&path, archive).await?.then_some(path)
+            }
+            _ => None,
+        })
+    }
+
+    /// Loads the image of a channel or packed map at `path`, converted to `format` and resized to `size`.
+    async fn load_channel_image(
+        path: &Path,
+        format: TextureFormat,
+        size: UVec2,
+        archive: Option<&mut Archive>,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Image, BuildLoaderError> {
+        let mut image = Self::load_image(path, archive, load_context).await?.take();
+        let source = image.texture_descriptor.format;
+        // The pixels of the image are taken to be in the channel's color space, whichever one it was loaded as, so
+        // that data such as normals are kept as they are rather than converted like colors
+        image.texture_descriptor.format = packer::with_srgb(source, format.is_srgb());
+        let image = packer::convert(image, format)
+            .ok_or_else(|| BuildLoaderError::TextureFormat(config_asset_path(path), source))?;
+        Ok(match image.size() == size {
+            true => image,
+            false => packer::resize(&image, size, FilterType::Triangle),
+        })
+    }
+
+    /// Returns `true` if there is a file at `path`, within `archive` if there is one.
+    async fn file_exists(
+        &self,
+        path: &Path,
+        archive: Option<&Archive>,
+    ) -> Result<bool, BuildLoaderError> {
+        if let Some(archive) = archive {
+            return Ok(archive.files().iter().any(|file| file == path));
+        }
+        let (source, path) = split_source(path);
+        let exists = match self.readers.reader(source)?.read(&path).await {
+            Ok(_) => true,
+            Err(AssetReaderError::NotFound(_)) => false,
+            Err(error) => return Err(error.into()),
+        };
+        Ok(exists)
+    }
+
+    /// Loads the image at `path`, from `archive` if there is one.
+    async fn load_image(
+        path: &Path,
+        archive: Option<&mut Archive>,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<LoadedAsset<Image>, BuildLoaderError> {
+        #[cfg(feature = "svg")]
+        if svg::is_svg(path) {
+            return Self::load_svg(path, archive, load_context).await;
+        }
+        match archive {
+            Some(archive) => archive.load_image(load_context, path).await,
+            None => Ok(load_context
+                .loader()
+                .immediate()
+                .load::<Image>(config_asset_path(path))
+                .await?),
+        }
+    }
+
+    /// Rasterizes the SVG image at `path` at `size`, from `archive` if there is one. Returns `None` if the image can't
+    /// be read, such as a texture that was replaced by a placeholder, in which case its loaded image is resized instead.
+    #[cfg(feature = "svg")]
+    async fn rasterize_svg(
+        path: &Path,
+        size: UVec2,
+        archive: Option<&mut Archive>,
+        load_context: &mut LoadContext<'_>,
+    ) -> Option<Image> {
+        match Self::read_svg(path, archive, load_context).await {
+            Ok(svg) => Some(svg.render(size)),
+            Err(error) => {
+                debug!("Unable to rasterize {path:?} at {size}: {error}");
+                None
+            }
+        }
+    }
+
+    /// Rasterizes the SVG image at `path` at its own size, from `archive` if there is one. Warns about the unsupported
+    /// elements and features of the image that are skipped.
+    #[cfg(feature = "svg")]
+    async fn load_svg(
+        path: &Path,
+        archive: Option<&mut Archive>,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<LoadedAsset<Image>, BuildLoaderError> {
+        trace!("Rasterizing atlas sub-texture from: {path:?}");
+        let svg = Self::read_svg(path, archive, load_context).await?;
+        let skipped = svg.skipped();
+        if !skipped.is_empty() {
+            warn!(
+                "The SVG image {path:?} of {} has unsupported features that aren't drawn: {}",
+                load_context.asset_path(),
+                skipped.join(", ")
+            );
+        }
+        Ok(svg.render(svg.size(96.0)).into())
+    }
+
+    /// Reads and parses the SVG image at `path`, from `archive` if there is one.
+    #[cfg(feature = "svg")]
+    async fn read_svg(
+        path: &Path,
+        archive: Option<&mut Archive>,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<svg::Svg, BuildLoaderError> {
+        let bytes = match archive {
+            Some(archive) => archive.read_file(path)?,
+            None => {
+                load_context
+                    .read_asset_bytes(config_asset_path(path))
+                    .await?
+            }
+        };
+        svg::Svg::parse(&bytes).map_err(|error| BuildLoaderError::Svg(path.to_path_buf(), error))
+    }
+
+    /// Returns the image packed in place of textures that fail to load in the given atlas format, which is loaded or
+    /// created the first time it is needed and kept in `cache`.
+    async fn placeholder(
+        cache: &mut Option<Image>,
+        placeholder: Option<&PlaceholderConfig>,
+        atlas_format: TextureFormat,
+        archive: Option<&mut Archive>,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Image, BuildLoaderError> {
+        if let Some(image) = cache.as_ref() {
+            return Ok(image.clone());
+        }
+        let image = match placeholder {
+            Some(PlaceholderConfig::Path(path)) => {
+                trace!("Loading placeholder texture from: {path:?}");
+                let image = Self::load_image(path, archive, load_context)
+                    .await
+                    .map_err(|error| BuildLoaderError::Placeholder(Box::new(error)))?
+                    .take();
+                let format = image.texture_descriptor.format;
+                packer::convert(image, atlas_format).ok_or_else(|| {
+                    BuildLoaderError::TextureFormat(config_asset_path(path), format)
+                })?
+            }
+            _ => packer::checkerboard(16, 4, atlas_format),
+        };
+        *cache = Some(image.clone());
+        Ok(image)
+    }
+
+    /// Copies the texture called `name` out of the atlas at `path`, returning it upright along with its
+    /// [`TextureInfo`]. Each atlas is only loaded once, and is kept in `atlases`.
+    async fn copy_texture(
+        atlases: &mut HashMap<AssetPath<'static>, LoadedAsset<TextureAtlasAsset>>,
+        path: &Path,
+        name: &str,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<(LoadedAsset<Image>, TextureInfo), BuildLoaderError> {
+        let path = config_asset_path(path);
+        if path == *load_context.asset_path() {
+            return Err(BuildLoaderError::CopiedFromSelf(path));
+        }
+        if !atlases.contains_key(&path) {
+            let atlas = load_context
+                .loader()
+                .immediate()
+                .load::<TextureAtlasAsset>(path.clone())
+                .await?;
+            atlases.insert(path.clone(), atlas);
+        }
+        let atlas = &atlases[&path];
+
+        let unknown = || BuildLoaderError::UnknownCopiedTexture {
+            atlas: path.clone(),
+            name: name.to_owned(),
+        };
+        let location = atlas.get().location_of(name).ok_or_else(unknown)?;
+        let info = atlas
+            .get()
+            .paths
+            .info_at(location)
+            .cloned()
+            .unwrap_or_default();
+        let rect = atlas
+            .get_labeled(page_label(location.page, "layout"))
+            .and_then(|layout| layout.get::<TextureAtlasLayout>())
+            .and_then(|layout| layout.textures.get(location.index).copied())
+            .ok_or_else(unknown)?;
+        // Every page of an array atlas shares the same texture, with each texture or page in its own layer
+        let (texture_label, layer) = match atlas.get().array {
+            Some(ArrayLayers::Textures) => ("texture".to_owned(), location.index),
+            Some(ArrayLayers::Pages) => ("texture".to_owned(), location.page),
+            None => (page_label(location.page, "texture"), 0),
+        };
+        let image = atlas
+            .get_labeled(texture_label)
+            .and_then(|texture| texture.get::<Image>())
+            .ok_or_else(unknown)?;
+
+        let format = image.texture_descriptor.format;
+        if format.is_compressed() {
+            return Err(BuildLoaderError::TextureFormat(
+                path.clone().with_label(name.to_owned()),
+                format,
+            ));
+        }
+        // Imported atlases may have rects that were never checked against their images
+        let size = image.size();
+        if rect.min.cmpgt(rect.max).any()
+            || rect.max.cmpgt(size).any()
+            || layer as u32 >= image.texture_descriptor.size.depth_or_array_layers
+        {
+            return Err(BuildLoaderError::CopiedTextureOutOfBounds {
+                atlas: path.clone(),
+                name: name.to_owned(),
+                rect,
+                layer,
+                size,
+            });
+        }
+        let texture = packer::extract(image, rect, layer as u32, info.rotated);
+        Ok((texture.into(), info))
+    }
+
+    /// Decodes and loads the inline image `data` of the texture entry with the given `name`.
+    async fn load_inline(
+        name: &str,
+        data: &str,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<LoadedAsset<Image>, BuildLoaderError> {
+        let invalid = |reason: String| BuildLoaderError::InlineData(name.to_owned(), reason);
+        let (format, data) = match data.trim_start().strip_prefix("data:") {
+            Some(uri) => {
+                let (mime_type, data) = uri
+                    .split_once(";base64,")
+                    .ok_or_else(|| invalid("expected a base64 `data:` URI".into()))?;
+                let format = ImageFormat::from_mime_type(mime_type)
+                    .ok_or_else(|| invalid(format!("unsupported image type {mime_type:?}")))?;
+                (Some(format), data)
+            }
+            None => (None, data),
+        };
+        let data: String = data.split_ascii_whitespace().collect();
+        let bytes = BASE64_STANDARD
+            .decode(data)
+            .map_err(|error| invalid(error.to_string()))?;
+
+        // The image is given a path within the config's path so that its loader can be chosen by extension. It's loaded
+        // in a separate context, so that this path isn't recorded as a dependency that the processor can never find.
+        let extension = format.map_or("png", |format| format.to_file_extensions()[0]);
+        let image_path = AssetPath::from(load_context.path().join(format!("{name}.{extension}")));
+        let image = load_context
+            .begin_labeled_asset()
+            .loader()
+            .immediate()
+            // Load untyped to force asset loader detection based on path
+            .with_unknown_type()
+            .with_reader(&mut VecReader::new(bytes))
+            .with_settings(move |image_settings: &mut ImageLoaderSettings| {
+                image_settings.format = match format {
+                    Some(format) => ImageFormatSetting::Format(format),
+                    None => ImageFormatSetting::Guess,
+                };
+            })
+            .load(&image_path)
+            .await?;
+        image
+            .downcast::<Image>()
+            .map_err(|_| BuildLoaderError::ImageTypeMismatch(image_path))
+    }
+
+    /// Inserts the `textures` of each config in `config.include` before the config's own textures. Included configs
+    /// may include others in turn, and `stack` holds the paths of the configs currently being included. The path of
+    /// every included config is added to `included`.
+    async fn include_textures(
+        &self,
+        config: &mut BuildLoaderConfig,
+        stack: &mut Vec<AssetPath<'static>>,
+        included: &mut Vec<AssetPath<'static>>,
+    ) -> Result<(), BuildLoaderError> {
+        let mut textures = Vec::new();
+        for path in std::mem::take(&mut config.include) {
+            let path = config_asset_path(&path);
+            if stack.contains(&path) {
+                return Err(BuildLoaderError::IncludeCycle(path));
+            }
+
+            // Included configs are read from the unprocessed source, as their processed form is an atlas image
+            trace!("Including atlas config: {path}");
+            let reader = self.readers.reader(path.source().clone())?;
+            let mut bytes = Vec::new();
+            reader
+                .read(path.path())
+                .await?
+                .read_to_end(&mut bytes)
+                .await?;
+            let mut include = BuildLoaderConfig::parse(&bytes, path.path())
+                .map_err(|error| InvalidConfig::new(path.clone(), &bytes, error))?;
+            include.migrate(&path);
+            if include.archive.is_some() {
+                return Err(BuildLoaderError::UnsupportedArchiveOption("include"));
+            }
+            include.apply_base_dir();
+            if include.relative_paths.unwrap_or(true) {
+                include.resolve_paths(&path, false)?;
+            }
+
+            if !included.contains(&path) {
+                included.push(path.clone());
+            }
+            stack.push(path);
+            Box::pin(self.include_textures(&mut include, stack, included)).await?;
+            stack.pop();
+            textures.append(&mut include.textures);
+        }
+        textures.append(&mut config.textures);
+        config.textures = textures;
+        Ok(())
+    }
+
+    /// Loads a sprite sheet image and generates its layout from a [`GridConfig`], making pixels of the `color_key`
+    /// transparent if it is set.
+    async fn load_grid(
+        grid: &GridConfig,
+        archive: Option<&mut Archive>,
+        sprites: Option<SpriteAssets>,
+        color_key: Option<[u8; 3]>,
+        page_options: &PageOptions,
+        prefix: &str,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<TextureAtlasAsset, BuildLoaderError> {
+        trace!("Loading atlas sprite sheet from: {:?}", grid.path);
+        let mut texture = match archive {
+            Some(archive) => archive.load_image(load_context, &grid.path).await?,
+            None => {
+                load_context
+                    .loader()
+                    .immediate()
+                    .load::<Image>(config_asset_path(&grid.path))
+                    .await?
+            }
+        };
+        if let Some(key) = color_key {
+            let format = texture.get().texture_descriptor.format;
+            if !packer::is_rgba8(format) {
+                warn!("Unable to apply the color key to a sprite sheet with the format {format:?}");
+            } else if let Some(keyed) = packer::color_key(texture.get(), key) {
+                texture = keyed.into();
+            }
+        }
+        if !page_options.is_empty() {
+            let mut image = texture.take();
+            page_options.apply(&mut image);
+            texture = image.into();
+        }
+
+        let mut layout = TextureAtlasLayout::from_grid(
+            grid.tile_size,
+            grid.columns,
+            grid.rows,
+            grid.padding,
+            grid.offset,
+        );
+        layout.size = texture.get().size();
+
+        let mut paths = TextureAtlasPaths::default();
+        for index in 0..layout.textures.len() {
+            paths.add(None, grid.names.get(index).cloned(), TextureInfo::default());
+        }
+
+        debug!(
+            "Built texture atlas grid containing {} sub-textures",
+            paths.path_indices.len()
+        );
+        let lods = page_options.lods(texture.get());
+        let mut atlas = TextureAtlasAsset::from_pages_with_prefix(
+            load_context,
+            prefix,
+            [(layout, texture)],
+            paths,
+            sprites,
+        );
+        atlas.add_lods(load_context, prefix, [lods]);
+        Ok(atlas)
+    }
+}
+
+impl AssetLoader for TextureAtlasBuildLoader {
+    type Asset = TextureAtlasAsset;
+    type Settings = ();
+    type Error = BuildLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        &(): &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        // Read the config file, which may be packaged in an archive with its textures
+        debug!("Building texture atlas from {:?}", load_context.path());
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let (bytes, config_path, archive) = if Archive::is_archive(&bytes) {
+            let mut archive = Archive::new(load_context.asset_path().clone_owned(), bytes)?;
+            let config_path = archive
+                .files()
+                .into_iter()
+                .find(|path| {
+                    let name = path.to_string_lossy();
+                    path.iter().count() == 1
+                        && CONFIG_EXTENSIONS
+                            .iter()
+                            .any(|ext| name.ends_with(&format!(".{ext}")))
+                })
+                .ok_or(BuildLoaderError::MissingArchiveConfig)?;
+            trace!("Reading archived atlas config from: {config_path:?}");
+            (archive.read_file(&config_path)?, config_path, Some(archive))
+        } else {
+            (bytes, load_context.path().to_owned(), None)
+        };
+        let mut config = BuildLoaderConfig::parse(&bytes, &config_path).map_err(|error| {
+            InvalidConfig::new(load_context.asset_path().clone_owned(), &bytes, error)
+        })?;
+        config.migrate(load_context.asset_path());
+        if !config.resolutions.is_empty() {
+            return self.build_resolutions(config, archive, load_context).await;
+        }
+        if !config.atlases.is_empty() {
+            return self.build_atlases(config, archive, load_context).await;
+        }
+        self.build_with_animations(config, archive, "", load_context)
+            .await
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["atlas.ron", "atlas.json", "atlas.toml", "atlas.zip"]
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum SaverError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    IntoDynamicImage(#[from] IntoDynamicImageError),
+    #[error("Unable to save image with format: {0:?}")]
+    InvalidImageFormat(ImageFormat),
+    #[error(transparent)]
+    Image(std::io::Error),
+    #[error("Unable to get `TextureAtlasLayout` sub-asset.")]
+    MissingLayout,
+    #[error("Unable to get `Image` sub-asset.")]
+    MissingTexture,
+    #[error("Unable to stack atlas pages with differing texture formats.")]
+    MismatchedPages,
+    #[error("Unable to get `AtlasAnimation` sub-asset.")]
+    MissingAnimation,
+    #[error("Unable to get named `TextureAtlasAsset` sub-asset.")]
+    MissingAtlas,
+    #[cfg(feature = "ktx2")]
+    #[error("Unable to encode the atlas image as KTX2: {0}")]
+    Ktx2(String),
+    #[cfg(feature = "dds")]
+    #[error(transparent)]
+    Dds(#[from] ddsfile::Error),
+    #[cfg(feature = "webp")]
+    #[error("Unable to encode the atlas image as WebP: {0}")]
+    WebP(String),
+    #[error("Unable to save a multi-page, array, or multi-channel atlas with format: {0:?}")]
+    CompressedPages(ImageFormat),
+    #[error("Unable to save an atlas with the float format {0:?} in an 8-bit image format")]
+    FloatPixels(TextureFormat),
+    #[error("Unable to save an atlas with 16-bit pixels in an 8-bit image format other than Png")]
+    Rgba16Pixels,
+    #[cfg(feature = "hdr")]
+    #[error("Unable to save an atlas with the format {0:?} as an HDR image")]
+    HdrFormat(TextureFormat),
+    #[error(
+        "Unable to align the atlas textures to compressed blocks within the maximum atlas size."
+    )]
+    CompressedSize,
+    #[error(transparent)]
+    Ron(#[from] ron::Error),
+    #[error(transparent)]
+    Png(#[from] png::EncodingError),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SaverSettings {
+    /// The format of the saved atlas image. `ImageFormat::Ktx2` requires the `ktx2` feature, which encodes the
+    /// image as a Basis Universal UASTC texture configured by [`SaverSettings::ktx2`]. `ImageFormat::Dds` requires
+    /// the `dds` feature, which encodes the image as a BCn texture configured by [`SaverSettings::dds`].
+    ///
+    /// Both of these formats compress each 4x4 block of pixels separately, so the textures of the atlas are moved so
+    /// that each one starts on a block boundary and no two textures share a block. Only single-page atlases that are
+    /// not texture arrays and have no `channels` can be saved in these formats.
+    pub format: ImageFormat,
+    /// Settings used when [`SaverSettings::format`] is `ImageFormat::Ktx2`.
+    #[serde(default)]
+    pub ktx2: Ktx2Settings,
+    /// Settings used when [`SaverSettings::format`] is `ImageFormat::Dds`.
+    #[serde(default)]
+    pub dds: DdsSettings,
+    /// Settings used when [`SaverSettings::format`] is [`ImageFormat::Png`].
+    #[serde(default)]
+    pub png: PngSettings,
+    /// Settings used when [`SaverSettings::format`] is `ImageFormat::Jpeg`.
+    #[serde(default)]
+    pub jpeg: JpegSettings,
+    /// Settings used when [`SaverSettings::format`] is `ImageFormat::WebP`.
+    #[serde(default)]
+    pub webp: WebPSettings,
+}
+
+impl Default for SaverSettings {
+    fn default() -> Self {
+        Self {
+            format: ImageFormat::Png,
+            ktx2: default(),
+            dds: default(),
+            png: default(),
+            jpeg: default(),
+            webp: default(),
+        }
+    }
+}
+
+/// Settings for saving atlas images as KTX2, which requires the `ktx2` feature.
+///
+/// KTX2 atlases are stored as UASTC textures, which are transcoded to a format supported by the GPU when they are
+/// loaded and stay compressed in video memory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Ktx2Settings {
+    /// The strength of the rate-distortion optimization, which trades quality for a smaller supercompressed file.
+    /// Good values are within `0.2..=4.0`, and `None` disables it. Defaults to `Some(1.0)`.
+    pub rdo: Option<f32>,
+    /// Whether to supercompress the texture with zlib. Defaults to `true`.
+    pub supercompress: bool,
+}
+
+impl Default for Ktx2Settings {
+    fn default() -> Self {
+        Self {
+            rdo: Some(1.0),
+            supercompress: true,
+        }
+    }
+}
+
+/// Settings for saving atlas images as DDS, which requires the `dds` feature.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DdsSettings {
+    /// The block compressed format of the texture. Defaults to [`BcFormat::Bc7`].
+    pub format: BcFormat,
+}
+
+/// The block compressed formats that atlas images can be saved as with [`DdsSettings`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BcFormat {
+    /// 4 bits per pixel with at most one bit of alpha. The smallest format, best suited to opaque images.
+    Bc1,
+    /// 8 bits per pixel with a separate alpha channel.
+    Bc3,
+    /// 8 bits per pixel with the highest quality color and alpha.
+    #[default]
+    Bc7,
+}
+
+/// Settings for saving atlas images as PNG.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PngSettings {
+    /// How much effort is spent compressing the image. Defaults to [`PngCompression::Fast`].
+    pub compression: PngCompression,
+    /// Whether to store the atlas layout in a text chunk of the image, so that it can be loaded as a single
+    /// `.atlas.png` file by the [`AtlasPngLoader`]. Defaults to `false`.
+    ///
+    /// [`AtlasPngLoader`]: crate::atlas_png::AtlasPngLoader
+    pub embed_layout: bool,
+}
+
+/// The compression levels of PNG images saved with [`PngSettings`]. PNG compression is lossless, so these only trade
+/// encoding time against file size.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PngCompression {
+    /// The fastest compression, with the largest files.
+    #[default]
+    Fast,
+    /// A balance of encoding time and file size.
+    Balanced,
+    /// The slowest compression, with the smallest files.
+    Best,
+    /// A compression level within `1..=9`, with higher levels giving smaller files.
+    Level(u8),
+}
+
+impl PngCompression {
+    /// Sets the compression of a PNG encoder to this level.
+    fn configure<W: Write>(self, encoder: &mut png::Encoder<W>) {
+        match self {
+            Self::Fast => encoder.set_compression(png::Compression::Fast),
+            Self::Balanced => encoder.set_compression(png::Compression::Balanced),
+            Self::Best => encoder.set_compression(png::Compression::High),
+            Self::Level(level) => {
+                encoder.set_deflate_compression(png::DeflateCompression::Level(level.clamp(1, 9)))
+            }
+        }
+    }
+}
+
+impl From<PngCompression> for image::codecs::png::CompressionType {
+    fn from(compression: PngCompression) -> Self {
+        match compression {
+            PngCompression::Fast => Self::Fast,
+            PngCompression::Balanced => Self::Default,
+            PngCompression::Best => Self::Best,
+            PngCompression::Level(level) => Self::Level(level.clamp(1, 9)),
+        }
+    }
+}
+
+/// Settings for saving atlas images as JPEG, which requires the `jpeg` feature. JPEG images have no alpha channel, so
+/// transparent areas of the atlas are saved with their color channels only.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JpegSettings {
+    /// The quality of the image within `1..=100`, with higher values giving better looking and larger images.
+    /// Defaults to `75`.
+    pub quality: u8,
+}
+
+impl Default for JpegSettings {
+    fn default() -> Self {
+        Self { quality: 75 }
+    }
+}
+
+/// Settings for saving atlas images as WebP. Without the `webp` feature, WebP images can still be saved losslessly
+/// if the `bevy` feature `webp` is enabled, but these settings are ignored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebPSettings {
+    /// Whether the image is compressed losslessly. Defaults to `true`.
+    pub lossless: bool,
+    /// The quality of lossy images, or the compression effort of lossless images, within `0.0..=100.0`. Higher
+    /// values give better looking lossy images and smaller lossless images. Defaults to `80.0`.
+    pub quality: f32,
+}
+
+impl Default for WebPSettings {
+    fn default() -> Self {
+        Self {
+            lossless: true,
+            quality: 80.0,
+        }
+    }
+}
+
+/// An [`AssetSaver`] that saves a [`TextureAtlasAsset`] as a single image, with the [`LoaderSettings`] needed to load
+/// it again.
+#[derive(Default)]
+pub struct TextureAtlasSaver {
+    /// The builds of incremental atlases, whose last encoded image is reused when they are saved again unchanged.
+    cache: BuildCache,
+}
+
+impl AssetSaver for TextureAtlasSaver {
+    type Asset = TextureAtlasAsset;
+    type Settings = SaverSettings;
+    type OutputLoader = TextureAtlasLoader;
+    type Error = SaverError;
+
+    async fn save(
+        &self,
+        writer: &mut Writer,
+        asset: SavedAsset<'_, Self::Asset>,
+        settings: &Self::Settings,
+    ) -> Result<<Self::OutputLoader as AssetLoader>::Settings, Self::Error> {
+        if let Some(loader_settings) = self.stream_png(writer, &asset, settings).await? {
+            return Ok(loader_settings);
+        }
+        let (buffer, loader_settings) = Self::export(&asset, settings, Some(&self.cache))?;
+        writer.write_all(&buffer).await?;
+        Ok(loader_settings)
+    }
+}
+
+/// The size of the `IDAT` chunks of saved PNG images, and roughly how many bytes of pixels are compressed at a time
+/// when they are streamed to the writer.
+const PNG_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Encoded bytes waiting to be written to an asynchronous [`Writer`], which an encoder writes to synchronously.
+#[derive(Clone, Default)]
+struct PendingBytes(Arc<Mutex<Vec<u8>>>);
+
+impl PendingBytes {
+    /// Removes and returns the bytes written so far.
+    fn take(&self) -> Vec<u8> {
+        std::mem::take(&mut self.0.lock().unwrap())
+    }
+}
+
+impl Write for PendingBytes {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The largest atlas that textures are aligned within when saving block compressed images.
+#[cfg(any(feature = "ktx2", feature = "dds"))]
+const MAX_COMPRESSED_SIZE: u32 = 16384;
+
+impl TextureAtlasSaver {
+    /// Sets the cache of incremental atlas builds, which is shared with the build loader.
+    pub(crate) fn with_cache(cache: BuildCache) -> Self {
+        Self { cache }
+    }
+
+    /// Saves a single atlas in PNG format by compressing its pixels a few rows at a time and writing them to `writer`
+    /// as they are compressed, so that the encoded image is never held in memory in full. Returns the
+    /// [`LoaderSettings`] needed to load it again, or `None` without writing anything if the atlas has named
+    /// `atlases`, isn't saved as PNG, or was built incrementally and should reuse or cache its encoded image.
+    async fn stream_png(
+        &self,
+        writer: &mut Writer,
+        asset: &SavedAsset<'_, TextureAtlasAsset>,
+        settings: &SaverSettings,
+    ) -> Result<Option<LoaderSettings>, SaverError> {
+        let atlas = asset.get();
+        if !matches!(settings.format, ImageFormat::Png) || !atlas.atlases.is_empty() {
+            return Ok(None);
+        }
+        let (textures, layouts) = Self::pages(asset, atlas, "")?;
+        if !self.cache.is_empty() {
+            let images: Vec<u64> = textures
+                .iter()
+                .map(|&texture| cache::hash_image(texture))
+                .collect();
+            if self.cache.contains(&images) {
+                return Ok(None);
+            }
+        }
+
+        debug!("Exporting texture atlas");
+        let texture = Self::flatten(&textures)?;
+        let loader_settings = LoaderSettings {
+            format: Some(settings.format),
+            ..Self::describe(asset, atlas, "", &layouts)?
+        };
+        let layout = settings
+            .png
+            .embed_layout
+            .then(|| atlas_png::layout_chunk(&loader_settings))
+            .transpose()?;
+
+        trace!("Streaming atlas image to writer (Png format)");
+        let (pixels, size, depth) = Self::png_pixels(&texture)?;
+        let output = PendingBytes::default();
+        let mut png = Self::png_writer(output.clone(), size, depth, &settings.png, layout)?;
+        let row_size = size.x as usize * 4 * depth as usize / 8;
+        let rows = (PNG_CHUNK_SIZE / row_size.max(1)).max(1);
+        for pixels in pixels.chunks(row_size * rows) {
+            png.write_all(pixels)?;
+            writer.write_all(&output.take()).await?;
+        }
+        png.finish()?;
+        writer.write_all(&output.take()).await?;
+        debug!("Exported texture atlas");
+        Ok(Some(loader_settings))
+    }
+
+    /// Encodes the atlas image, returning it with the [`LoaderSettings`] needed to load it again. If `cache` is given,
+    /// atlases that were built incrementally reuse their last encoding when their images, layouts, and `settings` are
+    /// unchanged.
+    pub(crate) fn export(
+        asset: &SavedAsset<'_, TextureAtlasAsset>,
+        settings: &SaverSettings,
+        cache: Option<&BuildCache>,
+    ) -> Result<(Vec<u8>, LoaderSettings), SaverError> {
+        debug!("Exporting texture atlas");
+        let (buffer, loader_settings) = if asset.get().atlases.is_empty() {
+            let array = asset.get().array;
+            let (textures, layouts) = Self::pages(asset, asset.get(), "")?;
+            let cache = cache.filter(|cache| !cache.is_empty());
+            let images: Vec<u64> = match cache {
+                Some(_) => textures
+                    .iter()
+                    .map(|&texture| cache::hash_image(texture))
+                    .collect(),
+                None => Vec::new(),
+            };
+            let key = cache::hash(format!("{settings:?} {layouts:?} {array:?}"));
+            let (buffer, aligned) = match cache.and_then(|cache| cache.encoding(&images, key)) {
+                Some(encoding) => {
+                    trace!("Reusing the previous encoding of the atlas image");
+                    (encoding.buffer, encoding.aligned)
+                }
+                None => {
+                    let texture = Self::flatten(&textures)?;
+                    trace!(
+                        "Writing atlas image to buffer ({:?} format)",
+                        settings.format
+                    );
+                    let (buffer, aligned) = Self::encode(&texture, &layouts, array, settings)?;
+                    if let Some(cache) = cache {
+                        cache.set_encoding(&images, || CachedEncoding {
+                            key,
+                            buffer: buffer.clone(),
+                            aligned: aligned.clone(),
+                        });
+                    }
+                    (buffer, aligned)
+                }
+            };
+            // Block compressed images may have moved their textures, which the images of their channels would be left
+            // behind by
+            if aligned.is_some() && !Self::channels(asset.get()).is_empty() {
+                return Err(SaverError::CompressedPages(settings.format));
+            }
+            let layouts = match aligned.as_ref() {
+                Some(layout) => vec![layout],
+                None => layouts,
+            };
+            let loader_settings = LoaderSettings {
+                format: Some(settings.format),
+                ..Self::describe(asset, asset.get(), "", &layouts)?
+            };
+            (buffer, loader_settings)
+        } else {
+            Self::export_atlases(asset, settings)?
+        };
+
+        let buffer = if matches!(settings.format, ImageFormat::Png) && settings.png.embed_layout {
+            trace!("Embedding atlas layout in PNG image");
+            atlas_png::embed_layout(&buffer, &loader_settings)?
+        } else {
+            buffer
+        };
+
+        debug!("Exported texture atlas");
+        Ok((buffer, loader_settings))
+    }
+
+    /// Encodes the named atlases of an atlas as a single image, with the atlases stacked vertically in order of their
+    /// names.
+    fn export_atlases(
+        asset: &SavedAsset<'_, TextureAtlasAsset>,
+        settings: &SaverSettings,
+    ) -> Result<(Vec<u8>, LoaderSettings), SaverError> {
+        let mut names: Vec<&String> = asset.get().atlases.keys().collect();
+        names.sort();
+
+        let mut textures = Vec::with_capacity(names.len());
+        let mut all_layouts = Vec::new();
+        let mut atlases = BTreeMap::new();
+        for name in names {
+            trace!("Exporting named atlas {name:?}");
+            let prefix = atlas_prefix(Some(name));
+            let atlas = (asset.get_labeled::<TextureAtlasAsset, str>(name))
+                .ok_or(SaverError::MissingAtlas)?
+                .get();
+            let (page_textures, layouts) = Self::pages(asset, atlas, &prefix)?;
+            let texture = Self::flatten(&page_textures)?;
+            let atlas_settings = LoaderSettings {
+                size: Some(texture.size()),
+                ..Self::describe(asset, atlas, &prefix, &layouts)?
+            };
+            atlases.insert(name.clone(), atlas_settings);
+            all_layouts.extend(layouts);
+            textures.push(texture);
+        }
+
+        trace!("Stacking {} named atlases", textures.len());
+        let images: Vec<&Image> = textures.iter().map(AsRef::as_ref).collect();
+        let texture = packer::stack(&images).ok_or(SaverError::MismatchedPages)?;
+        let (buffer, aligned) = Self::encode(&texture, &all_layouts, None, settings)?;
+        // The image can't be split back into atlases if block compression moved their textures
+        if aligned.is_some() {
+            return Err(SaverError::CompressedPages(settings.format));
+        }
+
+        let loader_settings = LoaderSettings {
             format: Some(settings.format),
-            textures: paths
-                .path_indices
+            atlases,
+            ..default()
+        };
+        Ok((buffer, loader_settings))
+    }
+
+    /// Returns the images and page layouts of an atlas whose sub-assets are labeled with `prefix`. Array atlases have
+    /// a single image shared by every page, and the images of each channel of the atlas follow the page images.
+    fn pages<'a>(
+        asset: &'a SavedAsset<'_, TextureAtlasAsset>,
+        atlas: &TextureAtlasAsset,
+        prefix: &str,
+    ) -> Result<(Vec<&'a Image>, Vec<&'a TextureAtlasLayout>), SaverError> {
+        let page_count = atlas.paths.page_count();
+        let mut layouts = Vec::with_capacity(page_count);
+        let mut textures = Vec::with_capacity(page_count);
+        for page in 0..page_count {
+            layouts.push(
+                asset
+                    .get_labeled::<TextureAtlasLayout, str>(&format!(
+                        "{prefix}{}",
+                        page_label(page, "layout")
+                    ))
+                    .ok_or(SaverError::MissingLayout)?
+                    .get(),
+            );
+            // Every page of an array atlas shares the same texture
+            if atlas.array.is_none() || page == 0 {
+                textures.push(
+                    asset
+                        .get_labeled::<Image, str>(&format!(
+                            "{prefix}{}",
+                            page_label(page, "texture")
+                        ))
+                        .ok_or(SaverError::MissingTexture)?
+                        .get(),
+                );
+            }
+        }
+        for channel in Self::channels(atlas) {
+            for page in 0..page_count {
+                textures.push(
+                    asset
+                        .get_labeled::<Image, str>(&format!(
+                            "{prefix}{}",
+                            page_label(page, channel)
+                        ))
+                        .ok_or(SaverError::MissingTexture)?
+                        .get(),
+                );
+            }
+        }
+        Ok((textures, layouts))
+    }
+
+    /// Returns the names of the channels of an atlas in the order that their images are saved in.
+    fn channels(atlas: &TextureAtlasAsset) -> Vec<&str> {
+        let mut channels: Vec<&str> = atlas
+            .pages
+            .first()
+            .map(|page| page.channels.keys().map(String::as_str).collect())
+            .unwrap_or_default();
+        channels.sort();
+        channels
+    }
+
+    /// Returns the image of an atlas with the given page images, with multiple pages stacked vertically. Array
+    /// images are borrowed as they are, as their layers are already laid out as if they were stacked vertically.
+    fn flatten<'a>(textures: &[&'a Image]) -> Result<Cow<'a, Image>, SaverError> {
+        // Only the first mip level of each page is saved, as mips are generated again when the atlas is loaded.
+        // Multi-page atlases are saved as a single image with the pages stacked vertically, as are the layers of
+        // array atlases
+        Ok(match textures {
+            [texture] => packer::base_level(texture),
+            textures => {
+                trace!("Stacking {} atlas pages", textures.len());
+                let levels: Vec<Cow<Image>> = textures
+                    .iter()
+                    .map(|texture| packer::base_level(texture))
+                    .collect();
+                let images: Vec<&Image> = levels.iter().map(AsRef::as_ref).collect();
+                Cow::Owned(packer::stack(&images).ok_or(SaverError::MismatchedPages)?)
+            }
+        })
+    }
+
+    /// Returns the [`LoaderSettings`] describing an atlas whose sub-assets are labeled with `prefix` and whose pages
+    /// have the given `layouts`, without its `format`.
+    fn describe(
+        asset: &SavedAsset<'_, TextureAtlasAsset>,
+        atlas: &TextureAtlasAsset,
+        prefix: &str,
+        layouts: &[&TextureAtlasLayout],
+    ) -> Result<LoaderSettings, SaverError> {
+        let paths = &atlas.paths;
+        let texture = asset
+            .get_labeled::<Image, str>(&format!("{prefix}{}", page_label(0, "texture")))
+            .map(|texture| texture.get());
+        // Images are kept in both worlds, are sRGB, use the default sampler, and have no mips unless the atlas was
+        // built with its own `asset_usage`, `is_srgb`, `sampler`, or `mipmaps`
+        let asset_usage = texture
+            .map(|texture| texture.asset_usage)
+            .filter(|&asset_usage| asset_usage != RenderAssetUsages::default());
+        let is_srgb = texture
+            .map(|texture| texture.texture_descriptor.format.is_srgb())
+            .filter(|&is_srgb| !is_srgb);
+        let mipmaps = texture
+            .map(|texture| texture.texture_descriptor.mip_level_count > 1)
+            .filter(|&mipmaps| mipmaps);
+        let premultiplied_alpha = atlas.premultiplied_alpha.then_some(true);
+        let channels: Vec<LoaderChannel> = Self::channels(atlas)
+            .into_iter()
+            .map(|name| {
+                let image = asset
+                    .get_labeled::<Image, str>(&format!("{prefix}{name}"))
+                    .ok_or(SaverError::MissingTexture)?;
+                Ok(LoaderChannel {
+                    name: name.to_owned(),
+                    is_srgb: image.get().texture_descriptor.format.is_srgb(),
+                })
+            })
+            .collect::<Result<_, SaverError>>()?;
+        let lods = atlas
+            .pages
+            .first()
+            .map(|page| page.lods.len() as u32)
+            .filter(|&lods| lods > 0);
+        let sampler = texture.and_then(|texture| match &texture.sampler {
+            ImageSampler::Default => None,
+            ImageSampler::Descriptor(sampler) => Some(sampler.clone()),
+        });
+        let animations = atlas
+            .animations
+            .keys()
+            .map(|name| {
+                let animation = asset
+                    .get_labeled::<AtlasAnimation, str>(&format!(
+                        "{prefix}{}",
+                        animation_label(name)
+                    ))
+                    .ok_or(SaverError::MissingAnimation)?;
+                Ok((name.clone(), animation.get().clone()))
+            })
+            .collect::<Result<_, SaverError>>()?;
+
+        Ok(LoaderSettings {
+            format: None,
+            textures: layouts
                 .iter()
-                .cloned()
-                .zip(layout.textures.iter().cloned())
+                .enumerate()
+                .flat_map(|(page, layout)| {
+                    layout
+                        .textures
+                        .iter()
+                        .enumerate()
+                        .map(move |(index, &rect)| {
+                            let location = AtlasLocation { page, index };
+                            let info = paths.info_at(location).cloned().unwrap_or_default();
+                            LoaderTexture {
+                                rect,
+                                path: paths.path_at(location).cloned(),
+                                name: paths.name_at(location).map(str::to_owned),
+                                page,
+                                rotated: info.rotated,
+                                trim: info.trim,
+                                pivot: info.pivot,
+                                slices: info.slices,
+                                meta: info.meta,
+                            }
+                        })
+                })
                 .collect(),
+            pages: match layouts.len() {
+                1 if channels.is_empty() => Vec::new(),
+                _ => layouts.iter().map(|layout| layout.size).collect(),
+            },
+            array: atlas.array,
+            animations,
+            sprite_assets: if !atlas.sprite_images.is_empty() {
+                Some(SpriteAssets::Images)
+            } else if !atlas.sprite_regions.is_empty() {
+                Some(SpriteAssets::Regions)
+            } else {
+                None
+            },
+            size: None,
+            atlases: BTreeMap::new(),
+            validate: false,
+            asset_usage,
+            is_srgb,
+            sampler,
+            mipmaps,
+            premultiplied_alpha,
+            lods,
+            distance_field: atlas.distance_field,
+            channels,
         })
     }
+
+    /// Encodes the atlas image in [`SaverSettings::format`]. Block compressed formats also return the layout of the
+    /// image after its textures were aligned to blocks.
+    #[cfg_attr(not(any(feature = "ktx2", feature = "dds")), allow(unused_variables))]
+    fn encode(
+        texture: &Image,
+        layouts: &[&TextureAtlasLayout],
+        array: Option<ArrayLayers>,
+        settings: &SaverSettings,
+    ) -> Result<(Vec<u8>, Option<TextureAtlasLayout>), SaverError> {
+        match settings.format {
+            #[cfg(feature = "ktx2")]
+            ImageFormat::Ktx2 => {
+                let (pixels, layout, srgb) =
+                    Self::align_blocks(texture, layouts, array, settings.format)?;
+                let buffer = ktx2::encode(&pixels, layout.size, srgb, &settings.ktx2)?;
+                Ok((buffer, Some(layout)))
+            }
+            #[cfg(feature = "dds")]
+            ImageFormat::Dds => {
+                let (pixels, layout, srgb) =
+                    Self::align_blocks(texture, layouts, array, settings.format)?;
+                let buffer = dds::encode(&pixels, layout.size, srgb, &settings.dds)?;
+                Ok((buffer, Some(layout)))
+            }
+            #[cfg(feature = "webp")]
+            ImageFormat::WebP => {
+                let (pixels, size) = Self::rgba8_pixels(texture)?;
+                let buffer = webp::Encoder::from_rgba(&pixels, size.x, size.y)
+                    .encode_simple(settings.webp.lossless, settings.webp.quality)
+                    .map_err(|error| SaverError::WebP(format!("{error:?}")))?;
+                Ok((buffer.to_vec(), None))
+            }
+            ImageFormat::Png => {
+                let (pixels, size, depth) = Self::png_pixels(texture)?;
+                let output = PendingBytes::default();
+                let mut writer =
+                    Self::png_writer(output.clone(), size, depth, &settings.png, None)?;
+                writer.write_all(&pixels)?;
+                writer.finish()?;
+                Ok((output.take(), None))
+            }
+            #[cfg(feature = "hdr")]
+            ImageFormat::Hdr => {
+                let format = texture.texture_descriptor.format;
+                let pixels = packer::linear_pixels(texture).ok_or(SaverError::HdrFormat(format))?;
+                let size = texture.texture_descriptor.size;
+                // Radiance HDR images have no alpha channel, so it is dropped as each pixel is encoded
+                let pixels: Vec<image::Rgb<f32>> = pixels
+                    .iter()
+                    .map(|color| image::Rgb(color.truncate().to_array()))
+                    .collect();
+                let mut buffer = Vec::<u8>::new();
+                image::codecs::hdr::HdrEncoder::new(&mut buffer)
+                    .encode(
+                        &pixels,
+                        size.width as usize,
+                        (size.height * size.depth_or_array_layers) as usize,
+                    )
+                    .map_err(|err| SaverError::Image(std::io::Error::other(err)))?;
+                Ok((buffer, None))
+            }
+            #[cfg(feature = "jpeg")]
+            ImageFormat::Jpeg => {
+                let (pixels, size) = Self::rgba8_pixels(texture)?;
+                let mut buffer = Vec::<u8>::new();
+                let mut encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(
+                    &mut buffer,
+                    settings.jpeg.quality.clamp(1, 100),
+                );
+                // The alpha channel is dropped as each pixel is encoded
+                let image = image::ImageBuffer::<image::Rgba<u8>, _>::from_raw(
+                    size.x,
+                    size.y,
+                    pixels.as_ref(),
+                )
+                .expect("atlas pixels should fill the atlas image");
+                encoder
+                    .encode_image(&image)
+                    .map_err(|err| SaverError::Image(std::io::Error::other(err)))?;
+                Ok((buffer, None))
+            }
+            // Other formats are only available if their `bevy` features are enabled
+            #[allow(unreachable_patterns)]
+            format => {
+                let image_format = format
+                    .as_image_crate_format()
+                    .ok_or(SaverError::InvalidImageFormat(format))?;
+                let (pixels, size) = Self::rgba8_pixels(texture)?;
+                let mut buffer = Vec::<u8>::new();
+                let written = image::write_buffer_with_format(
+                    &mut std::io::Cursor::new(&mut buffer),
+                    &pixels,
+                    size.x,
+                    size.y,
+                    ExtendedColorType::Rgba8,
+                    image_format,
+                );
+                match written {
+                    Ok(()) => {}
+                    // Formats without RGBA8 support are converted to a color type they support instead
+                    Err(image::ImageError::Unsupported(_)) => {
+                        buffer.clear();
+                        let mut texture = texture.clone();
+                        packer::flatten_array(&mut texture);
+                        texture
+                            .try_into_dynamic()?
+                            .write_to(&mut std::io::Cursor::new(&mut buffer), image_format)
+                            .map_err(|err| SaverError::Image(std::io::Error::other(err)))?;
+                    }
+                    Err(err) => return Err(SaverError::Image(std::io::Error::other(err))),
+                }
+                Ok((buffer, None))
+            }
+        }
+    }
+
+    /// Writes the header of an RGBA PNG image of the given size and bit depth to `output`, followed by the atlas layout
+    /// chunk if any, and returns a writer for its pixels. The image is written to `output` in chunks of
+    /// [`PNG_CHUNK_SIZE`] bytes as its pixels are compressed.
+    fn png_writer<W: Write>(
+        output: W,
+        size: UVec2,
+        depth: png::BitDepth,
+        settings: &PngSettings,
+        layout: Option<ITXtChunk>,
+    ) -> Result<png::StreamWriter<'static, W>, SaverError> {
+        let mut info = png::Info::with_size(size.x, size.y);
+        info.utf8_text.extend(layout);
+        let mut encoder = png::Encoder::with_info(output, info)?;
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(depth);
+        settings.compression.configure(&mut encoder);
+        encoder.set_filter(png::Filter::Adaptive);
+        Ok(encoder
+            .write_header()?
+            .into_stream_writer_with_size(PNG_CHUNK_SIZE)?)
+    }
+
+    /// Returns the pixels of an atlas image as RGBA8, with the layers of array images stacked vertically, along with
+    /// the size of the stacked image. The pixels of RGBA8 images are borrowed rather than copied.
+    fn rgba8_pixels(texture: &Image) -> Result<(Cow<'_, [u8]>, UVec2), SaverError> {
+        let size = texture.texture_descriptor.size;
+        // Array layers are already laid out one after another, as if they were stacked vertically
+        let size = UVec2::new(size.width, size.height * size.depth_or_array_layers);
+        match texture.texture_descriptor.format {
+            TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => {
+                Ok((Cow::Borrowed(&texture.data), size))
+            }
+            format if packer::is_rgba_float(format) => Err(SaverError::FloatPixels(format)),
+            TextureFormat::Rgba16Unorm => Err(SaverError::Rgba16Pixels),
+            _ => {
+                let mut texture = texture.clone();
+                packer::flatten_array(&mut texture);
+                let pixels = texture.try_into_dynamic()?.into_rgba8().into_raw();
+                Ok((Cow::Owned(pixels), size))
+            }
+        }
+    }
+
+    /// Returns the pixels of an atlas image as they are written to a PNG image, along with the size of the stacked image
+    /// and the bit depth of the PNG. Atlases with 16-bit pixels keep their depth, and others are written as RGBA8.
+    fn png_pixels(texture: &Image) -> Result<(Cow<'_, [u8]>, UVec2, png::BitDepth), SaverError> {
+        if texture.texture_descriptor.format != TextureFormat::Rgba16Unorm {
+            let (pixels, size) = Self::rgba8_pixels(texture)?;
+            return Ok((pixels, size, png::BitDepth::Eight));
+        }
+        let size = texture.texture_descriptor.size;
+        let size = UVec2::new(size.width, size.height * size.depth_or_array_layers);
+        // PNG samples are big-endian
+        let pixels = texture
+            .data
+            .chunks_exact(2)
+            .flat_map(|sample| [sample[1], sample[0]])
+            .collect();
+        Ok((Cow::Owned(pixels), size, png::BitDepth::Sixteen))
+    }
+
+    /// Aligns the textures of a single-page atlas to 4x4 pixel blocks, returning the aligned image as RGBA8 pixels,
+    /// its layout, and whether it is in the sRGB color space.
+    #[cfg(any(feature = "ktx2", feature = "dds"))]
+    fn align_blocks(
+        texture: &Image,
+        layouts: &[&TextureAtlasLayout],
+        array: Option<ArrayLayers>,
+        format: ImageFormat,
+    ) -> Result<(Vec<u8>, TextureAtlasLayout, bool), SaverError> {
+        // Compressed images can't be split back into pages or layers when the atlas is loaded
+        let [layout] = layouts else {
+            return Err(SaverError::CompressedPages(format));
+        };
+        if array.is_some() {
+            return Err(SaverError::CompressedPages(format));
+        }
+
+        let srgb = texture.texture_descriptor.format.is_srgb();
+        let (image, textures) = packer::align_blocks(
+            texture,
+            &layout.textures,
+            4,
+            UVec2::splat(MAX_COMPRESSED_SIZE),
+        )
+        .ok_or(SaverError::CompressedSize)?;
+        let size = image.size();
+        let pixels = image.try_into_dynamic()?.into_rgba8().into_raw();
+        Ok((pixels, TextureAtlasLayout { size, textures }, srgb))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_texture_tuples() {
+        let settings: LoaderSettings = ron::from_str(
+            r#"(
+                textures: [
+                    (Some("images/icon.png"), (min: (0, 0), max: (16, 16))),
+                    (None, (min: (16, 0), max: (32, 8))),
+                    (rect: (min: (0, 16), max: (8, 24)), name: Some("dot"), rotated: true),
+                ],
+            )"#,
+        )
+        .unwrap();
+        let textures = &settings.textures;
+        assert_eq!(textures.len(), 3);
+        assert_eq!(textures[0].path, Some(AssetPath::from("images/icon.png")));
+        assert_eq!(textures[0].rect, URect::new(0, 0, 16, 16));
+        assert_eq!(textures[1].path, None);
+        assert_eq!(textures[1].rect, URect::new(16, 0, 32, 8));
+        assert_eq!(textures[2].name.as_deref(), Some("dot"));
+        assert!(textures[2].rotated);
+    }
 }
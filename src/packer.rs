@@ -0,0 +1,1704 @@
+//! Rectangle packing and image compositing used by [`TextureAtlasBuildLoader`].
+//!
+//! [`TextureAtlasBuildLoader`]: crate::loader::TextureAtlasBuildLoader
+
+use std::borrow::Cow;
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{
+            Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureViewDescriptor,
+            TextureViewDimension,
+        },
+        texture::TextureFormatPixelInfo,
+    },
+};
+use image::{imageops, imageops::FilterType, ImageBuffer, Rgba};
+use serde::Deserialize;
+
+/// The algorithms that [`pack`] can place textures with. These trade packing quality against build time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub(crate) enum PackingAlgorithm {
+    /// Tracks every maximal free rectangle, placing textures where they leave the least space on their shortest side.
+    /// The slowest algorithm, but usually produces the tightest packing.
+    #[default]
+    MaxRects,
+    /// Tracks the top edge of the placed textures, placing each texture as low as possible. A good balance of speed
+    /// and quality.
+    Skyline,
+    /// Places textures in the best fitting free rectangle and splits the remaining space in two.
+    Guillotine,
+    /// Places textures left to right in rows, starting a new row when the current ones are full. The fastest
+    /// algorithm, and works well for textures of similar heights.
+    RowShelf,
+}
+
+/// Configures how textures are packed by [`pack`].
+#[derive(Debug, Clone)]
+pub(crate) struct PackSettings {
+    /// The algorithm used to place textures.
+    pub algorithm: PackingAlgorithm,
+    /// Spacing added to the right and bottom of each texture.
+    pub padding: UVec2,
+    /// The number of pixels that each texture's edges are extruded outward.
+    pub extrude: u32,
+    /// The multiple of pixels that each texture's position is aligned to. The space taken up by each texture, including
+    /// its padding and extrusion, is also rounded up to this multiple, so that no two textures share an aligned block.
+    pub align: u32,
+    /// The size of the first atlas that packing is attempted in.
+    pub initial_size: UVec2,
+    /// The largest atlas that packing is attempted in.
+    pub max_size: UVec2,
+    /// Whether the atlas dimensions must be powers of two.
+    pub power_of_two: bool,
+    /// Whether textures that do not fit within `max_size` are placed on additional pages.
+    pub multi_page: bool,
+    /// Whether textures may be rotated 90° clockwise when they do not otherwise fit.
+    pub allow_rotation: bool,
+}
+
+impl Default for PackSettings {
+    fn default() -> Self {
+        Self {
+            algorithm: PackingAlgorithm::MaxRects,
+            padding: UVec2::ZERO,
+            extrude: 0,
+            align: 1,
+            initial_size: UVec2::splat(256),
+            max_size: UVec2::splat(2048),
+            power_of_two: false,
+            multi_page: false,
+            allow_rotation: false,
+        }
+    }
+}
+
+/// A single page of packed textures.
+#[derive(Debug, Clone)]
+pub(crate) struct Packing {
+    /// The size of the page.
+    pub size: UVec2,
+    /// The indices of the textures placed on this page, in the order the textures were given.
+    pub indices: Vec<usize>,
+    /// The area covered by each texture in `indices` (excluding extrusion and padding).
+    pub rects: Vec<URect>,
+    /// Whether each texture in `indices` was rotated 90° clockwise, in which case its rect is also rotated.
+    pub rotated: Vec<bool>,
+}
+
+/// Packs textures with the given sizes into atlas pages. Each page starts at [`PackSettings::initial_size`] and is
+/// doubled until all remaining textures fit. If they do not fit within [`PackSettings::max_size`], the textures that
+/// were placed form a page and the rest are packed into further pages if [`PackSettings::multi_page`] is set.
+/// Otherwise, the index of the first texture that could not be placed is returned.
+///
+/// Packing is deterministic: the same sizes and settings always produce the same pages, and the textures on each page
+/// are listed in the order they were given.
+///
+/// If [`PackSettings::power_of_two`] is set, the initial size is rounded up and the max size is rounded down to the
+/// nearest powers of two. If [`PackSettings::allow_rotation`] is set, textures that do not fit are rotated and placed
+/// again. Textures are only placed at multiples of [`PackSettings::align`], as the space each takes up is rounded up
+/// to a multiple of it.
+pub(crate) fn pack(sizes: &[UVec2], settings: &PackSettings) -> Result<Vec<Packing>, usize> {
+    // Every placed area starts and ends on a multiple of `align`, so textures are aligned by leaving room for their
+    // extrusion before them in whole multiples
+    let align = settings.align.max(1);
+    let lead = settings.extrude.next_multiple_of(align);
+    let border = UVec2::splat(lead + settings.extrude) + settings.padding;
+    let padded_size = |size: UVec2| (size + border).map(|x| x.next_multiple_of(align));
+    let padded: Vec<UVec2> = sizes.iter().map(|&size| padded_size(size)).collect();
+
+    // Placing large textures first gives much tighter packing. Ties are broken by index so that the order (and so the
+    // packing) only depends on the given sizes.
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by_key(|&index| {
+        let size = padded[index];
+        (
+            std::cmp::Reverse(size.max_element()),
+            std::cmp::Reverse(size.x * size.y),
+            index,
+        )
+    });
+
+    let (initial_size, max_size) = if settings.power_of_two {
+        (
+            settings.initial_size.map(|x| x.max(1).next_power_of_two()),
+            settings.max_size.map(prev_power_of_two),
+        )
+    } else {
+        (settings.initial_size.max(UVec2::ONE), settings.max_size)
+    };
+
+    let mut pages = Vec::new();
+    let mut size = initial_size.min(max_size);
+    loop {
+        trace!("Attempting to pack {} textures into {size}", order.len());
+        let mut bin = Bin::new(settings.algorithm, size);
+        let mut placed = Vec::with_capacity(order.len());
+        let mut unplaced = Vec::new();
+        for &index in order.iter() {
+            let texture_size = sizes[index];
+            let placement = bin.insert(padded[index]).map(|position| (position, false));
+            let placement = placement.or_else(|| {
+                let rotate = settings.allow_rotation && texture_size.x != texture_size.y;
+                rotate
+                    .then(|| bin.insert(padded_size(texture_size.yx())))
+                    .flatten()
+                    .map(|position| (position, true))
+            });
+            match placement {
+                Some((position, rotated)) => {
+                    let min = position + UVec2::splat(lead);
+                    let texture_size = if rotated {
+                        texture_size.yx()
+                    } else {
+                        texture_size
+                    };
+                    placed.push((index, URect::from_corners(min, min + texture_size), rotated));
+                }
+                // Only a full size page needs to know about every texture that doesn't fit
+                None if size == max_size && settings.multi_page => unplaced.push(index),
+                None => {
+                    unplaced.push(index);
+                    break;
+                }
+            }
+        }
+
+        if unplaced.is_empty() || size == max_size {
+            if !unplaced.is_empty() && (!settings.multi_page || placed.is_empty()) {
+                return Err(unplaced[0]);
+            }
+
+            placed.sort_by_key(|&(index, _, _)| index);
+            let mut packing = Packing {
+                size,
+                indices: Vec::with_capacity(placed.len()),
+                rects: Vec::with_capacity(placed.len()),
+                rotated: Vec::with_capacity(placed.len()),
+            };
+            for (index, rect, rotated) in placed {
+                packing.indices.push(index);
+                packing.rects.push(rect);
+                packing.rotated.push(rotated);
+            }
+            pages.push(packing);
+            if unplaced.is_empty() {
+                return Ok(pages);
+            }
+
+            trace!("Starting new atlas page for {} textures", unplaced.len());
+            order = unplaced;
+            size = initial_size.min(max_size);
+        } else {
+            size = (size * 2).min(max_size);
+        }
+    }
+}
+
+/// Copies each texture on a page into a new atlas image of the given `format` at its packed location, extruding its
+/// edge pixels outward by `extrude` pixels. `textures` must contain the textures in [`Packing::indices`] in the same
+/// order, and must already be in `format` and rotated as described by [`Packing::rotated`].
+pub(crate) fn composite(
+    packing: &Packing,
+    format: TextureFormat,
+    textures: &[&Image],
+    extrude: u32,
+) -> Image {
+    let mut atlas = blank(packing.size, format);
+    for (texture, &rect) in textures.iter().zip(packing.rects.iter()) {
+        place(&mut atlas, texture, rect, extrude);
+    }
+    atlas
+}
+
+/// Copies a texture into `atlas` over `rect`, extruding its edge pixels outward by `extrude` pixels. The texture must
+/// already be in the atlas's format and have the size of `rect`.
+pub(crate) fn place(atlas: &mut Image, texture: &Image, rect: URect, extrude: u32) {
+    let pixel_size = atlas.texture_descriptor.format.pixel_size();
+    let atlas_width = atlas.width() as usize;
+    let width = rect.width() as usize;
+    let height = rect.height() as usize;
+    if width == 0 || height == 0 {
+        return;
+    }
+
+    let extrude = extrude as usize;
+    let row_size = width * pixel_size;
+    let min_x = rect.min.x as usize - extrude;
+    let min_y = rect.min.y as usize - extrude;
+    for y in 0..height + extrude * 2 {
+        // Rows within the extruded border repeat the nearest edge row
+        let source_y = y.saturating_sub(extrude).min(height - 1);
+        let source = &texture.data[source_y * row_size..(source_y + 1) * row_size];
+
+        let begin = ((min_y + y) * atlas_width + min_x) * pixel_size;
+        let row = &mut atlas.data[begin..begin + (width + extrude * 2) * pixel_size];
+        let (left, rest) = row.split_at_mut(extrude * pixel_size);
+        let (middle, right) = rest.split_at_mut(row_size);
+        middle.copy_from_slice(source);
+        for pixel in left.chunks_exact_mut(pixel_size) {
+            pixel.copy_from_slice(&source[..pixel_size]);
+        }
+        for pixel in right.chunks_exact_mut(pixel_size) {
+            pixel.copy_from_slice(&source[row_size - pixel_size..]);
+        }
+    }
+}
+
+/// Returns the sRGB or linear variant of a texture format.
+pub(crate) fn with_srgb(format: TextureFormat, is_srgb: bool) -> TextureFormat {
+    match is_srgb {
+        true => format.add_srgb_suffix(),
+        false => format.remove_srgb_suffix(),
+    }
+}
+
+/// Converts an image to `format`, which is either [`TextureFormat::Rgba8UnormSrgb`], [`TextureFormat::Rgba8Unorm`],
+/// [`TextureFormat::Rgba16Unorm`], or one of the float RGBA formats. Images that only differ from `format` in their
+/// color space keep their pixels as they are, and so do the channels of 8-bit and 16-bit images, which are only
+/// rescaled to the depth of `format`. Converting to or from float formats goes through linear colors. Returns `None` if
+/// the image's format can't be converted.
+pub(crate) fn convert(image: Image, format: TextureFormat) -> Option<Image> {
+    convert_dithered(image, format, false)
+}
+
+/// Converts an image like [`convert`], dithering images with 16-bit or float pixels as they are converted to an 8-bit
+/// `format` if `dither` is set.
+pub(crate) fn convert_dithered(image: Image, format: TextureFormat, dither: bool) -> Option<Image> {
+    let source = image.texture_descriptor.format;
+    let mut image = if source.remove_srgb_suffix() == format.remove_srgb_suffix() {
+        image
+    } else if is_rgba8(format) && (is_rgba_float(source) || channels16(&image).is_some()) {
+        quantize(&image, format, dither)?
+    } else if is_rgba8(format) {
+        image.convert(TextureFormat::Rgba8UnormSrgb)?
+    } else {
+        let image = match linear_pixels(&image) {
+            Some(_) => image,
+            None => image.convert(TextureFormat::Rgba8UnormSrgb)?,
+        };
+        let data = match is_rgba8(image.texture_descriptor.format)
+            && format == TextureFormat::Rgba16Unorm
+        {
+            true => widen(&image),
+            false => encode_pixels(&linear_pixels(&image)?, format),
+        };
+        Image { data, ..image }
+    };
+    image.texture_descriptor.format = format;
+    Some(image)
+}
+
+/// Returns the channels of each pixel of an image with 16-bit RGBA, grayscale, or grayscale and alpha pixels, from
+/// `0.0` to `1.0`. Grayscale PNGs with 16-bit samples are loaded as `R16Uint` or `Rg16Uint` images. Returns `None` if
+/// the image's format isn't one of these.
+fn channels16(image: &Image) -> Option<Vec<Vec4>> {
+    let values: Vec<f32> = image
+        .data
+        .chunks_exact(2)
+        .map(|value| u16::from_le_bytes([value[0], value[1]]) as f32 / 65535.0)
+        .collect();
+    let pixels = match image.texture_descriptor.format {
+        TextureFormat::Rgba16Unorm => values.chunks_exact(4).map(Vec4::from_slice).collect(),
+        TextureFormat::R16Uint => values
+            .iter()
+            .map(|&gray| Vec4::new(gray, gray, gray, 1.0))
+            .collect(),
+        TextureFormat::Rg16Uint => values
+            .chunks_exact(2)
+            .map(|pixel| Vec4::new(pixel[0], pixel[0], pixel[0], pixel[1]))
+            .collect(),
+        _ => return None,
+    };
+    Some(pixels)
+}
+
+/// Returns the 16-bit RGBA pixels of an image with 8-bit RGBA or BGRA pixels, keeping the value of each channel.
+fn widen(image: &Image) -> Vec<u8> {
+    let is_bgra = image.texture_descriptor.format.remove_srgb_suffix() == TextureFormat::Bgra8Unorm;
+    let mut data = Vec::with_capacity(2 * image.data.len());
+    for pixel in image.data.chunks_exact(4) {
+        let channels = match is_bgra {
+            true => [pixel[2], pixel[1], pixel[0], pixel[3]],
+            false => [pixel[0], pixel[1], pixel[2], pixel[3]],
+        };
+        for channel in channels {
+            data.extend((channel as u16 * 257).to_le_bytes());
+        }
+    }
+    data
+}
+
+/// The thresholds of a 4x4 ordered dither, in sixteenths.
+const BAYER: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// Converts an image with 16-bit or float pixels to the 8-bit RGBA or BGRA `format`. Each channel is rounded to the
+/// nearest 8-bit value, unless `dither` is set, in which case an ordered dither spreads the rounding error over blocks
+/// of 4x4 pixels to hide the banding of smooth gradients. Float colors are linear, and are encoded in the color space
+/// of `format` first. Returns `None` if the image's format isn't supported.
+fn quantize(image: &Image, format: TextureFormat, dither: bool) -> Option<Image> {
+    let pixels = match channels16(image) {
+        Some(pixels) => pixels,
+        None => {
+            let is_srgb = format.is_srgb();
+            linear_pixels(image)?
+                .into_iter()
+                .map(|color| match is_srgb {
+                    true => Vec3::from_array(
+                        color
+                            .truncate()
+                            .to_array()
+                            .map(Srgba::gamma_function_inverse),
+                    )
+                    .extend(color.w),
+                    false => color,
+                })
+                .collect()
+        }
+    };
+    let width = image.width() as usize;
+    let is_bgra = format.remove_srgb_suffix() == TextureFormat::Bgra8Unorm;
+    let mut data = Vec::with_capacity(4 * pixels.len());
+    for (index, color) in pixels.into_iter().enumerate() {
+        let threshold = match dither {
+            true => (BAYER[index / width % 4][index % width % 4] as f32 + 0.5) / 16.0,
+            false => 0.5,
+        };
+        let color = match is_bgra {
+            true => color.zyxw(),
+            false => color,
+        };
+        data.extend(
+            color
+                .to_array()
+                .map(|channel| (channel.clamp(0.0, 1.0) * 255.0 + threshold).min(255.0) as u8),
+        );
+    }
+    Some(Image {
+        data,
+        texture_descriptor: TextureDescriptor {
+            format,
+            ..image.texture_descriptor.clone()
+        },
+        sampler: image.sampler.clone(),
+        texture_view_descriptor: image.texture_view_descriptor.clone(),
+        asset_usage: image.asset_usage,
+    })
+}
+
+/// Creates a fully transparent image of the given size and uncompressed format.
+pub(crate) fn blank(size: UVec2, format: TextureFormat) -> Image {
+    Image::new(
+        Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        vec![0; format.pixel_size() * (size.x * size.y) as usize],
+        format,
+        RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD,
+    )
+}
+
+/// Returns the smallest area of an image with 8-bit, 16-bit, or float RGBA pixels that contains all of its pixels that are not
+/// fully transparent, or `None` if every pixel is transparent.
+pub(crate) fn opaque_bounds(image: &Image) -> Option<URect> {
+    let width = image.width() as usize;
+    let pixel_size = image.texture_descriptor.format.pixel_size();
+    let mut bounds: Option<URect> = None;
+    for (index, pixel) in image.data.chunks_exact(pixel_size).enumerate() {
+        // Alpha is the last channel of every supported format, and is only zero if all of its bits are
+        if pixel[pixel_size / 4 * 3..].iter().all(|&byte| byte == 0) {
+            continue;
+        }
+        let position = UVec2::new((index % width) as u32, (index / width) as u32);
+        bounds = Some(match bounds {
+            Some(bounds) => {
+                URect::from_corners(bounds.min.min(position), bounds.max.max(position + 1))
+            }
+            None => URect::from_corners(position, position + 1),
+        });
+    }
+    bounds
+}
+
+/// Copies an uncompressed image into a new image rotated 90° clockwise.
+pub(crate) fn rotate(image: &Image) -> Image {
+    let pixel_size = image.texture_descriptor.format.pixel_size();
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    let mut data = vec![0; image.data.len()];
+    for y in 0..height {
+        for x in 0..width {
+            // The left column of the source becomes the top row of the rotated image
+            let source = (y * width + x) * pixel_size;
+            let dest = (x * height + (height - 1 - y)) * pixel_size;
+            data[dest..dest + pixel_size].copy_from_slice(&image.data[source..source + pixel_size]);
+        }
+    }
+
+    let mut rotated = Image::new(
+        Extent3d {
+            width: image.height(),
+            height: image.width(),
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        image.texture_descriptor.format,
+        image.asset_usage,
+    );
+    rotated.sampler = image.sampler.clone();
+    rotated
+}
+
+/// Rotates the given area of an uncompressed image by 180° in place.
+pub(crate) fn rotate_half(image: &mut Image, rect: URect) {
+    let pixel_size = image.texture_descriptor.format.pixel_size();
+    let image_width = image.width() as usize;
+    let width = rect.width() as usize;
+    let pixels = width * rect.height() as usize;
+    let offset = |pixel: usize| {
+        let (x, y) = (pixel % width, pixel / width);
+        ((rect.min.y as usize + y) * image_width + rect.min.x as usize + x) * pixel_size
+    };
+
+    // Swap each pixel of the first half with its opposite pixel
+    for pixel in 0..pixels / 2 {
+        let (first, second) = (offset(pixel), offset(pixels - 1 - pixel));
+        for byte in 0..pixel_size {
+            image.data.swap(first + byte, second + byte);
+        }
+    }
+}
+
+/// Resizes an image with 8-bit, 16-bit, or float RGBA pixels to `size` with the given filter.
+pub(crate) fn resize(image: &Image, size: UVec2, filter: FilterType) -> Image {
+    let format = image.texture_descriptor.format;
+    let data = match !is_rgba8(format) {
+        true => {
+            let pixels =
+                linear_pixels(image).expect("the image should have 16-bit or float RGBA pixels");
+            // Float colors are clamped to `0.0..=1.0` as they are resized, so brighter colors are scaled down first
+            let range = pixels
+                .iter()
+                .fold(1.0, |range: f32, color| range.max(color.max_element()));
+            let channels = pixels
+                .iter()
+                .flat_map(|color| (*color / range).to_array())
+                .collect();
+            let source = ImageBuffer::<Rgba<f32>, Vec<f32>>::from_raw(
+                image.width(),
+                image.height(),
+                channels,
+            )
+            .expect("the image should have a color for each pixel");
+            let resized = imageops::resize(&source, size.x, size.y, filter);
+            let pixels: Vec<Vec4> = resized
+                .pixels()
+                .map(|pixel| Vec4::from(pixel.0) * range)
+                .collect();
+            encode_pixels(&pixels, format)
+        }
+        false => {
+            let source = ImageBuffer::<Rgba<u8>, _>::from_raw(
+                image.width(),
+                image.height(),
+                &image.data[..],
+            )
+            .expect("the image should have 8-bit RGBA pixels");
+            imageops::resize(&source, size.x, size.y, filter).into_raw()
+        }
+    };
+    let mut texture = Image::new(
+        Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        format,
+        image.asset_usage,
+    );
+    texture.sampler = image.sampler.clone();
+    texture
+}
+
+/// Copies the given area of an uncompressed image into a new image.
+pub(crate) fn crop(image: &Image, rect: URect) -> Image {
+    crop_at(image, 0, rect)
+}
+
+/// Copies an area of the level whose pixels start `offset` bytes into the data of an uncompressed image into a new
+/// image.
+fn crop_at(image: &Image, offset: usize, rect: URect) -> Image {
+    let format = image.texture_descriptor.format;
+    let pixel_size = format.pixel_size();
+    let image_row_size = image.width() as usize * pixel_size;
+    let row_size = rect.width() as usize * pixel_size;
+    let mut data = Vec::with_capacity(row_size * rect.height() as usize);
+    for y in rect.min.y as usize..rect.max.y as usize {
+        let begin = offset + y * image_row_size + rect.min.x as usize * pixel_size;
+        data.extend_from_slice(&image.data[begin..begin + row_size]);
+    }
+
+    let mut cropped = Image::new(
+        Extent3d {
+            width: rect.width(),
+            height: rect.height(),
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        format,
+        image.asset_usage,
+    );
+    cropped.sampler = image.sampler.clone();
+    cropped
+}
+
+/// Copies an uncompressed image into `target` with its top left corner at `position`. Both images must have the same
+/// format, and `source` must fit within `target` at that position.
+pub(crate) fn blit(target: &mut Image, source: &Image, position: UVec2) {
+    let pixel_size = target.texture_descriptor.format.pixel_size();
+    let target_row_size = target.width() as usize * pixel_size;
+    let row_size = source.width() as usize * pixel_size;
+    if row_size == 0 {
+        return;
+    }
+    for (y, row) in source.data.chunks_exact(row_size).enumerate() {
+        let begin = (position.y as usize + y) * target_row_size + position.x as usize * pixel_size;
+        target.data[begin..begin + row_size].copy_from_slice(row);
+    }
+}
+
+/// Copies the texture at `rect` within the given layer of an uncompressed atlas image into a new image, rotating it
+/// back to its original orientation if it was rotated when it was packed.
+pub(crate) fn extract(image: &Image, rect: URect, layer: u32, rotated: bool) -> Image {
+    // Each layer is followed by its mip levels, if it has any
+    let layers = image.texture_descriptor.size.depth_or_array_layers as usize;
+    let offset = layer as usize * (image.data.len() / layers);
+    let mut texture = crop_at(image, offset, rect);
+    if rotated {
+        // A further 270° clockwise rotation restores the texture's original orientation
+        texture = rotate(&texture);
+        let bounds = URect::from_corners(UVec2::ZERO, texture.size());
+        rotate_half(&mut texture, bounds);
+    }
+    texture
+}
+
+/// Creates a `size` by `size` image of alternating magenta and black squares, each `square` pixels wide.
+pub(crate) fn checkerboard(size: u32, square: u32, format: TextureFormat) -> Image {
+    let mut data = Vec::with_capacity((size * size * 4) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            let magenta = (x / square + y / square).is_multiple_of(2);
+            data.extend(if magenta {
+                [255, 0, 255, 255]
+            } else {
+                [0, 0, 0, 255]
+            });
+        }
+    }
+    let image = Image::new(
+        Extent3d {
+            width: size,
+            height: size,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    convert(image, format).expect("the checkerboard should convert to any atlas format")
+}
+
+/// Creates an image of the given size filled with `color`, whose channels are kept as they are in 8-bit formats and
+/// are linear values otherwise.
+pub(crate) fn filled(size: UVec2, color: [u8; 4], format: TextureFormat) -> Image {
+    let image = Image::new(
+        Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        color.repeat((size.x * size.y) as usize),
+        with_srgb(TextureFormat::Rgba8Unorm, format.is_srgb()),
+        RenderAssetUsages::default(),
+    );
+    convert(image, format).expect("the filled image should convert to any atlas format")
+}
+
+/// Copies the first component of each pixel of `source` into the given component of the same pixel of `image`, such
+/// as to pack a grayscale map into one component of a channel. Both images must have the same size and RGBA format.
+pub(crate) fn copy_component(image: &mut Image, component: usize, source: &Image) {
+    let pixel_size = image.texture_descriptor.format.pixel_size();
+    let component_size = pixel_size / 4;
+    let offset = component * component_size;
+    for (pixel, source) in image
+        .data
+        .chunks_exact_mut(pixel_size)
+        .zip(source.data.chunks_exact(pixel_size))
+    {
+        pixel[offset..offset + component_size].copy_from_slice(&source[..component_size]);
+    }
+}
+
+/// Stacks uncompressed images vertically into a single image, aligned to its left edge. The layers of array images are
+/// stacked in order, and the stacked image has the format of the first image. Returns `None` if the images do not
+/// share the same format, apart from their color space.
+pub(crate) fn stack(images: &[&Image]) -> Option<Image> {
+    let format = images.first()?.texture_descriptor.format;
+    if images.iter().any(|image| {
+        image.texture_descriptor.format.remove_srgb_suffix() != format.remove_srgb_suffix()
+    }) {
+        return None;
+    }
+
+    let pixel_size = format.pixel_size();
+    let width = images.iter().map(|image| image.width()).max()?;
+    let height = images
+        .iter()
+        .map(|image| image.height() * image.texture_descriptor.size.depth_or_array_layers)
+        .sum();
+    let row_size = width as usize * pixel_size;
+    let mut data = Vec::with_capacity(row_size * height as usize);
+    for image in images {
+        let image_row_size = image.width() as usize * pixel_size;
+        for row in image.data.chunks_exact(image_row_size) {
+            data.extend_from_slice(row);
+            data.resize(data.len() + row_size - image_row_size, 0);
+        }
+    }
+
+    let mut stacked = Image::new(
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        format,
+        images[0].asset_usage,
+    );
+    stacked.sampler = images[0].sampler.clone();
+    Some(stacked)
+}
+
+/// Copies uncompressed images into the top left corner of each layer of a new 2D array texture, with layers of the
+/// given `size`. Each image must fit within `size`. Returns `None` if there are no images, or if they do not share the
+/// same format.
+pub(crate) fn layers(images: &[&Image], size: UVec2) -> Option<Image> {
+    let format = images.first()?.texture_descriptor.format;
+    if images
+        .iter()
+        .any(|image| image.texture_descriptor.format != format)
+    {
+        return None;
+    }
+
+    let pixel_size = format.pixel_size();
+    let row_size = size.x as usize * pixel_size;
+    let layer_size = row_size * size.y as usize;
+    let mut data = vec![0; layer_size * images.len()];
+    for (layer, image) in images.iter().enumerate() {
+        let image_row_size = image.width() as usize * pixel_size;
+        if image_row_size == 0 {
+            continue;
+        }
+        for (y, row) in image.data.chunks_exact(image_row_size).enumerate() {
+            let begin = layer * layer_size + y * row_size;
+            data[begin..begin + image_row_size].copy_from_slice(row);
+        }
+    }
+
+    let mut array = Image::new(
+        Extent3d {
+            width: size.x,
+            height: size.y * images.len() as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        format,
+        images[0].asset_usage,
+    );
+    array.sampler = images[0].sampler.clone();
+    into_array(&mut array, images.len() as u32);
+    Some(array)
+}
+
+/// Reinterprets an image containing vertically stacked layers of the same size as a 2D array texture. Unlike
+/// [`Image::reinterpret_stacked_2d_as_array`], the texture is always viewed as an array, even with a single layer.
+pub(crate) fn into_array(image: &mut Image, layers: u32) {
+    image.reinterpret_stacked_2d_as_array(layers);
+    image.texture_view_descriptor = Some(TextureViewDescriptor {
+        dimension: Some(TextureViewDimension::D2Array),
+        ..default()
+    });
+}
+
+/// Reinterprets a 2D array texture as a single image with its layers stacked vertically.
+pub(crate) fn flatten_array(image: &mut Image) {
+    let size = image.texture_descriptor.size;
+    image.reinterpret_size(Extent3d {
+        width: size.width,
+        height: size.height * size.depth_or_array_layers,
+        depth_or_array_layers: 1,
+    });
+    image.texture_view_descriptor = None;
+}
+
+/// Returns `true` if a format has 8-bit RGBA or BGRA pixels.
+pub(crate) fn is_rgba8(format: TextureFormat) -> bool {
+    matches!(
+        format.remove_srgb_suffix(),
+        TextureFormat::Rgba8Unorm | TextureFormat::Bgra8Unorm
+    )
+}
+
+/// Returns `true` if a format has 16-bit or 32-bit float RGBA pixels.
+pub(crate) fn is_rgba_float(format: TextureFormat) -> bool {
+    matches!(
+        format,
+        TextureFormat::Rgba16Float | TextureFormat::Rgba32Float
+    )
+}
+
+/// Returns the linear RGBA colors of the pixels of an image with 8-bit RGBA or BGRA pixels, 16-bit pixels, or float
+/// RGBA pixels, in the order they are stored. Returns `None` if the image's format isn't supported.
+pub(crate) fn linear_pixels(image: &Image) -> Option<Vec<Vec4>> {
+    let format = image.texture_descriptor.format;
+    let is_srgb = format.is_srgb();
+    let pixels = match format.remove_srgb_suffix() {
+        TextureFormat::Rgba8Unorm | TextureFormat::Bgra8Unorm => image
+            .data
+            .chunks_exact(4)
+            .map(|pixel| {
+                let [r, g, b] = [0, 1, 2].map(|channel| to_linear(pixel[channel], is_srgb));
+                Vec4::new(r, g, b, pixel[3] as f32 / 255.0)
+            })
+            .map(|color| match format.remove_srgb_suffix() {
+                TextureFormat::Bgra8Unorm => color.zyxw(),
+                _ => color,
+            })
+            .collect(),
+        TextureFormat::Rgba16Float => image
+            .data
+            .chunks_exact(8)
+            .map(|pixel| {
+                Vec4::from_array([0, 2, 4, 6].map(|offset| {
+                    f16_to_f32(u16::from_le_bytes([pixel[offset], pixel[offset + 1]]))
+                }))
+            })
+            .collect(),
+        TextureFormat::Rgba32Float => image
+            .data
+            .chunks_exact(16)
+            .map(|pixel| {
+                Vec4::from_array([0, 4, 8, 12].map(|offset| {
+                    f32::from_le_bytes(pixel[offset..offset + 4].try_into().unwrap())
+                }))
+            })
+            .collect(),
+        // 16-bit channels have no color space of their own, so they are treated as linear
+        _ => return channels16(image),
+    };
+    Some(pixels)
+}
+
+/// Encodes linear RGBA colors as the pixels of an image with the given 8-bit RGBA or BGRA, 16-bit RGBA, or float RGBA
+/// format, clamping colors to the range of 8-bit and 16-bit formats.
+pub(crate) fn encode_pixels(pixels: &[Vec4], format: TextureFormat) -> Vec<u8> {
+    let is_srgb = format.is_srgb();
+    let mut data = Vec::with_capacity(format.pixel_size() * pixels.len());
+    for &color in pixels {
+        match format.remove_srgb_suffix() {
+            TextureFormat::Rgba16Float => {
+                for channel in color.to_array() {
+                    data.extend(f32_to_f16(channel).to_le_bytes());
+                }
+            }
+            TextureFormat::Rgba32Float => {
+                for channel in color.to_array() {
+                    data.extend(channel.to_le_bytes());
+                }
+            }
+            TextureFormat::Rgba16Unorm => {
+                for channel in color.to_array() {
+                    data.extend(((channel.clamp(0.0, 1.0) * 65535.0).round() as u16).to_le_bytes());
+                }
+            }
+            format => {
+                let color = match format {
+                    TextureFormat::Bgra8Unorm => color.zyxw(),
+                    _ => color,
+                };
+                data.extend([
+                    from_linear(color.x, is_srgb),
+                    from_linear(color.y, is_srgb),
+                    from_linear(color.z, is_srgb),
+                    (color.w.clamp(0.0, 1.0) * 255.0).round() as u8,
+                ]);
+            }
+        }
+    }
+    data
+}
+
+/// Returns the value of a 16-bit float, given by its bits.
+fn f16_to_f32(half: u16) -> f32 {
+    let sign = ((half & 0x8000) as u32) << 16;
+    let exponent = ((half >> 10) & 0x1f) as u32;
+    let mantissa = (half & 0x3ff) as u32;
+    match exponent {
+        // Subnormals are scaled by the smallest exponent
+        0 => {
+            let magnitude = mantissa as f32 * (-24f32).exp2();
+            f32::from_bits(sign | magnitude.to_bits())
+        }
+        // Infinities and NaNs keep their mantissa
+        0x1f => f32::from_bits(sign | 0x7f80_0000 | (mantissa << 13)),
+        exponent => f32::from_bits(sign | ((exponent + 112) << 23) | (mantissa << 13)),
+    }
+}
+
+/// Returns the bits of the 16-bit float nearest to a value, which is infinite if the value is too large.
+fn f32_to_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+    if exponent == 0xff {
+        let nan = if mantissa != 0 { 0x200 } else { 0 };
+        return sign | 0x7c00 | nan;
+    }
+    let exponent = exponent - 127 + 15;
+    if exponent >= 0x1f {
+        return sign | 0x7c00;
+    }
+    if exponent <= 0 {
+        // Values too small for the smallest exponent become subnormals, or zero
+        if exponent < -10 {
+            return sign;
+        }
+        let mantissa = mantissa | 0x80_0000;
+        let shift = (14 - exponent) as u32;
+        let rounding = (mantissa >> (shift - 1)) & 1;
+        return sign | ((mantissa >> shift) + rounding) as u16;
+    }
+    // Rounding can carry into the exponent, which gives the next power of two as it should
+    let rounding = (mantissa >> 12) & 1;
+    sign | (((exponent as u32) << 10 | (mantissa >> 13)) + rounding) as u16
+}
+
+/// Returns the linear value of an 8-bit color channel.
+fn to_linear(value: u8, is_srgb: bool) -> f32 {
+    let value = value as f32 / 255.0;
+    match is_srgb {
+        true => Srgba::gamma_function(value),
+        false => value,
+    }
+}
+
+/// Returns the 8-bit color channel of a linear value.
+fn from_linear(value: f32, is_srgb: bool) -> u8 {
+    let value = match is_srgb {
+        true => Srgba::gamma_function_inverse(value),
+        false => value,
+    };
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// Returns a copy of an image with 8-bit RGBA or BGRA pixels where each pixel of the RGB `key` color is made transparent
+/// black, or `None` if the image has no such pixels or its format isn't supported.
+pub(crate) fn color_key(image: &Image, key: [u8; 3]) -> Option<Image> {
+    let format = image.texture_descriptor.format;
+    if !is_rgba8(format) {
+        return None;
+    }
+    let key = match format.remove_srgb_suffix() {
+        TextureFormat::Bgra8Unorm => [key[2], key[1], key[0]],
+        _ => key,
+    };
+    let is_key = |pixel: &[u8]| pixel[..3] == key;
+    if !image.data.chunks_exact(4).any(is_key) {
+        return None;
+    }
+    let mut keyed = image.clone();
+    for pixel in keyed.data.chunks_exact_mut(4) {
+        if is_key(pixel) {
+            pixel.fill(0);
+        }
+    }
+    Some(keyed)
+}
+
+/// Multiplies the color of each pixel of an image with 8-bit RGBA or BGRA pixels by its alpha, in linear space for
+/// sRGB formats. Returns `false` without changing the image if its format isn't supported.
+pub(crate) fn premultiply_alpha(image: &mut Image) -> bool {
+    let format = image.texture_descriptor.format;
+    if !is_rgba8(format) {
+        return false;
+    }
+    let is_srgb = format.is_srgb();
+    for pixel in image.data.chunks_exact_mut(4) {
+        match pixel[3] {
+            255 => {}
+            0 => pixel[..3].fill(0),
+            alpha => {
+                let alpha = alpha as f32 / 255.0;
+                for channel in pixel[..3].iter_mut() {
+                    *channel = from_linear(to_linear(*channel, is_srgb) * alpha, is_srgb);
+                }
+            }
+        }
+    }
+    true
+}
+
+/// Appends a full chain of mip levels to each layer of an image with 8-bit RGBA or BGRA pixels, down to a single pixel.
+/// Each level averages 2x2 pixels of the level above, averaging sRGB colors in linear space. Unless the image is
+/// `premultiplied`, colors are weighted by their alpha so that transparent pixels don't darken their neighbors. Returns
+/// `false` without changing the image if it already has mips, or its format isn't supported.
+pub(crate) fn generate_mipmaps(image: &mut Image, premultiplied: bool) -> bool {
+    let descriptor = &image.texture_descriptor;
+    let format = descriptor.format;
+    if descriptor.mip_level_count > 1 || !is_rgba8(format) {
+        return false;
+    }
+    let size = image.size();
+    let levels = u32::BITS - size.x.max(size.y).leading_zeros();
+    if levels <= 1 {
+        return true;
+    }
+
+    // Each layer is followed by its own mip levels
+    let layers = descriptor.size.depth_or_array_layers as usize;
+    let layer_size = image.data.len() / layers;
+    let mut data = Vec::with_capacity(image.data.len() / 3 * 4 + 4 * layers * levels as usize);
+    for layer in image.data.chunks_exact(layer_size) {
+        data.extend_from_slice(layer);
+        let mut begin = data.len() - layer_size;
+        let mut level_size = size;
+        for _ in 1..levels {
+            let end = data.len();
+            let level = downsample(
+                &data[begin..end],
+                level_size,
+                format.is_srgb(),
+                premultiplied,
+            );
+            data.extend_from_slice(&level);
+            begin = end;
+            level_size = (level_size / 2).max(UVec2::ONE);
+        }
+    }
+    image.data = data;
+    image.texture_descriptor.mip_level_count = levels;
+    true
+}
+
+/// Halves the size of the pixels of a single mip level, rounding down to at least one pixel.
+fn downsample(pixels: &[u8], size: UVec2, is_srgb: bool, premultiplied: bool) -> Vec<u8> {
+    let decode = |value: u8| to_linear(value, is_srgb);
+    let encode = |value: f32| from_linear(value, is_srgb);
+
+    let next = (size / 2).max(UVec2::ONE);
+    let mut data = Vec::with_capacity(4 * (next.x * next.y) as usize);
+    for y in 0..next.y {
+        for x in 0..next.x {
+            let mut color = Vec3::ZERO;
+            let mut weighted = Vec3::ZERO;
+            let mut alpha = 0.0;
+            for offset in [UVec2::ZERO, UVec2::X, UVec2::Y, UVec2::ONE] {
+                // Odd sizes clamp to the last row or column rather than reading past it
+                let source = (UVec2::new(x, y) * 2 + offset).min(size - 1);
+                let index = 4 * (source.y * size.x + source.x) as usize;
+                let pixel = &pixels[index..index + 4];
+                let rgb = Vec3::new(decode(pixel[0]), decode(pixel[1]), decode(pixel[2]));
+                let a = pixel[3] as f32 / 255.0;
+                color += rgb;
+                weighted += rgb * a;
+                alpha += a;
+            }
+            // Premultiplied colors are already weighted, and fully transparent pixels keep their plain average, as there
+            // is nothing to weight them by
+            let color = match alpha > 0.0 && !premultiplied {
+                true => weighted / alpha,
+                false => color / 4.0,
+            };
+            data.extend([
+                encode(color.x),
+                encode(color.y),
+                encode(color.z),
+                (alpha / 4.0 * 255.0).round() as u8,
+            ]);
+        }
+    }
+    data
+}
+
+/// Returns a copy of an image with 8-bit RGBA or BGRA pixels at half its size, rounded down to at least one pixel,
+/// averaging 2x2 pixels like [`generate_mipmaps`]. The copy has no mip levels of its own. Returns `None` if the image's
+/// format isn't supported.
+pub(crate) fn half_size(image: &Image, premultiplied: bool) -> Option<Image> {
+    let format = image.texture_descriptor.format;
+    if !is_rgba8(format) {
+        return None;
+    }
+    let image = base_level(image);
+    let size = image.size();
+    let layers = image.texture_descriptor.size.depth_or_array_layers;
+    let mut texture_descriptor = image.texture_descriptor.clone();
+    let next = (size / 2).max(UVec2::ONE);
+    texture_descriptor.size = Extent3d {
+        width: next.x,
+        height: next.y,
+        depth_or_array_layers: layers,
+    };
+    Some(Image {
+        data: image
+            .data
+            .chunks_exact(image.data.len() / layers as usize)
+            .flat_map(|layer| downsample(layer, size, format.is_srgb(), premultiplied))
+            .collect(),
+        texture_descriptor,
+        sampler: image.sampler.clone(),
+        texture_view_descriptor: image.texture_view_descriptor.clone(),
+        asset_usage: image.asset_usage,
+    })
+}
+
+/// Returns an image with only the first mip level of each layer of `image`, borrowing it if it has no other levels.
+pub(crate) fn base_level(image: &Image) -> Cow<'_, Image> {
+    let levels = image.texture_descriptor.mip_level_count;
+    if levels <= 1 {
+        return Cow::Borrowed(image);
+    }
+    let layers = image.texture_descriptor.size.depth_or_array_layers as usize;
+    let layer_size = image.data.len() / layers;
+    let base_size =
+        image.texture_descriptor.format.pixel_size() * (image.width() * image.height()) as usize;
+    let mut texture_descriptor = image.texture_descriptor.clone();
+    texture_descriptor.mip_level_count = 1;
+    Cow::Owned(Image {
+        data: image
+            .data
+            .chunks_exact(layer_size)
+            .flat_map(|layer| &layer[..base_size])
+            .copied()
+            .collect(),
+        texture_descriptor,
+        sampler: image.sampler.clone(),
+        texture_view_descriptor: image.texture_view_descriptor.clone(),
+        asset_usage: image.asset_usage,
+    })
+}
+
+/// Moves the textures of an uncompressed atlas image so that each one starts on a boundary of `block` pixels and no two
+/// textures share a block, which keeps block compressed formats from blending neighboring textures together. The image
+/// is also grown to a multiple of `block` pixels. If the textures are already aligned they keep their places, otherwise
+/// they are all packed again. Returns the new image and the area covered by each texture, or `None` if the textures do
+/// not fit within `max_size`.
+#[cfg(any(feature = "ktx2", feature = "dds"))]
+pub(crate) fn align_blocks(
+    image: &Image,
+    rects: &[URect],
+    block: u32,
+    max_size: UVec2,
+) -> Option<(Image, Vec<URect>)> {
+    let blocks: Vec<URect> = rects
+        .iter()
+        .map(|rect| URect::from_corners(rect.min / block, (rect.max + block - 1) / block))
+        .collect();
+    let aligned = rects.iter().all(|rect| rect.min % block == UVec2::ZERO)
+        && blocks
+            .iter()
+            .enumerate()
+            .all(|(index, a)| blocks[index + 1..].iter().all(|b| !overlaps(a, b)));
+
+    let format = image.texture_descriptor.format;
+    let size = (image.size() + block - 1) / block;
+    if aligned {
+        let packing = Packing {
+            size: size * block,
+            indices: vec![0],
+            rects: vec![URect::from_corners(UVec2::ZERO, image.size())],
+            rotated: vec![false],
+        };
+        return Some((composite(&packing, format, &[image], 0), rects.to_vec()));
+    }
+
+    trace!("Aligning {} textures to {block}px blocks", rects.len());
+    let sizes: Vec<UVec2> = blocks.iter().map(URect::size).collect();
+    let settings = PackSettings {
+        initial_size: size,
+        max_size: max_size / block,
+        ..default()
+    };
+    let [page] = <[Packing; 1]>::try_from(pack(&sizes, &settings).ok()?).ok()?;
+    let textures: Vec<Image> = rects.iter().map(|&rect| crop(image, rect)).collect();
+    let packing = Packing {
+        size: page.size * block,
+        rects: rects
+            .iter()
+            .zip(page.rects.iter())
+            .map(|(rect, placed)| {
+                let min = placed.min * block;
+                URect::from_corners(min, min + rect.size())
+            })
+            .collect(),
+        ..page
+    };
+    let textures: Vec<&Image> = textures.iter().collect();
+    Some((composite(&packing, format, &textures, 0), packing.rects))
+}
+
+/// A bin packer using one of the [`PackingAlgorithm`]s.
+enum Bin {
+    MaxRects(MaxRects),
+    Skyline(Skyline),
+    Guillotine(Guillotine),
+    RowShelf(RowShelf),
+}
+
+impl Bin {
+    fn new(algorithm: PackingAlgorithm, size: UVec2) -> Self {
+        match algorithm {
+            PackingAlgorithm::MaxRects => Self::MaxRects(MaxRects::new(size)),
+            PackingAlgorithm::Skyline => Self::Skyline(Skyline::new(size)),
+            PackingAlgorithm::Guillotine => Self::Guillotine(Guillotine::new(size)),
+            PackingAlgorithm::RowShelf => Self::RowShelf(RowShelf::new(size)),
+        }
+    }
+
+    /// Places a rectangle of the given size, returning its position if there was room.
+    fn insert(&mut self, size: UVec2) -> Option<UVec2> {
+        match self {
+            Self::MaxRects(bin) => bin.insert(size),
+            Self::Skyline(bin) => bin.insert(size),
+            Self::Guillotine(bin) => bin.insert(size),
+            Self::RowShelf(bin) => bin.insert(size),
+        }
+    }
+}
+
+/// A bin packer using the maximal rectangles algorithm with the "best short side fit" heuristic.
+struct MaxRects {
+    free: Vec<URect>,
+}
+
+impl MaxRects {
+    fn new(size: UVec2) -> Self {
+        Self {
+            free: vec![URect::from_corners(UVec2::ZERO, size)],
+        }
+    }
+
+    /// Places a rectangle of the given size, returning its position if there was room.
+    fn insert(&mut self, size: UVec2) -> Option<UVec2> {
+        let position = self
+            .free
+            .iter()
+            .filter(|free| free.width() >= size.x && free.height() >= size.y)
+            .min_by_key(|free| {
+                let leftover = free.size() - size;
+                (
+                    leftover.min_element(),
+                    leftover.max_element(),
+                    free.min.y,
+                    free.min.x,
+                )
+            })?
+            .min;
+
+        if size.x > 0 && size.y > 0 {
+            self.split(URect::from_corners(position, position + size));
+            self.prune();
+        }
+        Some(position)
+    }
+
+    /// Splits every free rectangle overlapping `placed` into the maximal rectangles around it.
+    fn split(&mut self, placed: URect) {
+        let mut split = Vec::new();
+        self.free.retain(|free| {
+            if !overlaps(free, &placed) {
+                return true;
+            }
+            if placed.min.x > free.min.x {
+                split.push(URect::new(free.min.x, free.min.y, placed.min.x, free.max.y));
+            }
+            if placed.max.x < free.max.x {
+                split.push(URect::new(placed.max.x, free.min.y, free.max.x, free.max.y));
+            }
+            if placed.min.y > free.min.y {
+                split.push(URect::new(free.min.x, free.min.y, free.max.x, placed.min.y));
+            }
+            if placed.max.y < free.max.y {
+                split.push(URect::new(free.min.x, placed.max.y, free.max.x, free.max.y));
+            }
+            false
+        });
+        self.free.extend(split);
+    }
+
+    /// Removes free rectangles that are entirely contained by another.
+    fn prune(&mut self) {
+        let mut index = 0;
+        while index < self.free.len() {
+            let rect = self.free[index];
+            let contained = self.free.iter().enumerate().any(|(other_index, other)| {
+                other_index != index
+                    && contains(other, &rect)
+                    && (*other != rect || other_index < index)
+            });
+            if contained {
+                self.free.remove(index);
+            } else {
+                index += 1;
+            }
+        }
+    }
+}
+
+/// A bin packer using the bottom-left skyline algorithm.
+struct Skyline {
+    size: UVec2,
+    /// Horizontal segments of the skyline from left to right, as `(x, y, width)`.
+    segments: Vec<(u32, u32, u32)>,
+}
+
+impl Skyline {
+    fn new(size: UVec2) -> Self {
+        Self {
+            size,
+            segments: vec![(0, 0, size.x)],
+        }
+    }
+
+    /// Places a rectangle of the given size, returning its position if there was room.
+    fn insert(&mut self, size: UVec2) -> Option<UVec2> {
+        if size.x == 0 || size.y == 0 {
+            return size.cmple(self.size).all().then_some(UVec2::ZERO);
+        }
+
+        // Find the segment that a rectangle can start at with the lowest top edge
+        let (start, position) = (0..self.segments.len())
+            .filter_map(|start| Some((start, self.fit(start, size)?)))
+            .min_by_key(|&(_, position)| (position.y + size.y, position.x))?;
+
+        // Replace the covered part of the skyline with the new segment
+        let right = position.x + size.x;
+        let mut end = start;
+        while end < self.segments.len() && self.segments[end].0 < right {
+            end += 1;
+        }
+        let (last_x, last_y, last_width) = self.segments[end - 1];
+        let mut replacement = vec![(position.x, position.y + size.y, size.x)];
+        if last_x + last_width > right {
+            replacement.push((right, last_y, last_x + last_width - right));
+        }
+        self.segments.splice(start..end, replacement);
+
+        // Merge neighboring segments at the same height
+        self.segments.dedup_by(|next, prev| {
+            let merge = next.1 == prev.1;
+            if merge {
+                prev.2 += next.2;
+            }
+            merge
+        });
+        Some(position)
+    }
+
+    /// Returns the position a rectangle would be placed at if it started at the given segment.
+    fn fit(&self, start: usize, size: UVec2) -> Option<UVec2> {
+        let x = self.segments[start].0;
+        if x + size.x > self.size.x {
+            return None;
+        }
+        let y = self.segments[start..]
+            .iter()
+            .take_while(|&&(segment_x, _, _)| segment_x < x + size.x)
+            .map(|&(_, y, _)| y)
+            .max()?;
+        (y + size.y <= self.size.y).then_some(UVec2::new(x, y))
+    }
+}
+
+/// A bin packer using the guillotine algorithm with the "best area fit" heuristic, splitting along the shorter
+/// leftover axis. Placed rectangles can be freed again, so it is also used to allocate space in dynamic atlases.
+#[derive(Debug, Clone)]
+pub(crate) struct Guillotine {
+    free: Vec<URect>,
+}
+
+impl Guillotine {
+    pub(crate) fn new(size: UVec2) -> Self {
+        Self {
+            free: vec![URect::from_corners(UVec2::ZERO, size)],
+        }
+    }
+
+    /// Extends the bin from `size` to `new_size`, adding the new area to the right and bottom as free space.
+    pub(crate) fn grow(&mut self, size: UVec2, new_size: UVec2) {
+        let right = URect::new(size.x, 0, new_size.x, new_size.y);
+        let bottom = URect::new(0, size.y, size.x, new_size.y);
+        for rect in [right, bottom] {
+            if rect.width() > 0 && rect.height() > 0 {
+                self.free(rect);
+            }
+        }
+    }
+
+    /// Returns a placed rectangle to the free space, merging it with any free rectangles that share one of its edges.
+    pub(crate) fn free(&mut self, mut rect: URect) {
+        while let Some(index) = self.free.iter().position(|free| {
+            let same_rows = free.min.y == rect.min.y && free.max.y == rect.max.y;
+            let same_columns = free.min.x == rect.min.x && free.max.x == rect.max.x;
+            (same_rows && (free.max.x == rect.min.x || free.min.x == rect.max.x))
+                || (same_columns && (free.max.y == rect.min.y || free.min.y == rect.max.y))
+        }) {
+            rect = rect.union(self.free.swap_remove(index));
+        }
+        self.free.push(rect);
+    }
+
+    /// Places a rectangle of the given size, returning its position if there was room.
+    pub(crate) fn insert(&mut self, size: UVec2) -> Option<UVec2> {
+        let (index, free) = self
+            .free
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|(_, free)| free.width() >= size.x && free.height() >= size.y)
+            .min_by_key(|(_, free)| {
+                let leftover = free.size() - size;
+                (free.width() * free.height(), leftover.min_element())
+            })?;
+        if size.x == 0 || size.y == 0 {
+            return Some(free.min);
+        }
+
+        self.free.swap_remove(index);
+        let leftover = free.size() - size;
+        let (right, bottom) = if leftover.x < leftover.y {
+            // Split horizontally, giving the bottom rectangle the full width
+            (
+                URect::new(
+                    free.min.x + size.x,
+                    free.min.y,
+                    free.max.x,
+                    free.min.y + size.y,
+                ),
+                URect::new(free.min.x, free.min.y + size.y, free.max.x, free.max.y),
+            )
+        } else {
+            // Split vertically, giving the right rectangle the full height
+            (
+                URect::new(free.min.x + size.x, free.min.y, free.max.x, free.max.y),
+                URect::new(
+                    free.min.x,
+                    free.min.y + size.y,
+                    free.min.x + size.x,
+                    free.max.y,
+                ),
+            )
+        };
+        self.free.extend(
+            [right, bottom]
+                .into_iter()
+                .filter(|rect| rect.width() > 0 && rect.height() > 0),
+        );
+        Some(free.min)
+    }
+}
+
+/// A bin packer that places rectangles left to right in rows, using the first row that they fit in.
+struct RowShelf {
+    size: UVec2,
+    /// The rows of the bin, as `(y, height, used_width)`.
+    rows: Vec<(u32, u32, u32)>,
+}
+
+impl RowShelf {
+    fn new(size: UVec2) -> Self {
+        Self {
+            size,
+            rows: Vec::new(),
+        }
+    }
+
+    /// Places a rectangle of the given size, returning its position if there was room.
+    fn insert(&mut self, size: UVec2) -> Option<UVec2> {
+        if size.x > self.size.x {
+            return None;
+        }
+        if let Some((y, _, used)) = self
+            .rows
+            .iter_mut()
+            .find(|(_, height, used)| size.y <= *height && *used + size.x <= self.size.x)
+        {
+            let position = UVec2::new(*used, *y);
+            *used += size.x;
+            return Some(position);
+        }
+
+        let y = self.rows.last().map_or(0, |&(y, height, _)| y + height);
+        if y + size.y > self.size.y {
+            return None;
+        }
+        self.rows.push((y, size.y, size.x));
+        Some(UVec2::new(0, y))
+    }
+}
+
+/// Returns the largest power of two less than or equal to `x`, or zero if `x` is zero.
+fn prev_power_of_two(x: u32) -> u32 {
+    match x {
+        0 => 0,
+        x => 1 << x.ilog2(),
+    }
+}
+
+fn overlaps(a: &URect, b: &URect) -> bool {
+    a.min.x < b.max.x && b.min.x < a.max.x && a.min.y < b.max.y && b.min.y < a.max.y
+}
+
+fn contains(outer: &URect, inner: &URect) -> bool {
+    outer.min.cmple(inner.min).all() && outer.max.cmpge(inner.max).all()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALGORITHMS: [PackingAlgorithm; 4] = [
+        PackingAlgorithm::MaxRects,
+        PackingAlgorithm::Skyline,
+        PackingAlgorithm::Guillotine,
+        PackingAlgorithm::RowShelf,
+    ];
+
+    /// Asserts that every rect of `packing` lies within the page and that no two overlap.
+    fn assert_disjoint(packing: &Packing) {
+        let page = URect::from_corners(UVec2::ZERO, packing.size);
+        for (i, rect) in packing.rects.iter().enumerate() {
+            assert!(contains(&page, rect), "{rect:?} is outside {page:?}");
+            for other in &packing.rects[i + 1..] {
+                assert!(!overlaps(rect, other), "{rect:?} overlaps {other:?}");
+            }
+        }
+    }
+
+    /// Returns the RGBA8 pixel of `image` at `position`.
+    fn pixel(image: &Image, position: UVec2) -> [u8; 4] {
+        let offset = (position.y * image.width() + position.x) as usize * 4;
+        image.data[offset..offset + 4].try_into().unwrap()
+    }
+
+    #[test]
+    fn extrudes_edge_pixels() {
+        // A 2x2 texture with a different color in each pixel
+        let texture = Image::new(
+            Extent3d {
+                width: 2,
+                height: 2,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            (1..=16).collect(),
+            TextureFormat::Rgba8Unorm,
+            RenderAssetUsages::default(),
+        );
+        let settings = PackSettings {
+            extrude: 2,
+            ..default()
+        };
+        let packing = &pack(&[UVec2::splat(2)], &settings).unwrap()[0];
+        let atlas = composite(packing, TextureFormat::Rgba8Unorm, &[&texture], 2);
+
+        let rect = packing.rects[0];
+        assert!(rect.min.cmpge(UVec2::splat(2)).all());
+        for y in 0..6 {
+            for x in 0..6 {
+                // Each pixel of the extruded border repeats the nearest pixel of the texture
+                let source = UVec2::new(x, y)
+                    .saturating_sub(UVec2::splat(2))
+                    .min(UVec2::ONE);
+                let position = rect.min - UVec2::splat(2) + UVec2::new(x, y);
+                assert_eq!(
+                    pixel(&atlas, position),
+                    pixel(&texture, source),
+                    "{position}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn grows_the_page_until_textures_fit() {
+        let settings = PackSettings {
+            initial_size: UVec2::splat(32),
+            ..default()
+        };
+        let pages = pack(&[UVec2::splat(100)], &settings).unwrap();
+        assert_eq!(pages[0].size, UVec2::splat(128));
+    }
+
+    #[test]
+    fn returns_the_index_of_a_texture_that_does_not_fit() {
+        let settings = PackSettings {
+            max_size: UVec2::splat(256),
+            ..default()
+        };
+        let sizes = [UVec2::splat(16), UVec2::splat(300), UVec2::splat(16)];
+        assert_eq!(pack(&sizes, &settings).unwrap_err(), 1);
+    }
+
+    #[test]
+    fn places_overflowing_textures_on_more_pages() {
+        let settings = PackSettings {
+            max_size: UVec2::splat(256),
+            multi_page: true,
+            ..default()
+        };
+        let sizes = [UVec2::splat(200); 3];
+        let pages = pack(&sizes, &settings).unwrap();
+        assert_eq!(pages.len(), 3);
+        let mut indices: Vec<usize> = pages.iter().flat_map(|page| page.indices.clone()).collect();
+        indices.sort();
+        assert_eq!(indices, [0, 1, 2]);
+        pages.iter().for_each(assert_disjoint);
+
+        // A texture larger than a whole page still fails
+        let sizes = [UVec2::splat(16), UVec2::splat(300)];
+        assert_eq!(pack(&sizes, &settings).unwrap_err(), 1);
+    }
+
+    #[test]
+    fn packs_textures_that_fit() {
+        let sizes = [
+            UVec2::new(64, 64),
+            UVec2::new(32, 16),
+            UVec2::new(16, 48),
+            UVec2::new(100, 20),
+            UVec2::new(8, 8),
+        ];
+        for algorithm in ALGORITHMS {
+            let settings = PackSettings {
+                algorithm,
+                ..default()
+            };
+            let pages = pack(&sizes, &settings).unwrap();
+            assert_eq!(pages.len(), 1, "{algorithm:?}");
+            let page = &pages[0];
+            assert_eq!(page.indices, [0, 1, 2, 3, 4], "{algorithm:?}");
+            for (&index, rect) in page.indices.iter().zip(&page.rects) {
+                assert_eq!(rect.size(), sizes[index], "{algorithm:?}");
+            }
+            assert_disjoint(page);
+        }
+    }
+
+    #[test]
+    fn rotates_textures_that_only_fit_rotated() {
+        let size = UVec2::new(300, 100);
+        let settings = PackSettings {
+            initial_size: UVec2::new(128, 512),
+            max_size: UVec2::new(128, 512),
+            ..default()
+        };
+        assert_eq!(pack(&[size], &settings).unwrap_err(), 0);
+
+        let settings = PackSettings {
+            allow_rotation: true,
+            ..settings
+        };
+        let pages = pack(&[size], &settings).unwrap();
+        assert_eq!(pages[0].rotated, [true]);
+        assert_eq!(pages[0].rects[0].size(), size.yx());
+    }
+
+    #[test]
+    fn places_every_texture_of_multiple_pages_with_rotation() {
+        // Textures that don't fit on a full page must all move to later pages, whether or not they can be rotated
+        let sizes = [UVec2::new(120, 60); 10];
+        for allow_rotation in [false, true] {
+            let settings = PackSettings {
+                max_size: UVec2::splat(128),
+                multi_page: true,
+                allow_rotation,
+                ..default()
+            };
+            let pages = pack(&sizes, &settings).unwrap();
+            let placed: usize = pages.iter().map(|page| page.indices.len()).sum();
+            assert_eq!(placed, sizes.len());
+            pages.iter().for_each(assert_disjoint);
+        }
+    }
+
+    #[test]
+    fn packs_deterministically() {
+        let sizes: Vec<UVec2> = (0..40)
+            .map(|i| UVec2::new(8 + (i * 7) % 29, 8 + (i * 13) % 31))
+            .collect();
+        for algorithm in ALGORITHMS {
+            let settings = PackSettings {
+                algorithm,
+                allow_rotation: true,
+                ..default()
+            };
+            let first = pack(&sizes, &settings).unwrap();
+            let second = pack(&sizes, &settings).unwrap();
+            assert_eq!(first.len(), second.len());
+            for (first, second) in first.iter().zip(&second) {
+                assert_eq!(first.size, second.size);
+                assert_eq!(first.indices, second.indices);
+                assert_eq!(first.rects, second.rects);
+                assert_eq!(first.rotated, second.rotated);
+                assert!(first.indices.is_sorted());
+            }
+        }
+    }
+
+    #[test]
+    fn aligns_textures() {
+        let settings = PackSettings {
+            align: 4,
+            extrude: 1,
+            padding: UVec2::ONE,
+            ..default()
+        };
+        let sizes = [
+            UVec2::new(3, 5),
+            UVec2::new(7, 2),
+            UVec2::new(1, 1),
+            UVec2::new(9, 9),
+        ];
+        let pages = pack(&sizes, &settings).unwrap();
+        for rect in &pages[0].rects {
+            assert_eq!(rect.min % 4, UVec2::ZERO, "{rect:?}");
+        }
+        assert_disjoint(&pages[0]);
+    }
+
+    #[test]
+    fn starts_from_a_zero_initial_size() {
+        let settings = PackSettings {
+            initial_size: UVec2::ZERO,
+            ..default()
+        };
+        let pages = pack(&[UVec2::splat(10)], &settings).unwrap();
+        assert_eq!(pages[0].size, UVec2::splat(16));
+    }
+}
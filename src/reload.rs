@@ -0,0 +1,170 @@
+//! Tracking of textures that move when their atlas is rebuilt, such as when it is hot reloaded.
+
+use bevy::{prelude::*, utils::HashMap};
+
+use super::{AtlasLocation, TextureAtlasAsset, TextureAtlasPaths};
+
+/// An [`Event`] sent when a [`TextureAtlasAsset`] is rebuilt, such as when it is hot reloaded, and any of the textures
+/// it had before have moved or been removed.
+///
+/// Systems that store raw texture indices can use this to find their textures' new locations, instead of displaying
+/// whichever texture now has their old index.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_mod_atlas_loader::reload::AtlasRemapped;
+/// fn fix_icons(mut remapped: EventReader<AtlasRemapped>, mut icons: Query<&mut Icon>) {
+///     for event in remapped.read() {
+///         for mut icon in &mut icons {
+///             if let Some(index) = event.index(icon.0) {
+///                 icon.0 = index;
+///             }
+///         }
+///     }
+/// }
+/// # #[derive(Component)]
+/// # struct Icon(usize);
+/// ```
+#[derive(Event, Debug, Clone)]
+pub struct AtlasRemapped {
+    /// The atlas that was rebuilt.
+    pub atlas: AssetId<TextureAtlasAsset>,
+    /// The new location of each texture that is still in the atlas, by its location before it was rebuilt. Textures
+    /// are matched by name, or by path if they have no name. Textures with neither are assumed not to have moved.
+    pub locations: HashMap<AtlasLocation, AtlasLocation>,
+    /// The location of each named texture after the atlas was rebuilt.
+    pub names: HashMap<String, AtlasLocation>,
+}
+
+impl AtlasRemapped {
+    /// Returns the new location of the texture that was at `location`, or `None` if it was removed.
+    pub fn location(&self, location: AtlasLocation) -> Option<AtlasLocation> {
+        self.locations.get(&location).copied()
+    }
+
+    /// Returns the new index of the texture that was at `index` on the first page, or `None` if it was removed or is
+    /// now on another page.
+    pub fn index(&self, index: usize) -> Option<usize> {
+        let first_page = |index| AtlasLocation { page: 0, index };
+        self.location(first_page(index))
+            .filter(|location| location.page == 0)
+            .map(|location| location.index)
+    }
+
+    /// Compares the textures of an atlas before and after it was rebuilt, returning `None` if none of them moved.
+    fn new(
+        atlas: AssetId<TextureAtlasAsset>,
+        old: &TextureAtlasPaths,
+        new: &TextureAtlasPaths,
+    ) -> Option<Self> {
+        let mut moved = false;
+        let mut locations = HashMap::new();
+        for entry in 0..old.path_indices.len() {
+            let location = old.location(entry);
+            let new_location = match (&old.name_indices[entry], &old.path_indices[entry]) {
+                (Some(name), _) => new.location_of(name),
+                (None, Some(path)) => new.texture_location(path.clone()),
+                (None, None) => new.entry(location).map(|_| location),
+            };
+            moved |= new_location != Some(location);
+            if let Some(new_location) = new_location {
+                locations.insert(location, new_location);
+            }
+        }
+        if !moved {
+            return None;
+        }
+
+        let names = new
+            .name_ids
+            .iter()
+            .map(|(name, &entry)| (name.clone(), new.location(entry)))
+            .collect();
+        Some(Self {
+            atlas,
+            locations,
+            names,
+        })
+    }
+}
+
+/// The textures of each loaded atlas as of its last change, which modified atlases are compared against.
+#[derive(Resource, Default)]
+pub(crate) struct AtlasSnapshots(HashMap<AssetId<TextureAtlasAsset>, TextureAtlasPaths>);
+
+/// Sends an [`AtlasRemapped`] event for each modified atlas whose textures have moved.
+pub(crate) fn remap_atlases(
+    mut events: EventReader<AssetEvent<TextureAtlasAsset>>,
+    atlases: Res<Assets<TextureAtlasAsset>>,
+    mut snapshots: ResMut<AtlasSnapshots>,
+    mut remapped: EventWriter<AtlasRemapped>,
+) {
+    for event in events.read() {
+        match *event {
+            AssetEvent::Added { id } | AssetEvent::Modified { id } => {
+                let Some(atlas) = atlases.get(id) else {
+                    continue;
+                };
+                let old = snapshots.0.insert(id, atlas.paths.clone());
+                if let Some(event) = old.and_then(|old| AtlasRemapped::new(id, &old, &atlas.paths))
+                {
+                    debug!("Textures of atlas {id} moved when it was rebuilt");
+                    remapped.send(event);
+                }
+            }
+            AssetEvent::Removed { id } => {
+                snapshots.0.remove(&id);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Updates the [`TextureAtlas`] of each [`Sprite`] using a page of an atlas that sent an [`AtlasRemapped`] event, so
+/// that it keeps displaying the same texture. Sprites whose texture was removed are left as is.
+pub(crate) fn patch_sprites(
+    mut remapped: EventReader<AtlasRemapped>,
+    atlases: Res<Assets<TextureAtlasAsset>>,
+    mut sprites: Query<&mut Sprite>,
+) {
+    for event in remapped.read() {
+        let Some(atlas) = atlases.get(event.atlas) else {
+            continue;
+        };
+        let page_of = |layout: AssetId<TextureAtlasLayout>| {
+            atlas
+                .pages
+                .iter()
+                .position(|page| page.layout.id() == layout)
+        };
+        for sprite in &mut sprites {
+            let Some(texture_atlas) = sprite.texture_atlas.as_ref() else {
+                continue;
+            };
+            let Some(page) = page_of(texture_atlas.layout.id()) else {
+                continue;
+            };
+            let old = AtlasLocation {
+                page,
+                index: texture_atlas.index,
+            };
+            let Some(new) = event.location(old).filter(|&new| new != old) else {
+                continue;
+            };
+            // Sprites are only marked as changed once they are known to need patching
+            let sprite = sprite.into_inner();
+            let Some(texture_atlas) = sprite.texture_atlas.as_mut() else {
+                continue;
+            };
+            texture_atlas.index = new.index;
+            // The atlas's pages are reloaded in place, so only textures that moved page need new handles
+            if new.page != old.page {
+                let (old_page, new_page) = (&atlas.pages[old.page], &atlas.pages[new.page]);
+                texture_atlas.layout = new_page.layout.clone();
+                if sprite.image == old_page.texture {
+                    sprite.image = new_page.texture.clone();
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,368 @@
+//! Converting textures into signed distance fields for atlases built with `distance_field`.
+//!
+//! Raster textures are converted from their alpha, which is thresholded at `0.5` and measured with an exact Euclidean
+//! distance transform. Glyphs rasterized from a `font` are converted from their outlines instead, which places edges
+//! more precisely and, for multi-channel fields, keeps corners sharp by giving the edges on either side of each
+//! corner different channels.
+
+use bevy::prelude::*;
+#[cfg(feature = "font")]
+use bevy::render::render_resource::TextureFormat;
+
+use super::{packer, DistanceField};
+
+/// A squared distance larger than any within an image, used for pixels with nothing to measure the distance to.
+const FAR: f64 = 1e20;
+
+/// Converts an image into a distance field of its alpha, grown by the padding of the field on every side and with the
+/// same format. Returns `None` if the image's format isn't supported.
+pub(crate) fn from_image(image: &Image, field: DistanceField) -> Option<Image> {
+    let pixels = packer::linear_pixels(image)?;
+    let size = image.size();
+    let padding = field.padding();
+    let grown = size + 2 * padding;
+    let (width, height) = (grown.x as usize, grown.y as usize);
+    let coverage: Vec<f32> = (0..width * height)
+        .map(|index| {
+            let position = IVec2::new((index % width) as i32, (index / width) as i32);
+            let position = position - padding as i32;
+            match position.cmpge(IVec2::ZERO).all() && position.cmplt(size.as_ivec2()).all() {
+                true => pixels[(position.y * size.x as i32 + position.x) as usize].w,
+                false => 0.0,
+            }
+        })
+        .collect();
+
+    // The squared distance of each pixel from the nearest pixel inside and outside of the shape
+    let mut inside: Vec<f64> = coverage
+        .iter()
+        .map(|&alpha| if alpha >= 0.5 { 0.0 } else { FAR })
+        .collect();
+    let mut outside: Vec<f64> = coverage
+        .iter()
+        .map(|&alpha| if alpha >= 0.5 { FAR } else { 0.0 })
+        .collect();
+    distance_transform(&mut inside, width, height);
+    distance_transform(&mut outside, width, height);
+
+    let values: Vec<Vec4> = coverage
+        .iter()
+        .enumerate()
+        .map(|(index, &alpha)| {
+            // The edge is halfway between the centers of the pixels on either side of it
+            let distance = match alpha >= 0.5 {
+                true => outside[index].sqrt() as f32 - 0.5,
+                false => 0.5 - inside[index].sqrt() as f32,
+            };
+            // Partially covered pixels place the edge within them more precisely
+            let distance = match alpha > 0.0 && alpha < 1.0 && distance.abs() <= 1.0 {
+                true => alpha - 0.5,
+                false => distance,
+            };
+            Vec4::splat(encode(distance, field.range))
+        })
+        .collect();
+    let format = image.texture_descriptor.format;
+    let mut image = packer::blank(grown, format);
+    image.data = packer::encode_pixels(&values, format);
+    Some(image)
+}
+
+/// Returns the value that a signed distance in pixels is stored as, in the range `0..=1`.
+fn encode(distance: f32, range: f32) -> f32 {
+    (distance / range + 0.5).clamp(0.0, 1.0)
+}
+
+/// Replaces the squared distance of each pixel of a grid from the nearest pixel with a distance of `0` with the exact
+/// squared Euclidean distance, using the algorithm of Felzenszwalb and Huttenlocher.
+fn distance_transform(grid: &mut [f64], width: usize, height: usize) {
+    let mut line = vec![0.0; width.max(height)];
+    let mut scratch = Scratch::new(width.max(height));
+    for x in 0..width {
+        for y in 0..height {
+            line[y] = grid[y * width + x];
+        }
+        scratch.transform(&mut line[..height]);
+        for y in 0..height {
+            grid[y * width + x] = line[y];
+        }
+    }
+    for row in grid.chunks_exact_mut(width) {
+        scratch.transform(row);
+    }
+}
+
+/// The buffers of the one-dimensional distance transform, which are reused for each row and column.
+struct Scratch {
+    /// The squared distances of the line before it was transformed.
+    source: Vec<f64>,
+    /// The positions of the parabolas that make up the lower envelope.
+    parabolas: Vec<usize>,
+    /// The boundaries between the parabolas of the lower envelope.
+    boundaries: Vec<f64>,
+}
+
+impl Scratch {
+    fn new(length: usize) -> Self {
+        Self {
+            source: vec![0.0; length],
+            parabolas: vec![0; length],
+            boundaries: vec![0.0; length + 1],
+        }
+    }
+
+    /// Transforms the squared distances of a single row or column.
+    fn transform(&mut self, line: &mut [f64]) {
+        let source = &mut self.source[..line.len()];
+        source.copy_from_slice(line);
+        let intersection = |a: usize, b: usize| {
+            let (fa, fb) = (source[a] + (a * a) as f64, source[b] + (b * b) as f64);
+            (fb - fa) / (2 * b - 2 * a) as f64
+        };
+
+        let mut count = 0;
+        self.parabolas[0] = 0;
+        self.boundaries[0] = f64::NEG_INFINITY;
+        self.boundaries[1] = f64::INFINITY;
+        for position in 1..line.len() {
+            let mut boundary = intersection(self.parabolas[count], position);
+            while boundary <= self.boundaries[count] {
+                count -= 1;
+                boundary = intersection(self.parabolas[count], position);
+            }
+            count += 1;
+            self.parabolas[count] = position;
+            self.boundaries[count] = boundary;
+            self.boundaries[count + 1] = f64::INFINITY;
+        }
+
+        let mut parabola = 0;
+        for (position, distance) in line.iter_mut().enumerate() {
+            while self.boundaries[parabola + 1] < position as f64 {
+                parabola += 1;
+            }
+            let nearest = self.parabolas[parabola];
+            let offset = position.abs_diff(nearest) as f64;
+            *distance = offset * offset + source[nearest];
+        }
+    }
+}
+
+/// A closed contour of an outline, made up of edges that each end where the next one begins. Each edge is a polyline
+/// of at least two points, along which the outline is smooth.
+#[cfg(feature = "font")]
+pub(crate) type Contour = Vec<Vec<Vec2>>;
+
+/// The channels of a multi-channel field that a segment of an outline contributes to, as bits of red, green, and blue.
+#[cfg(feature = "font")]
+const WHITE: u8 = 0b111;
+
+/// The colors that the edges between corners of a contour cycle through, which each share one channel with the
+/// others. These are cyan, magenta, and yellow.
+#[cfg(feature = "font")]
+const CORNER_COLORS: [u8; 3] = [0b110, 0b101, 0b011];
+
+/// A straight segment of an outline.
+#[cfg(feature = "font")]
+struct Segment {
+    start: Vec2,
+    end: Vec2,
+    /// The channels of a multi-channel field that the segment contributes to.
+    channels: u8,
+    /// Whether the segment starts or ends at a corner, beyond which its distance is measured along its line, so that
+    /// the corner stays sharp where two channels meet.
+    corner_start: bool,
+    corner_end: bool,
+}
+
+/// The distance from a point to the nearest segment found so far.
+#[cfg(feature = "font")]
+#[derive(Clone, Copy)]
+struct Nearest {
+    distance: f32,
+    /// How close to parallel the segment is to the direction of the point from it, which decides between segments at
+    /// the same distance, such as those meeting at a corner.
+    parallel: f32,
+    /// The signed distance of the point, which is measured along the segment's line beyond its corners.
+    signed: f32,
+    /// The side of the segment that the point is on.
+    side: f32,
+}
+
+#[cfg(feature = "font")]
+impl Nearest {
+    const NONE: Self = Self {
+        distance: f32::INFINITY,
+        parallel: f32::INFINITY,
+        signed: f32::NEG_INFINITY,
+        side: 1.0,
+    };
+
+    fn is_closer_than(&self, other: &Self) -> bool {
+        const EPSILON: f32 = 1e-4;
+        self.distance < other.distance - EPSILON
+            || (self.distance <= other.distance + EPSILON && self.parallel < other.parallel)
+    }
+}
+
+#[cfg(feature = "font")]
+impl Segment {
+    /// Measures the distance from `point` to the segment.
+    fn measure(&self, point: Vec2) -> Nearest {
+        let direction = self.end - self.start;
+        let offset = point - self.start;
+        let along = offset.dot(direction) / direction.length_squared();
+        let side = direction.perp_dot(offset).signum();
+        let closest = self.start + direction * along.clamp(0.0, 1.0);
+        let distance = point.distance(closest);
+        let parallel = match along {
+            0.0..=1.0 => 0.0,
+            _ => direction
+                .normalize()
+                .dot((point - closest).normalize_or_zero())
+                .abs(),
+        };
+        let beyond_corner = (along < 0.0 && self.corner_start) || (along > 1.0 && self.corner_end);
+        let signed = match beyond_corner {
+            true => direction.perp_dot(offset) / direction.length(),
+            false => distance * side,
+        };
+        Nearest {
+            distance,
+            parallel,
+            signed,
+            side,
+        }
+    }
+
+    /// Returns how the segment changes the winding number of `point`, by whether it crosses the ray to its right.
+    fn winding(&self, point: Vec2) -> i32 {
+        if (self.start.y <= point.y) == (self.end.y <= point.y) {
+            return 0;
+        }
+        let along = (point.y - self.start.y) / (self.end.y - self.start.y);
+        match self.start.x + (self.end.x - self.start.x) * along > point.x {
+            true if self.end.y > self.start.y => 1,
+            true => -1,
+            false => 0,
+        }
+    }
+}
+
+/// Returns the segments of the contours of an outline, with the channels of a multi-channel field that they each
+/// contribute to if `multi_channel` is set.
+#[cfg(feature = "font")]
+fn segments(contours: &[Contour], multi_channel: bool) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    for contour in contours {
+        let direction = |edge: &[Vec2], end: bool| match end {
+            true => (edge[edge.len() - 1] - edge[edge.len() - 2]).normalize_or_zero(),
+            false => (edge[1] - edge[0]).normalize_or_zero(),
+        };
+        // An edge starts at a corner if it turns sharply from the edge before it
+        let corners: Vec<bool> = (0..contour.len())
+            .map(|index| {
+                let previous = &contour[(index + contour.len() - 1) % contour.len()];
+                let (before, after) =
+                    (direction(previous, true), direction(&contour[index], false));
+                multi_channel && (before.dot(after) <= 0.0 || before.perp_dot(after).abs() > 0.14)
+            })
+            .collect();
+
+        // The edges between each pair of corners share a color, which differs from the colors on either side of them.
+        // Contours with fewer than two corners stay white, as they have no corners to keep sharp between colors.
+        let mut colors = vec![WHITE; contour.len()];
+        let starts: Vec<usize> = (0..contour.len()).filter(|&index| corners[index]).collect();
+        if starts.len() >= 2 {
+            for (spline, &start) in starts.iter().enumerate() {
+                // The last color also has to differ from the first, which it follows around the contour
+                let color = match spline % 3 {
+                    0 if spline > 0 && spline == starts.len() - 1 => CORNER_COLORS[1],
+                    index => CORNER_COLORS[index],
+                };
+                let end = starts
+                    .get(spline + 1)
+                    .copied()
+                    .unwrap_or(starts[0] + contour.len());
+                for index in start..end {
+                    colors[index % contour.len()] = color;
+                }
+            }
+        }
+
+        for (index, edge) in contour.iter().enumerate() {
+            let corner_end = starts.len() >= 2 && corners[(index + 1) % contour.len()];
+            for (point, pair) in edge.windows(2).enumerate() {
+                if pair[0] == pair[1] {
+                    continue;
+                }
+                segments.push(Segment {
+                    start: pair[0],
+                    end: pair[1],
+                    channels: colors[index],
+                    corner_start: starts.len() >= 2 && corners[index] && point == 0,
+                    corner_end: corner_end && point == edge.len() - 2,
+                });
+            }
+        }
+    }
+    segments
+}
+
+/// Generates a distance field of the given size from an outline in its pixel coordinates, as an RGBA8 image. The
+/// alpha channel always stores the true distance, which the color channels also store unless the field is
+/// multi-channel.
+#[cfg(feature = "font")]
+pub(crate) fn from_outline(contours: &[Contour], size: UVec2, field: DistanceField) -> Image {
+    let segments = segments(contours, field.multi_channel);
+    let mut values = Vec::with_capacity((size.x * size.y) as usize);
+    for y in 0..size.y {
+        for x in 0..size.x {
+            let point = Vec2::new(x as f32, y as f32) + 0.5;
+            let mut nearest = Nearest::NONE;
+            let mut channels = [Nearest::NONE; 3];
+            let mut winding = 0;
+            for segment in &segments {
+                winding += segment.winding(point);
+                let measured = segment.measure(point);
+                if measured.is_closer_than(&nearest) {
+                    nearest = measured;
+                }
+                for (channel, nearest) in channels.iter_mut().enumerate() {
+                    if segment.channels & (1 << channel) != 0 && measured.is_closer_than(nearest) {
+                        *nearest = measured;
+                    }
+                }
+            }
+
+            // Contours may wind either way, so signs are flipped if the nearest segment disagrees with the winding
+            let inside = winding != 0;
+            let distance = if inside {
+                nearest.distance
+            } else {
+                -nearest.distance
+            };
+            let flip = if (nearest.side > 0.0) == inside {
+                1.0
+            } else {
+                -1.0
+            };
+            let [red, green, blue] = channels.map(|channel| match channel.distance.is_finite() {
+                true => channel.signed * flip,
+                false => distance,
+            });
+            // Pixels whose channels disagree with the true distance about which side of the edge they are on would
+            // draw artifacts, so they fall back to the true distance
+            let median = red.max(green).min(red.min(green).max(blue));
+            let color = match field.multi_channel && (median > 0.0) == inside {
+                true => Vec3::new(red, green, blue),
+                false => Vec3::splat(distance),
+            };
+            values
+                .push(Vec4::from((color, distance)).map(|distance| encode(distance, field.range)));
+        }
+    }
+    let mut image = packer::blank(size, TextureFormat::Rgba8Unorm);
+    image.data = packer::encode_pixels(&values, TextureFormat::Rgba8Unorm);
+    image
+}
@@ -0,0 +1,269 @@
+//! Loading of Sparrow and Starling XML texture atlases, including the sheets of Kenney asset packs.
+
+use std::str::FromStr;
+
+use bevy::{
+    asset::{
+        io::Reader, AssetLoader, AsyncReadExt, LoadContext, LoadDirectError, ParseAssetPathError,
+    },
+    prelude::*,
+};
+use thiserror::Error;
+
+use super::{TextureAtlasAsset, TextureAtlasPaths, TextureInfo, TextureTrim};
+
+/// Errors encountered by [`SparrowLoader`].
+#[derive(Debug, Error)]
+pub enum SparrowLoaderError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Parse(#[from] SparrowParseError),
+    #[error(transparent)]
+    ParseAssetPath(#[from] ParseAssetPathError),
+    #[error(transparent)]
+    LoadDirect(#[from] LoadDirectError),
+}
+
+/// An error in the contents of a Sparrow XML atlas.
+#[derive(Debug, Error)]
+pub enum SparrowParseError {
+    #[error(transparent)]
+    Xml(#[from] roxmltree::Error),
+    #[error("Expected a `TextureAtlas` element with an `imagePath` attribute")]
+    MissingImagePath,
+    #[error("The sub-texture on line {line} is missing its `{attribute}` attribute")]
+    MissingAttribute { line: u32, attribute: &'static str },
+    #[error("The `{attribute}` attribute of the sub-texture on line {line} is invalid")]
+    InvalidAttribute { line: u32, attribute: &'static str },
+}
+
+/// An [`AssetLoader`] that loads a [`TextureAtlasAsset`] from a Sparrow or Starling XML texture atlas, as exported by
+/// many 2D tools. The atlas image is loaded from the path in the `imagePath` attribute of its `TextureAtlas` element,
+/// relative to the XML file.
+///
+/// Each `SubTexture` element is named after its `name` attribute. Its `frame*` attributes are kept as its
+/// [`TextureInfo::trim`], and its `rotated` and `pivotX`/`pivotY` attributes are kept as its rotation and pivot.
+///
+/// This is also the format of the XML sheets that ship with [Kenney](https://kenney.nl/) asset packs, so those can be
+/// loaded as they are. The `sprite` elements of TexturePacker's "Generic XML" format are read as well, with their
+/// `n` attribute as their name, `oX`/`oY`/`oW`/`oH` as their trim, `r="y"` marking rotation, and `pX`/`pY` as their
+/// normalized pivot.
+///
+/// [`TextureInfo::trim`]: crate::TextureInfo::trim
+pub struct SparrowLoader;
+
+impl AssetLoader for SparrowLoader {
+    type Asset = TextureAtlasAsset;
+    type Settings = ();
+    type Error = SparrowLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        &(): &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        debug!("Loading Sparrow atlas from {:?}", load_context.path());
+        let sheet = {
+            let mut text = String::new();
+            reader.read_to_string(&mut text).await?;
+            parse(&text)?
+        };
+
+        let image_path = load_context.asset_path().resolve_embed(&sheet.image)?;
+        trace!("Loading Sparrow atlas image from: {image_path}");
+        let texture = load_context
+            .loader()
+            .immediate()
+            .load::<Image>(image_path)
+            .await?;
+
+        let mut paths = TextureAtlasPaths::default();
+        let mut textures = Vec::with_capacity(sheet.sub_textures.len());
+        for (name, rect, info) in sheet.sub_textures {
+            paths.add(None, Some(name), info);
+            textures.push(rect);
+        }
+
+        let layout = TextureAtlasLayout {
+            size: texture.get().size(),
+            textures,
+        };
+        debug!(
+            "Loaded Sparrow atlas containing {} sub-textures",
+            paths.path_indices.len(),
+        );
+        Ok(TextureAtlasAsset::from_pages(
+            load_context,
+            [(layout, texture)],
+            paths,
+        ))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["xml"]
+    }
+}
+
+/// The contents of a Sparrow XML atlas.
+struct SparrowSheet {
+    /// The path of the atlas image, relative to the XML file.
+    image: String,
+    /// The name, area, and info of each sub-texture.
+    sub_textures: Vec<(String, URect, TextureInfo)>,
+}
+
+/// Parses a Sparrow XML atlas.
+fn parse(text: &str) -> Result<SparrowSheet, SparrowParseError> {
+    let document = roxmltree::Document::parse(text)?;
+    let root = document.root_element();
+    let image = root
+        .has_tag_name("TextureAtlas")
+        .then(|| root.attribute("imagePath"))
+        .flatten()
+        .ok_or(SparrowParseError::MissingImagePath)?;
+
+    let mut sub_textures = Vec::new();
+    for node in root.children().filter(|node| node.is_element()) {
+        let line = document.text_pos_at(node.range().start).row;
+        let optional = |attribute: &'static str| {
+            node.attribute(attribute)
+                .map(|value| {
+                    value
+                        .trim()
+                        .parse::<f32>()
+                        .map_err(|_| SparrowParseError::InvalidAttribute { line, attribute })
+                })
+                .transpose()
+        };
+        let required = |attribute: &'static str| {
+            optional(attribute)?.ok_or(SparrowParseError::MissingAttribute { line, attribute })
+        };
+        let name = |attribute: &'static str| {
+            node.attribute(attribute)
+                .ok_or(SparrowParseError::MissingAttribute { line, attribute })
+        };
+
+        let sub_texture = match node.tag_name().name() {
+            "SubTexture" => {
+                let rotated = match node.attribute("rotated") {
+                    Some(rotated) => bool::from_str(rotated).map_err(|_| {
+                        SparrowParseError::InvalidAttribute {
+                            line,
+                            attribute: "rotated",
+                        }
+                    })?,
+                    None => false,
+                };
+                // Rotated sub-textures are rotated 90° clockwise, and their size is the size of their area
+                let size = Vec2::new(required("width")?, required("height")?);
+                let frame = match (optional("frameWidth")?, optional("frameHeight")?) {
+                    (Some(width), Some(height)) if width > 0.0 && height > 0.0 => {
+                        let offset = Vec2::new(
+                            optional("frameX")?.unwrap_or_default(),
+                            optional("frameY")?.unwrap_or_default(),
+                        );
+                        Some((-offset, Vec2::new(width, height)))
+                    }
+                    _ => None,
+                };
+                SubTexture {
+                    name: name("name")?,
+                    position: Vec2::new(required("x")?, required("y")?),
+                    size: if rotated { size.yx() } else { size },
+                    rotated,
+                    frame,
+                    pivot: match (optional("pivotX")?, optional("pivotY")?) {
+                        (None, None) => None,
+                        (x, y) => Some(Vec2::new(x.unwrap_or(0.0), y.unwrap_or(0.0))),
+                    },
+                    normalized_pivot: false,
+                }
+            }
+            "sprite" => {
+                let rotated = node.attribute("r") == Some("y");
+                let frame = match (optional("oW")?, optional("oH")?) {
+                    (Some(width), Some(height)) if width > 0.0 && height > 0.0 => {
+                        let offset = Vec2::new(
+                            optional("oX")?.unwrap_or_default(),
+                            optional("oY")?.unwrap_or_default(),
+                        );
+                        Some((offset, Vec2::new(width, height)))
+                    }
+                    _ => None,
+                };
+                SubTexture {
+                    name: name("n")?,
+                    position: Vec2::new(required("x")?, required("y")?),
+                    size: Vec2::new(required("w")?, required("h")?),
+                    rotated,
+                    frame,
+                    pivot: optional("pX")?
+                        .zip(optional("pY")?)
+                        .map(|(x, y)| Vec2::new(x, y)),
+                    normalized_pivot: true,
+                }
+            }
+            _ => continue,
+        };
+        sub_textures.push(sub_texture.into_parts());
+    }
+
+    Ok(SparrowSheet {
+        image: image.to_owned(),
+        sub_textures,
+    })
+}
+
+/// A sub-texture described by either a Sparrow `SubTexture` element or a generic XML `sprite` element.
+struct SubTexture<'a> {
+    name: &'a str,
+    /// The position of the sub-texture's area within the atlas.
+    position: Vec2,
+    /// The size of the sub-texture before it was rotated.
+    size: Vec2,
+    rotated: bool,
+    /// The offset of the sub-texture within the untrimmed image, and the untrimmed image's size.
+    frame: Option<(Vec2, Vec2)>,
+    /// The pivot, measured from the top left corner of the untrimmed image.
+    pivot: Option<Vec2>,
+    /// Whether the pivot is normalized rather than measured in pixels.
+    normalized_pivot: bool,
+}
+
+impl SubTexture<'_> {
+    fn into_parts(self) -> (String, URect, TextureInfo) {
+        let size = self.size.as_uvec2();
+        let trim = self
+            .frame
+            .map(|(offset, source_size)| TextureTrim {
+                offset: offset.max(Vec2::ZERO).as_uvec2(),
+                source_size: source_size.as_uvec2(),
+            })
+            .filter(|trim| trim.offset != UVec2::ZERO || trim.source_size != size);
+
+        let source_size = self.frame.map_or(self.size, |(_, size)| size);
+        let pivot = self.pivot.map(|pivot| {
+            let pivot = if self.normalized_pivot {
+                pivot
+            } else {
+                pivot / source_size
+            };
+            Vec2::new(pivot.x - 0.5, 0.5 - pivot.y)
+        });
+
+        let position = self.position.as_uvec2();
+        let packed_size = if self.rotated { size.yx() } else { size };
+        (
+            self.name.to_owned(),
+            URect::from_corners(position, position + packed_size),
+            TextureInfo {
+                rotated: self.rotated,
+                trim,
+                pivot,
+                ..default()
+            },
+        )
+    }
+}
@@ -0,0 +1,297 @@
+//! Loading atlases and spawning their sprites by name, without looking up labeled sub-assets or texture indices by
+//! hand.
+
+use bevy::{asset::AssetPath, prelude::*, utils::HashSet};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    atlas_prefix, events::AtlasEvent, handle::AtlasHandle, TextureAtlasAsset, TextureInfo,
+};
+
+/// The handles needed to display an atlas's first page before the atlas has loaded. Returned by
+/// [`AtlasAssetServerExt::load_atlas`].
+#[derive(Debug, Clone)]
+pub struct AtlasHandles {
+    /// The atlas itself.
+    pub atlas: AtlasHandle,
+    /// The texture of the atlas's first page.
+    pub texture: Handle<Image>,
+    /// The layout of the atlas's first page.
+    pub layout: Handle<TextureAtlasLayout>,
+}
+
+impl AtlasHandles {
+    /// Creates a [`TextureAtlas`] handle for the given index on the first page.
+    pub fn texture_atlas(&self, index: usize) -> TextureAtlas {
+        TextureAtlas {
+            layout: self.layout.clone(),
+            index,
+        }
+    }
+
+    /// Creates a [`Sprite`] displaying the given index on the first page.
+    pub fn sprite(&self, index: usize) -> Sprite {
+        Sprite::from_atlas_image(self.texture.clone(), self.texture_atlas(index))
+    }
+}
+
+/// A [`Component`] that displays the texture with the given `name` from an atlas on its entity's [`Sprite`].
+///
+/// The sprite's image, texture atlas, and anchor are set once the atlas has loaded, and again whenever the atlas is
+/// rebuilt or this component is changed. Other fields of the sprite, such as its color, are left as is. Entities
+/// without a sprite are given one once the atlas has loaded, so that nothing is displayed until then. Textures that
+/// were packed rotated (see [`TextureInfo::rotated`]) are displayed upright by rotating the entity's [`Transform`] by
+/// [`TextureInfo::rotation`], which is undone again if the sprite changes to a texture that isn't rotated.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct AtlasSprite {
+    /// The atlas containing the texture.
+    pub atlas: AtlasHandle,
+    /// The name of the texture.
+    pub name: String,
+}
+
+impl AtlasSprite {
+    /// Creates a component displaying the texture with the given `name` from `atlas`.
+    pub fn new(atlas: impl Into<AtlasHandle>, name: impl Into<String>) -> Self {
+        Self {
+            atlas: atlas.into(),
+            name: name.into(),
+        }
+    }
+
+    /// Returns a serializable reference to this sprite's texture, or `None` if its atlas wasn't loaded from a path.
+    pub fn to_ref(&self) -> Option<AtlasSpriteRef> {
+        let path = self.atlas.0.path()?.clone_owned();
+        Some(AtlasSpriteRef::new(path, self.name.clone()))
+    }
+}
+
+/// A serializable reference to a texture in an atlas, by the atlas's asset path and the texture's name.
+///
+/// Handles and texture indices can't be saved, and indices change whenever an atlas is re-packed. Storing this
+/// component in `DynamicScene`s and save games instead of an [`AtlasSprite`] keeps sprites pointing at the same
+/// texture after their atlas is rebuilt. When it is added or changed, the atlas is loaded and an [`AtlasSprite`] is
+/// inserted on its entity, which displays the texture once the atlas has loaded.
+///
+/// Scenes are serialized through reflection, which can't save handles, so [`AtlasSprite`] and [`Sprite`] should be
+/// excluded from them with `DynamicSceneBuilder::deny_component`. Save games can serialize these directly:
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_mod_atlas_loader::sprite::{AtlasSprite, AtlasSpriteRef};
+/// fn save(sprites: Query<&AtlasSprite>) -> String {
+///     let refs: Vec<AtlasSpriteRef> = sprites.iter().filter_map(AtlasSprite::to_ref).collect();
+///     ron::to_string(&refs).unwrap()
+/// }
+///
+/// fn load(mut commands: Commands, save: &str) {
+///     for sprite_ref in ron::from_str::<Vec<AtlasSpriteRef>>(save).unwrap() {
+///         commands.spawn(sprite_ref);
+///     }
+/// }
+/// ```
+#[derive(Component, Reflect, Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[reflect(Component, Serialize, Deserialize)]
+pub struct AtlasSpriteRef {
+    /// The asset path of the atlas, including its label if it is a named atlas.
+    pub atlas: AssetPath<'static>,
+    /// The name of the texture.
+    pub name: String,
+}
+
+impl AtlasSpriteRef {
+    /// Creates a reference to the texture with the given `name` in the atlas at `atlas`.
+    pub fn new<'a>(atlas: impl Into<AssetPath<'a>>, name: impl Into<String>) -> Self {
+        Self {
+            atlas: atlas.into().into_owned(),
+            name: name.into(),
+        }
+    }
+
+    /// Loads the referenced atlas, returning an [`AtlasSprite`] that displays the texture once it has loaded.
+    pub fn load(&self, assets: &AssetServer) -> AtlasSprite {
+        AtlasSprite::new(assets.load(self.atlas.clone()), self.name.clone())
+    }
+}
+
+/// Extends [`AssetServer`] with methods for loading atlases along with their labeled sub-assets.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_mod_atlas_loader::sprite::AtlasAssetServerExt;
+/// fn startup(mut commands: Commands, assets: Res<AssetServer>) {
+///     commands.spawn(assets.load_atlas_sprite("hero.atlas.ron", "hero_idle"));
+/// }
+/// ```
+pub trait AtlasAssetServerExt {
+    /// Loads the atlas at `path`, along with the texture and layout of its first page. Named atlases (e.g.
+    /// `my_atlas.atlas.ron#ui`) load their own sub-assets.
+    fn load_atlas<'a>(&self, path: impl Into<AssetPath<'a>>) -> AtlasHandles;
+
+    /// Loads the atlas at `path` like [`AtlasAssetServerExt::load_atlas`], and returns a [`Sprite`] and an
+    /// [`AtlasSprite`] that displays the texture with the given `name` once the atlas has loaded, so that both can be
+    /// spawned immediately.
+    fn load_atlas_sprite<'a>(
+        &self,
+        path: impl Into<AssetPath<'a>>,
+        name: impl Into<String>,
+    ) -> (Sprite, AtlasSprite);
+}
+
+impl AtlasAssetServerExt for AssetServer {
+    fn load_atlas<'a>(&self, path: impl Into<AssetPath<'a>>) -> AtlasHandles {
+        let path = path.into().into_owned();
+        let prefix = atlas_prefix(path.label());
+        AtlasHandles {
+            atlas: AtlasHandle::new(self.load(path.clone())),
+            texture: self.load(path.clone().with_label(format!("{prefix}texture"))),
+            layout: self.load(path.with_label(format!("{prefix}layout"))),
+        }
+    }
+
+    fn load_atlas_sprite<'a>(
+        &self,
+        path: impl Into<AssetPath<'a>>,
+        name: impl Into<String>,
+    ) -> (Sprite, AtlasSprite) {
+        let handles = self.load_atlas(path);
+        // The first page's handles are used until the texture is found, so that its image is kept loading
+        (handles.sprite(0), AtlasSprite::new(handles.atlas, name))
+    }
+}
+
+/// Extends [`Commands`] with methods for spawning sprites from atlases.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_mod_atlas_loader::{handle::AtlasHandle, sprite::AtlasCommandsExt};
+/// fn spawn_hero(mut commands: Commands, atlas: Res<AtlasHandle>) {
+///     commands.spawn_atlas_sprite(&atlas, "hero_idle", Transform::from_xyz(0.0, 100.0, 0.0));
+/// }
+/// ```
+pub trait AtlasCommandsExt {
+    /// Spawns an entity with the given `transform` displaying the texture with the given `name` from `atlas`. The
+    /// entity's [`Sprite`] is inserted by its [`AtlasSprite`] once the atlas has loaded, or before the next frame is
+    /// drawn if it already has.
+    fn spawn_atlas_sprite(
+        &mut self,
+        atlas: &AtlasHandle,
+        name: impl Into<String>,
+        transform: Transform,
+    ) -> EntityCommands<'_>;
+}
+
+impl AtlasCommandsExt for Commands<'_, '_> {
+    fn spawn_atlas_sprite(
+        &mut self,
+        atlas: &AtlasHandle,
+        name: impl Into<String>,
+        transform: Transform,
+    ) -> EntityCommands<'_> {
+        self.spawn((AtlasSprite::new(atlas.clone(), name), transform))
+    }
+}
+
+/// The rotation that [`resolve_atlas_sprites`] last applied to the [`Transform`] of an [`AtlasSprite`] to display its
+/// texture upright, so that it can be undone when the sprite changes texture.
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub(crate) struct AtlasSpriteRotation(Quat);
+
+/// Inserts an [`AtlasSprite`] for each [`AtlasSpriteRef`] that was added or changed, unless the entity already has
+/// one referencing the same texture.
+pub(crate) fn load_atlas_sprite_refs(
+    refs: Query<(Entity, &AtlasSpriteRef, Option<&AtlasSprite>), Changed<AtlasSpriteRef>>,
+    assets: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    for (entity, sprite_ref, atlas_sprite) in &refs {
+        if atlas_sprite.and_then(AtlasSprite::to_ref).as_ref() == Some(sprite_ref) {
+            continue;
+        }
+        commands.entity(entity).insert(sprite_ref.load(&assets));
+    }
+}
+
+/// The components of an [`AtlasSprite`]'s entity that [`resolve_atlas_sprites`] updates.
+type AtlasSpriteQuery = (
+    Entity,
+    Ref<'static, AtlasSprite>,
+    Option<&'static mut Sprite>,
+    Option<&'static mut Transform>,
+    Option<&'static AtlasSpriteRotation>,
+);
+
+/// Updates or inserts the [`Sprite`] of each [`AtlasSprite`] that was added or changed, or whose atlas was loaded or
+/// rebuilt. Sprites whose atlas isn't loaded yet are retried each update.
+pub(crate) fn resolve_atlas_sprites(
+    mut events: EventReader<AtlasEvent>,
+    atlases: Res<Assets<TextureAtlasAsset>>,
+    layouts: Res<Assets<TextureAtlasLayout>>,
+    mut sprites: Query<AtlasSpriteQuery>,
+    mut pending: Local<HashSet<Entity>>,
+    mut commands: Commands,
+) {
+    let loaded: HashSet<AssetId<TextureAtlasAsset>> = events
+        .read()
+        .filter(|event| !matches!(event, AtlasEvent::Failed { .. }))
+        .map(|event| event.handle().id())
+        .collect();
+    for (entity, atlas_sprite, sprite, transform, applied) in &mut sprites {
+        let loaded = loaded.contains(&atlas_sprite.atlas.id());
+        if !atlas_sprite.is_changed() && !loaded && !pending.contains(&entity) {
+            continue;
+        }
+        if !atlas_sprite.atlas.is_ready(&atlases) {
+            pending.insert(entity);
+            continue;
+        }
+        pending.remove(&entity);
+
+        let Some(resolved) = atlas_sprite
+            .atlas
+            .sprite(&atlases, &layouts, &atlas_sprite.name)
+        else {
+            let atlas = atlas_sprite
+                .atlas
+                .0
+                .path()
+                .map_or_else(|| atlas_sprite.atlas.id().to_string(), ToString::to_string);
+            warn!("Atlas {atlas} has no texture named {:?}", atlas_sprite.name);
+            continue;
+        };
+        let rotation = atlas_sprite
+            .atlas
+            .get(&atlases)
+            .and_then(|atlas| atlas.info_of(&atlas_sprite.name))
+            .map_or(Quat::IDENTITY, TextureInfo::rotation);
+        let previous = applied.map_or(Quat::IDENTITY, |applied| applied.0);
+        if rotation != previous {
+            match transform {
+                Some(mut transform) => {
+                    transform.rotation = transform.rotation * previous.inverse() * rotation;
+                }
+                None => {
+                    commands
+                        .entity(entity)
+                        .insert(Transform::from_rotation(rotation));
+                }
+            }
+            commands
+                .entity(entity)
+                .insert(AtlasSpriteRotation(rotation));
+        }
+        match sprite {
+            Some(mut sprite) => {
+                sprite.image = resolved.image;
+                sprite.texture_atlas = resolved.texture_atlas;
+                sprite.anchor = resolved.anchor;
+            }
+            None => {
+                commands.entity(entity).insert(resolved);
+            }
+        }
+    }
+    // Forget sprites that were despawned or had their component removed while waiting for their atlas
+    pending.retain(|&entity| sprites.contains(entity));
+}
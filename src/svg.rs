@@ -0,0 +1,600 @@
+//! Rasterizing the SVG texture entries of atlas build configs for the `svg` feature.
+//!
+//! Only the subset of SVG used by typical icons is supported: `path`, `rect`, `circle`, `ellipse`, `line`, `polyline`,
+//! and `polygon` shapes within nested `g` groups, with transforms and solid fills and strokes given as attributes or
+//! inline styles. Gradients, patterns, clip paths, masks, filters, style sheets, `use` references, text, and embedded
+//! images are skipped, and are listed by [`Svg::skipped`] so that the build loader can warn about them.
+
+use std::{collections::BTreeSet, path::Path};
+
+use bevy::{
+    asset::RenderAssetUsages,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+use thiserror::Error;
+use zeno::{Angle, Cap, Fill, Join, Mask, Stroke, Style, Transform};
+
+/// Errors encountered while rasterizing an SVG image.
+#[derive(Debug, Error)]
+pub enum SvgError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Utf8(#[from] std::str::Utf8Error),
+    #[error(transparent)]
+    Xml(#[from] roxmltree::Error),
+    #[error("Expected an `svg` root element")]
+    NotSvg,
+    #[error("The image has no `width` and `height` or `viewBox` to give it a size")]
+    MissingSize,
+}
+
+/// Returns `true` if the file at `path` is an SVG image, going by its extension.
+pub(crate) fn is_svg(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("svg"))
+}
+
+/// A parsed SVG image.
+pub(crate) struct Svg {
+    /// The size of the image in CSS pixels.
+    size: Vec2,
+    /// The area of user space that is mapped onto the image.
+    view_box: Rect,
+    shapes: Vec<Shape>,
+    /// The unsupported elements and features that were skipped while parsing the image.
+    skipped: BTreeSet<String>,
+}
+
+/// A shape to draw, with its path data in user space.
+struct Shape {
+    path: String,
+    transform: Transform,
+    fill: Option<(Fill, Vec4)>,
+    stroke: Option<(StrokeStyle, Vec4)>,
+}
+
+#[derive(Clone, Copy)]
+struct StrokeStyle {
+    width: f32,
+    join: Join,
+    cap: Cap,
+    miter_limit: f32,
+}
+
+/// A paint given by a `fill` or `stroke` property.
+#[derive(Clone, Copy)]
+enum Paint {
+    None,
+    Color(Vec4),
+    CurrentColor,
+}
+
+/// The properties that shapes inherit from their ancestors.
+#[derive(Clone, Copy)]
+struct Inherited {
+    transform: Transform,
+    color: Vec4,
+    fill: Paint,
+    fill_opacity: f32,
+    fill_rule: Fill,
+    stroke: Paint,
+    stroke_opacity: f32,
+    stroke_style: StrokeStyle,
+    /// The product of the `opacity` of the element and its ancestors, which is applied to each shape on its own.
+    opacity: f32,
+}
+
+impl Svg {
+    /// Parses an SVG image.
+    pub fn parse(bytes: &[u8]) -> Result<Self, SvgError> {
+        let text = std::str::from_utf8(bytes)?;
+        // SVG files exported by editors often start with a DTD declaration, which must be explicitly allowed
+        let options = roxmltree::ParsingOptions {
+            allow_dtd: true,
+            ..default()
+        };
+        let document = roxmltree::Document::parse_with_options(text, options)?;
+        let root = document.root_element();
+        if !root.has_tag_name("svg") {
+            return Err(SvgError::NotSvg);
+        }
+
+        let view_box = root
+            .attribute("viewBox")
+            .map(numbers)
+            .filter(|values| values.len() == 4 && values[2] > 0.0 && values[3] > 0.0)
+            .map(|values| {
+                Rect::new(
+                    values[0],
+                    values[1],
+                    values[0] + values[2],
+                    values[1] + values[3],
+                )
+            });
+        let width = root.attribute("width").and_then(length);
+        let height = root.attribute("height").and_then(length);
+        let size = match (width, height, view_box) {
+            (Some(width), Some(height), _) => Vec2::new(width, height),
+            // A missing dimension follows the aspect ratio of the view box
+            (Some(width), None, Some(view_box)) => {
+                Vec2::new(width, width * view_box.height() / view_box.width())
+            }
+            (None, Some(height), Some(view_box)) => {
+                Vec2::new(height * view_box.width() / view_box.height(), height)
+            }
+            (None, None, Some(view_box)) => view_box.size(),
+            _ => return Err(SvgError::MissingSize),
+        };
+        let view_box = view_box.unwrap_or(Rect::from_corners(Vec2::ZERO, size));
+
+        let inherited = Inherited {
+            transform: Transform::IDENTITY,
+            color: Vec4::new(0.0, 0.0, 0.0, 1.0),
+            fill: Paint::Color(Vec4::new(0.0, 0.0, 0.0, 1.0)),
+            fill_opacity: 1.0,
+            fill_rule: Fill::NonZero,
+            stroke: Paint::None,
+            stroke_opacity: 1.0,
+            stroke_style: StrokeStyle {
+                width: 1.0,
+                join: Join::Miter,
+                cap: Cap::Butt,
+                miter_limit: 4.0,
+            },
+            opacity: 1.0,
+        };
+        let mut shapes = Vec::new();
+        let mut skipped = BTreeSet::new();
+        parse_children(root, inherited, &mut shapes, &mut skipped);
+        Ok(Self {
+            size,
+            view_box,
+            shapes,
+            skipped,
+        })
+    }
+
+    /// Returns the unsupported elements and features of the image that aren't drawn, in the order of their names.
+    pub fn skipped(&self) -> Vec<&str> {
+        self.skipped.iter().map(String::as_str).collect()
+    }
+
+    /// Returns the size that the image is rasterized at for the given resolution, rounded to whole pixels.
+    pub fn size(&self, dpi: f32) -> UVec2 {
+        (self.size * dpi / 96.0).round().as_uvec2().max(UVec2::ONE)
+    }
+
+    /// Rasterizes the image at `size`, scaling it uniformly to fit and centering it if its aspect ratio differs.
+    pub fn render(&self, size: UVec2) -> Image {
+        let scale = (size.as_vec2() / self.view_box.size()).min_element();
+        let offset =
+            (size.as_vec2() - self.view_box.size() * scale) / 2.0 - self.view_box.min * scale;
+        let view = Transform::scale(scale, scale).then_translate(offset.x, offset.y);
+
+        // Shapes are blended in the sRGB color space with premultiplied alpha, like browsers do
+        let mut pixels = vec![Vec4::ZERO; (size.x * size.y) as usize];
+        for shape in &self.shapes {
+            let transform = shape.transform.then(&view);
+            let stroke = shape.stroke.map(|(style, color)| {
+                let mut stroke = Stroke::new(style.width);
+                stroke
+                    .join(style.join)
+                    .cap(style.cap)
+                    .miter_limit(style.miter_limit);
+                (Style::Stroke(stroke), color)
+            });
+            let layers = shape
+                .fill
+                .map(|(fill, color)| (Style::Fill(fill), color))
+                .into_iter()
+                .chain(stroke);
+            for (style, color) in layers {
+                let (coverage, _) = Mask::new(shape.path.as_str())
+                    .style(style)
+                    .transform(Some(transform))
+                    .size(size.x, size.y)
+                    .render();
+                let color = (color.truncate() * color.w).extend(color.w);
+                for (pixel, &coverage) in pixels.iter_mut().zip(coverage.iter()) {
+                    if coverage > 0 {
+                        let source = color * (coverage as f32 / 255.0);
+                        *pixel = source + *pixel * (1.0 - source.w);
+                    }
+                }
+            }
+        }
+
+        let data = pixels
+            .iter()
+            .flat_map(|pixel| {
+                let color = match pixel.w > 0.0 {
+                    true => pixel.truncate() / pixel.w,
+                    false => Vec3::ZERO,
+                };
+                color
+                    .extend(pixel.w)
+                    .to_array()
+                    .map(|channel| (channel.clamp(0.0, 1.0) * 255.0).round() as u8)
+            })
+            .collect();
+        Image::new(
+            Extent3d {
+                width: size.x,
+                height: size.y,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::default(),
+        )
+    }
+}
+
+/// Adds the shapes of the children of an element, which inherit `inherited`, to `shapes`, and the unsupported
+/// elements and features that are skipped to `skipped`.
+fn parse_children(
+    node: roxmltree::Node,
+    inherited: Inherited,
+    shapes: &mut Vec<Shape>,
+    skipped: &mut BTreeSet<String>,
+) {
+    for node in node.children().filter(|node| node.is_element()) {
+        parse_element(node, inherited, shapes, skipped);
+    }
+}
+
+/// Adds the shapes of an element and its descendants.
+fn parse_element(
+    node: roxmltree::Node,
+    inherited: Inherited,
+    shapes: &mut Vec<Shape>,
+    skipped: &mut BTreeSet<String>,
+) {
+    let name = node.tag_name().name();
+    if property(node, "display") == Some("none") {
+        return;
+    }
+    let state = inherit(node, inherited, skipped);
+    let path = match name {
+        "g" | "a" => {
+            parse_children(node, state, shapes, skipped);
+            return;
+        }
+        "path" => node.attribute("d").map(str::to_string),
+        "rect" => rect_path(node),
+        "circle" => {
+            let radius = number(node, "r");
+            (radius > 0.0)
+                .then(|| ellipse_path(number(node, "cx"), number(node, "cy"), radius, radius))
+        }
+        "ellipse" => {
+            let radius = Vec2::new(number(node, "rx"), number(node, "ry"));
+            (radius.min_element() > 0.0)
+                .then(|| ellipse_path(number(node, "cx"), number(node, "cy"), radius.x, radius.y))
+        }
+        "line" => Some(format!(
+            "M{} {} L{} {}",
+            number(node, "x1"),
+            number(node, "y1"),
+            number(node, "x2"),
+            number(node, "y2")
+        )),
+        "polyline" | "polygon" => node.attribute("points").and_then(|points| {
+            let points = numbers(points);
+            (points.len() >= 4).then(|| {
+                let mut path = format!("M{} {}", points[0], points[1]);
+                for point in points[2..].chunks_exact(2) {
+                    path.push_str(&format!(" L{} {}", point[0], point[1]));
+                }
+                if name == "polygon" {
+                    path.push_str(" Z");
+                }
+                path
+            })
+        }),
+        // Definitions and metadata don't draw anything by themselves. The paint servers defined in them are reported
+        // where they are referenced instead
+        "defs" | "title" | "desc" | "metadata" => None,
+        // Elements of other namespaces, such as those added by editors, aren't drawn by browsers either
+        _ if node.tag_name().namespace() != root_namespace(node) => None,
+        // Unsupported elements such as text are skipped along with their children
+        _ => {
+            trace!("Skipping unsupported SVG element: {name}");
+            skipped.insert(format!("`{name}` elements"));
+            None
+        }
+    };
+    let Some(path) = path else {
+        return;
+    };
+    if matches!(property(node, "visibility"), Some("hidden" | "collapse")) {
+        return;
+    }
+
+    let paint = |paint: Paint, opacity: f32| {
+        let color = match paint {
+            Paint::None => return None,
+            Paint::Color(color) => color,
+            Paint::CurrentColor => state.color,
+        };
+        let alpha = color.w * opacity * state.opacity;
+        (alpha > 0.0).then(|| color.truncate().extend(alpha))
+    };
+    let fill = paint(state.fill, state.fill_opacity).map(|color| (state.fill_rule, color));
+    let stroke = paint(state.stroke, state.stroke_opacity)
+        .filter(|_| state.stroke_style.width > 0.0)
+        .map(|color| (state.stroke_style, color));
+    if fill.is_some() || stroke.is_some() {
+        shapes.push(Shape {
+            path,
+            transform: state.transform,
+            fill,
+            stroke,
+        });
+    }
+}
+
+/// Returns the namespace of the root `svg` element of the document containing `node`.
+fn root_namespace<'a>(node: roxmltree::Node<'a, '_>) -> Option<&'a str> {
+    node.document().root_element().tag_name().namespace()
+}
+
+/// Returns the properties of an element, given those it inherits from its parent. Unsupported properties that are
+/// ignored are added to `skipped`.
+fn inherit(
+    node: roxmltree::Node,
+    inherited: Inherited,
+    skipped: &mut BTreeSet<String>,
+) -> Inherited {
+    let mut state = inherited;
+    for name in ["clip-path", "mask", "filter"] {
+        if property(node, name).is_some_and(|value| value != "none") {
+            skipped.insert(format!("`{name}` properties"));
+        }
+    }
+    for name in ["fill", "stroke"] {
+        if property(node, name).is_some_and(|value| value.starts_with("url(")) {
+            skipped.insert("gradient and pattern paints".to_string());
+        }
+    }
+    if let Some(transform) = node.attribute("transform") {
+        state.transform = parse_transform(transform).then(&inherited.transform);
+    }
+    if let Some(color) = property(node, "color").and_then(parse_color) {
+        state.color = color;
+    }
+    if let Some(paint) = property(node, "fill").and_then(parse_paint) {
+        state.fill = paint;
+    }
+    if let Some(paint) = property(node, "stroke").and_then(parse_paint) {
+        state.stroke = paint;
+    }
+    let opacity = |name| property(node, name).and_then(parse_opacity);
+    state.fill_opacity = opacity("fill-opacity").unwrap_or(state.fill_opacity);
+    state.stroke_opacity = opacity("stroke-opacity").unwrap_or(state.stroke_opacity);
+    state.opacity *= opacity("opacity").unwrap_or(1.0);
+    match property(node, "fill-rule") {
+        Some("evenodd") => state.fill_rule = Fill::EvenOdd,
+        Some("nonzero") => state.fill_rule = Fill::NonZero,
+        _ => {}
+    }
+    let style = &mut state.stroke_style;
+    if let Some(width) = property(node, "stroke-width").and_then(length) {
+        style.width = width;
+    }
+    if let Some(limit) = property(node, "stroke-miterlimit").and_then(|value| value.parse().ok()) {
+        style.miter_limit = limit;
+    }
+    match property(node, "stroke-linejoin") {
+        Some("miter") => style.join = Join::Miter,
+        Some("round") => style.join = Join::Round,
+        Some("bevel") => style.join = Join::Bevel,
+        _ => {}
+    }
+    match property(node, "stroke-linecap") {
+        Some("butt") => style.cap = Cap::Butt,
+        Some("round") => style.cap = Cap::Round,
+        Some("square") => style.cap = Cap::Square,
+        _ => {}
+    }
+    state
+}
+
+/// Returns the value of a property of an element, from its `style` attribute if it is set there, and from its
+/// presentation attribute otherwise.
+fn property<'a>(node: roxmltree::Node<'a, '_>, name: &str) -> Option<&'a str> {
+    let style = node
+        .attribute("style")
+        .into_iter()
+        .flat_map(|style| style.split(';'))
+        .filter_map(|declaration| declaration.split_once(':'))
+        .find(|(property, _)| property.trim() == name)
+        .map(|(_, value)| value.trim());
+    style.or_else(|| node.attribute(name).map(str::trim))
+}
+
+/// Returns the path of a `rect` element, with rounded corners if it sets `rx` or `ry`.
+fn rect_path(node: roxmltree::Node) -> Option<String> {
+    let (x, y) = (number(node, "x"), number(node, "y"));
+    let (width, height) = (number(node, "width"), number(node, "height"));
+    if width <= 0.0 || height <= 0.0 {
+        return None;
+    }
+    // A missing corner radius takes the value of the other one
+    let rx = node.attribute("rx").and_then(length);
+    let ry = node.attribute("ry").and_then(length);
+    let radius = Vec2::new(rx.or(ry).unwrap_or(0.0), ry.or(rx).unwrap_or(0.0))
+        .min(Vec2::new(width, height) / 2.0);
+    if radius.min_element() <= 0.0 {
+        return Some(format!("M{x} {y} H{} V{} H{x} Z", x + width, y + height));
+    }
+    let (rx, ry) = (radius.x, radius.y);
+    let (right, bottom) = (x + width, y + height);
+    Some(format!(
+        "M{} {y} H{} A{rx} {ry} 0 0 1 {right} {} V{} A{rx} {ry} 0 0 1 {} {bottom} H{} \
+         A{rx} {ry} 0 0 1 {x} {} V{} A{rx} {ry} 0 0 1 {} {y} Z",
+        x + rx,
+        right - rx,
+        y + ry,
+        bottom - ry,
+        right - rx,
+        x + rx,
+        bottom - ry,
+        y + ry,
+        x + rx,
+    ))
+}
+
+/// Returns the path of an ellipse, drawn as two arcs.
+fn ellipse_path(cx: f32, cy: f32, rx: f32, ry: f32) -> String {
+    format!(
+        "M{} {cy} A{rx} {ry} 0 1 0 {} {cy} A{rx} {ry} 0 1 0 {} {cy} Z",
+        cx - rx,
+        cx + rx,
+        cx - rx
+    )
+}
+
+/// Returns the value of a numeric attribute of an element, which is zero if it is missing or invalid.
+fn number(node: roxmltree::Node, name: &str) -> f32 {
+    node.attribute(name).and_then(length).unwrap_or(0.0)
+}
+
+/// Parses a length in CSS pixels, such as `12`, `12px`, or `0.5in`. Percentages and font-relative units aren't
+/// supported.
+fn length(value: &str) -> Option<f32> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| c.is_ascii_alphabetic() || c == '%')
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f32 = number.trim().parse().ok()?;
+    let scale = match unit {
+        "" | "px" => 1.0,
+        "pt" => 4.0 / 3.0,
+        "pc" => 16.0,
+        "in" => 96.0,
+        "cm" => 96.0 / 2.54,
+        "mm" => 96.0 / 25.4,
+        _ => return None,
+    };
+    Some(number * scale)
+}
+
+/// Parses a list of numbers separated by whitespace or commas, stopping at the first invalid number.
+fn numbers(value: &str) -> Vec<f32> {
+    value
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|number| !number.is_empty())
+        .map_while(|number| number.parse().ok())
+        .collect()
+}
+
+/// Parses an opacity, either as a number or a percentage.
+fn parse_opacity(value: &str) -> Option<f32> {
+    let opacity = match value.strip_suffix('%') {
+        Some(percent) => percent.trim().parse::<f32>().ok()? / 100.0,
+        None => value.parse().ok()?,
+    };
+    Some(opacity.clamp(0.0, 1.0))
+}
+
+/// Parses a `fill` or `stroke` paint. References to paint servers such as gradients use their fallback color if they
+/// have one, and paint nothing otherwise.
+fn parse_paint(value: &str) -> Option<Paint> {
+    if let Some(reference) = value.strip_prefix("url(") {
+        let fallback = reference
+            .split_once(')')
+            .map(|(_, fallback)| fallback.trim());
+        return match fallback.filter(|fallback| !fallback.is_empty()) {
+            Some(fallback) => parse_paint(fallback),
+            None => Some(Paint::None),
+        };
+    }
+    match value {
+        "none" => Some(Paint::None),
+        "currentColor" => Some(Paint::CurrentColor),
+        _ => parse_color(value).map(Paint::Color),
+    }
+}
+
+/// Parses a color in the sRGB color space, given as a hex code, `rgb()` or `rgba()`, or one of the basic named colors.
+fn parse_color(value: &str) -> Option<Vec4> {
+    if value.starts_with('#') {
+        return Srgba::hex(value).ok().map(|color| color.to_vec4());
+    }
+    if let Some(arguments) = (value
+        .strip_prefix("rgba(")
+        .or_else(|| value.strip_prefix("rgb(")))
+    .and_then(|arguments| arguments.strip_suffix(')'))
+    {
+        let channels: Vec<&str> = arguments
+            .split([',', ' ', '/'])
+            .filter(|channel| !channel.is_empty())
+            .collect();
+        let channel = |channel: &str| match channel.strip_suffix('%') {
+            Some(percent) => percent.parse::<f32>().ok().map(|percent| percent / 100.0),
+            None => channel.parse::<f32>().ok().map(|value| value / 255.0),
+        };
+        let [r, g, b] = [0, 1, 2].map(|index| channels.get(index).and_then(|value| channel(value)));
+        let alpha = match channels.get(3) {
+            Some(alpha) => parse_opacity(alpha)?,
+            None => 1.0,
+        };
+        return Some(Vec4::new(r?, g?, b?, alpha));
+    }
+    let rgb = match value.to_ascii_lowercase().as_str() {
+        "transparent" => return Some(Vec4::ZERO),
+        "black" => [0, 0, 0],
+        "white" => [255, 255, 255],
+        "red" => [255, 0, 0],
+        "lime" => [0, 255, 0],
+        "green" => [0, 128, 0],
+        "blue" => [0, 0, 255],
+        "yellow" => [255, 255, 0],
+        "cyan" | "aqua" => [0, 255, 255],
+        "magenta" | "fuchsia" => [255, 0, 255],
+        "gray" | "grey" => [128, 128, 128],
+        "silver" => [192, 192, 192],
+        "maroon" => [128, 0, 0],
+        "olive" => [128, 128, 0],
+        "navy" => [0, 0, 128],
+        "purple" => [128, 0, 128],
+        "teal" => [0, 128, 128],
+        "orange" => [255, 165, 0],
+        _ => return None,
+    };
+    Some(Vec3::from_array(rgb.map(|channel| channel as f32 / 255.0)).extend(1.0))
+}
+
+/// Parses a `transform` attribute, which applies its transforms from right to left. Invalid transforms are ignored.
+fn parse_transform(value: &str) -> Transform {
+    let mut transform = Transform::IDENTITY;
+    for item in value.split_inclusive(')') {
+        let Some((name, arguments)) = item.split_once('(') else {
+            continue;
+        };
+        let name = name.trim_matches(|c: char| c.is_whitespace() || c == ',');
+        let arguments = numbers(arguments.trim_end_matches(')'));
+        let item = match (name, arguments.as_slice()) {
+            ("matrix", &[a, b, c, d, e, f]) => Transform::new(a, b, c, d, e, f),
+            ("translate", &[x]) => Transform::translation(x, 0.0),
+            ("translate", &[x, y]) => Transform::translation(x, y),
+            ("scale", &[scale]) => Transform::scale(scale, scale),
+            ("scale", &[x, y]) => Transform::scale(x, y),
+            ("rotate", &[angle]) => Transform::rotation(Angle::from_degrees(angle)),
+            ("rotate", &[angle, x, y]) => Transform::translation(-x, -y)
+                .then(&Transform::rotation(Angle::from_degrees(angle)))
+                .then_translate(x, y),
+            ("skewX", &[angle]) => Transform::skew(Angle::from_degrees(angle), Angle::ZERO),
+            ("skewY", &[angle]) => Transform::skew(Angle::ZERO, Angle::from_degrees(angle)),
+            _ => continue,
+        };
+        // Each transform applies before the ones to its left
+        transform = item.then(&transform);
+    }
+    transform
+}
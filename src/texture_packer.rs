@@ -0,0 +1,190 @@
+//! Loading of atlases exported by [TexturePacker](https://www.codeandweb.com/texturepacker).
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext, LoadDirectError, ParseAssetPathError},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{
+    json::{JsonFrame, JsonFrames, JsonSize},
+    AtlasLocation, TextureAtlasAsset, TextureAtlasPaths,
+};
+
+/// Errors encountered by [`TexturePackerLoader`].
+#[derive(Debug, Error)]
+pub enum TexturePackerLoaderError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    ParseAssetPath(#[from] ParseAssetPathError),
+    #[error(transparent)]
+    LoadDirect(#[from] LoadDirectError),
+}
+
+/// An [`AssetLoader`] that loads a [`TextureAtlasAsset`] from an atlas exported by TexturePacker in its "JSON (Hash)"
+/// or "JSON (Array)" data formats. The atlas image is loaded from the path in its `meta.image` field, relative to the
+/// JSON file.
+///
+/// Each sprite is named after its `filename`. Rotated sprites, trimmed sprites, and sprite pivots are all preserved in
+/// the sprite's [`TextureInfo`], so [`TextureAtlasAsset::sprite`] displays them like the original images.
+///
+/// TexturePacker writes atlases with a plain `.json` extension, so they must be renamed to `.texturepacker.json` to
+/// be picked up by this loader.
+///
+/// [`TextureInfo`]: crate::TextureInfo
+pub struct TexturePackerLoader;
+
+impl AssetLoader for TexturePackerLoader {
+    type Asset = TextureAtlasAsset;
+    type Settings = ();
+    type Error = TexturePackerLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        &(): &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        debug!("Loading TexturePacker atlas from {:?}", load_context.path());
+        let sheet = {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            serde_json::from_slice::<TexturePackerSheet>(&bytes)?
+        };
+
+        let image_path = load_context.asset_path().resolve_embed(&sheet.meta.image)?;
+        trace!("Loading TexturePacker atlas image from: {image_path}");
+        let texture = load_context
+            .loader()
+            .immediate()
+            .load::<Image>(image_path)
+            .await?;
+
+        let mut paths = TextureAtlasPaths::default();
+        let mut textures = Vec::with_capacity(sheet.frames.0.len());
+        for (name, frame) in sheet.frames.0.iter() {
+            paths.add(None, Some(name.clone()), frame.info());
+            textures.push(frame.rect());
+        }
+
+        let layout = TextureAtlasLayout {
+            size: texture.get().size(),
+            textures,
+        };
+        debug!(
+            "Loaded TexturePacker atlas containing {} sprites",
+            paths.path_indices.len(),
+        );
+        Ok(TextureAtlasAsset::from_pages(
+            load_context,
+            [(layout, texture)],
+            paths,
+        ))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["texturepacker.json"]
+    }
+}
+
+/// The root of a TexturePacker JSON atlas.
+#[derive(Deserialize)]
+struct TexturePackerSheet {
+    frames: JsonFrames,
+    meta: TexturePackerMeta,
+}
+
+#[derive(Deserialize)]
+struct TexturePackerMeta {
+    /// The path of the atlas image, relative to the JSON file.
+    image: String,
+}
+
+/// Builds a TexturePacker "JSON (Hash)" manifest describing `atlas`, so that atlases built by this crate can be used by
+/// other tools. `image` is the path of the atlas image relative to the manifest.
+///
+/// The manifest describes the atlas image as it is written by [`TextureAtlasSaver`], so the pages of a multi-page
+/// atlas are stacked vertically. Since an [`AssetSaver`] can only write a single file, the manifest must be written
+/// separately from the image. Textures are named after their name, their path if they have no name, or else their
+/// index. Returns `None` if a page's layout is not loaded, or if `atlas` is an array atlas.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_mod_atlas_loader::{texture_packer, TextureAtlasAsset};
+/// # fn export(atlas: &TextureAtlasAsset, layouts: &Assets<TextureAtlasLayout>) -> std::io::Result<()> {
+/// if let Some(manifest) = texture_packer::manifest(atlas, layouts, "sprites.png") {
+///     std::fs::write("sprites.json", manifest)?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`TextureAtlasSaver`]: crate::loader::TextureAtlasSaver
+/// [`AssetSaver`]: bevy::asset::saver::AssetSaver
+pub fn manifest(
+    atlas: &TextureAtlasAsset,
+    layouts: &Assets<TextureAtlasLayout>,
+    image: &str,
+) -> Option<String> {
+    if atlas.array.is_some() {
+        return None;
+    }
+
+    let paths = &atlas.paths;
+    let mut frames = Vec::with_capacity(paths.path_indices.len());
+    let mut size = UVec2::ZERO;
+    for (page, atlas_page) in atlas.pages.iter().enumerate() {
+        let layout = layouts.get(&atlas_page.layout)?;
+        let offset = UVec2::new(0, size.y);
+        for (index, &rect) in layout.textures.iter().enumerate() {
+            let location = AtlasLocation { page, index };
+            let name = match (paths.name_at(location), paths.path_at(location)) {
+                (Some(name), _) => name.to_owned(),
+                (None, Some(path)) => path.to_string(),
+                (None, None) => (paths.page_start(page) + index).to_string(),
+            };
+            let info = paths.info_at(location).cloned().unwrap_or_default();
+            let rect = URect::from_corners(rect.min + offset, rect.max + offset);
+            frames.push((name, JsonFrame::new(rect, &info)));
+        }
+        size = UVec2::new(size.x.max(layout.size.x), size.y + layout.size.y);
+    }
+
+    serde_json::to_string_pretty(&Manifest {
+        frames: JsonFrames(frames),
+        meta: ManifestMeta {
+            app: env!("CARGO_PKG_NAME"),
+            version: "1.0",
+            image,
+            format: "RGBA8888",
+            size: JsonSize {
+                w: size.x,
+                h: size.y,
+            },
+            scale: "1",
+        },
+    })
+    .ok()
+}
+
+/// A TexturePacker JSON atlas written by [`manifest`].
+#[derive(Serialize)]
+struct Manifest<'a> {
+    frames: JsonFrames,
+    meta: ManifestMeta<'a>,
+}
+
+#[derive(Serialize)]
+struct ManifestMeta<'a> {
+    /// The tool that wrote the atlas.
+    app: &'static str,
+    version: &'static str,
+    image: &'a str,
+    format: &'static str,
+    size: JsonSize,
+    scale: &'static str,
+}
@@ -0,0 +1,336 @@
+//! Loading of [Tiled](https://www.mapeditor.org/) `.tsx` tilesets as texture atlases.
+
+use std::time::Duration;
+
+use bevy::{
+    asset::{
+        io::Reader, AssetLoader, AsyncReadExt, LoadContext, LoadDirectError, ParseAssetPathError,
+    },
+    prelude::*,
+};
+use thiserror::Error;
+
+use super::{
+    animation::{AnimationFrame, AtlasAnimation},
+    AtlasLocation, TextureAtlasAsset, TextureAtlasPaths, TextureInfo,
+};
+
+/// Errors encountered by [`TiledLoader`].
+#[derive(Debug, Error)]
+pub enum TiledLoaderError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Parse(#[from] TiledParseError),
+    #[error(transparent)]
+    ParseAssetPath(#[from] ParseAssetPathError),
+    #[error(transparent)]
+    LoadDirect(#[from] LoadDirectError),
+}
+
+/// An error in the contents of a Tiled `.tsx` tileset.
+#[derive(Debug, Error)]
+pub enum TiledParseError {
+    #[error(transparent)]
+    Xml(#[from] roxmltree::Error),
+    #[error("Expected a `tileset` element")]
+    MissingTileset,
+    #[error("Expected a tileset with a single image, as image collections are not supported")]
+    MissingImage,
+    #[error("The element on line {line} is missing its `{attribute}` attribute")]
+    MissingAttribute { line: u32, attribute: &'static str },
+    #[error("The `{attribute}` attribute of the element on line {line} is invalid")]
+    InvalidAttribute { line: u32, attribute: &'static str },
+    #[error("The tile on line {line} is outside of the tileset")]
+    InvalidTile { line: u32 },
+}
+
+/// An [`AssetLoader`] that loads a [`TextureAtlasAsset`] from a Tiled `.tsx` tileset. The tileset's image is loaded
+/// from a path relative to the `.tsx` file, and is split into tiles using the tileset's tile size, spacing, margin and
+/// column count.
+///
+/// Each tile is named after its ID in decimal. The custom properties of a tile are stored in its
+/// [`TextureInfo::meta`] as a map from property names to their values, so they can be read with
+/// [`TextureAtlasAsset::meta_of`]. Tiles with an animation also add an [`AtlasAnimation`] named after the tile's ID.
+///
+/// [`TextureInfo::meta`]: crate::TextureInfo::meta
+/// [`TextureAtlasAsset::meta_of`]: crate::TextureAtlasAsset::meta_of
+pub struct TiledLoader;
+
+impl AssetLoader for TiledLoader {
+    type Asset = TextureAtlasAsset;
+    type Settings = ();
+    type Error = TiledLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        &(): &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        debug!("Loading Tiled tileset from {:?}", load_context.path());
+        let tileset = {
+            let mut text = String::new();
+            reader.read_to_string(&mut text).await?;
+            parse(&text)?
+        };
+
+        let image_path = load_context.asset_path().resolve_embed(&tileset.image)?;
+        trace!("Loading Tiled tileset image from: {image_path}");
+        let texture = load_context
+            .loader()
+            .immediate()
+            .load::<Image>(image_path)
+            .await?;
+
+        let rows = tileset.count.div_ceil(tileset.columns.max(1));
+        let mut layout = TextureAtlasLayout::from_grid(
+            tileset.tile_size,
+            tileset.columns,
+            rows,
+            Some(UVec2::splat(tileset.spacing)),
+            Some(UVec2::splat(tileset.margin)),
+        );
+        layout.textures.truncate(tileset.count as usize);
+        layout.size = texture.get().size();
+
+        let mut infos = vec![TextureInfo::default(); layout.textures.len()];
+        for (id, meta) in tileset.properties {
+            infos[id].meta = Some(meta);
+        }
+        let mut paths = TextureAtlasPaths::default();
+        for (id, info) in infos.into_iter().enumerate() {
+            paths.add(None, Some(id.to_string()), info);
+        }
+
+        let animations: Vec<(String, AtlasAnimation)> = tileset
+            .animations
+            .into_iter()
+            .map(|(id, frames)| {
+                let frames = frames
+                    .into_iter()
+                    .map(|(index, duration)| AnimationFrame {
+                        location: AtlasLocation { page: 0, index },
+                        duration,
+                    })
+                    .collect();
+                (
+                    id.to_string(),
+                    AtlasAnimation {
+                        frames,
+                        looping: true,
+                        pages: Vec::new(),
+                    },
+                )
+            })
+            .collect();
+
+        debug!(
+            "Loaded Tiled tileset containing {} tiles",
+            paths.path_indices.len(),
+        );
+        let mut atlas = TextureAtlasAsset::from_pages(load_context, [(layout, texture)], paths);
+        atlas.add_animations(load_context, animations);
+        Ok(atlas)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tsx"]
+    }
+}
+
+/// The contents of a Tiled tileset.
+struct Tileset {
+    /// The path of the tileset image, relative to the `.tsx` file.
+    image: String,
+    tile_size: UVec2,
+    /// The space between adjacent tiles in the image.
+    spacing: u32,
+    /// The space between the tiles and the edges of the image.
+    margin: u32,
+    columns: u32,
+    count: u32,
+    /// The custom properties of each tile that has any, by tile ID.
+    properties: Vec<(usize, ron::Value)>,
+    /// The frames of each animated tile as tile IDs and durations, by tile ID.
+    animations: Vec<(usize, Vec<(usize, Duration)>)>,
+}
+
+/// Reads the attributes of an element.
+struct Attributes<'a, 'input> {
+    node: roxmltree::Node<'a, 'input>,
+    line: u32,
+}
+
+impl<'a> Attributes<'a, '_> {
+    fn new<'input>(
+        document: &roxmltree::Document<'input>,
+        node: roxmltree::Node<'a, 'input>,
+    ) -> Attributes<'a, 'input> {
+        Attributes {
+            node,
+            line: document.text_pos_at(node.range().start).row,
+        }
+    }
+
+    fn str(&self, attribute: &'static str) -> Result<&'a str, TiledParseError> {
+        self.node
+            .attribute(attribute)
+            .ok_or(TiledParseError::MissingAttribute {
+                line: self.line,
+                attribute,
+            })
+    }
+
+    fn optional<T: std::str::FromStr>(
+        &self,
+        attribute: &'static str,
+    ) -> Result<Option<T>, TiledParseError> {
+        self.node
+            .attribute(attribute)
+            .map(|value| {
+                value
+                    .trim()
+                    .parse()
+                    .map_err(|_| TiledParseError::InvalidAttribute {
+                        line: self.line,
+                        attribute,
+                    })
+            })
+            .transpose()
+    }
+
+    fn required<T: std::str::FromStr>(
+        &self,
+        attribute: &'static str,
+    ) -> Result<T, TiledParseError> {
+        self.optional(attribute)?
+            .ok_or(TiledParseError::MissingAttribute {
+                line: self.line,
+                attribute,
+            })
+    }
+}
+
+/// Converts the `property` elements within a `properties` element to a map from their names to their values.
+fn properties(
+    document: &roxmltree::Document,
+    node: roxmltree::Node,
+) -> Result<ron::Value, TiledParseError> {
+    let mut map = ron::Map::new();
+    for property in node.children().filter(|node| node.has_tag_name("property")) {
+        let attributes = Attributes::new(document, property);
+        let name = attributes.str("name")?;
+        // Long string values are written as the element's text instead of its `value` attribute
+        let text = || {
+            property
+                .attribute("value")
+                .or_else(|| property.text())
+                .unwrap_or_default()
+        };
+        let value = match property.attribute("type").unwrap_or("string") {
+            "bool" => ron::Value::Bool(attributes.required("value")?),
+            "int" | "object" => ron::Value::Number(ron::Number::Integer(
+                attributes.optional("value")?.unwrap_or_default(),
+            )),
+            "float" => ron::Value::Number(ron::Number::new(
+                attributes.optional::<f64>("value")?.unwrap_or_default(),
+            )),
+            // Class properties contain their members as nested properties
+            "class" => match property
+                .children()
+                .find(|node| node.has_tag_name("properties"))
+            {
+                Some(members) => properties(document, members)?,
+                None => ron::Value::Map(ron::Map::new()),
+            },
+            // Strings, colors, and files are all kept as strings
+            _ => ron::Value::String(text().to_owned()),
+        };
+        map.insert(ron::Value::String(name.to_owned()), value);
+    }
+    Ok(ron::Value::Map(map))
+}
+
+/// Parses a Tiled tileset.
+fn parse(text: &str) -> Result<Tileset, TiledParseError> {
+    let document = roxmltree::Document::parse(text)?;
+    let root = document.root_element();
+    if !root.has_tag_name("tileset") {
+        return Err(TiledParseError::MissingTileset);
+    }
+    let attributes = Attributes::new(&document, root);
+    let tile_size = UVec2::new(
+        attributes.required("tilewidth")?,
+        attributes.required("tileheight")?,
+    );
+    let spacing = attributes.optional("spacing")?.unwrap_or(0);
+    let margin = attributes.optional("margin")?.unwrap_or(0);
+
+    let image_node = root
+        .children()
+        .find(|node| node.has_tag_name("image"))
+        .ok_or(TiledParseError::MissingImage)?;
+    let image = Attributes::new(&document, image_node);
+
+    // Older tilesets may not list their column and tile counts, so they are found from the image size like Tiled does
+    let fit = |length: u32, tile: u32| {
+        (length.saturating_sub(margin) + spacing) / (tile + spacing).max(1)
+    };
+    let columns = match attributes.optional("columns")? {
+        Some(columns) => columns,
+        None => fit(image.required("width")?, tile_size.x),
+    };
+    if columns == 0 {
+        return Err(TiledParseError::MissingImage);
+    }
+    let count = match attributes.optional("tilecount")? {
+        Some(count) => count,
+        None => columns * fit(image.required("height")?, tile_size.y),
+    };
+
+    let mut properties_by_tile = Vec::new();
+    let mut animations = Vec::new();
+    for tile in root.children().filter(|node| node.has_tag_name("tile")) {
+        let attributes = Attributes::new(&document, tile);
+        let id: usize = attributes.required("id")?;
+        if id >= count as usize {
+            return Err(TiledParseError::InvalidTile {
+                line: attributes.line,
+            });
+        }
+
+        for child in tile.children() {
+            if child.has_tag_name("properties") {
+                properties_by_tile.push((id, properties(&document, child)?));
+            } else if child.has_tag_name("animation") {
+                let mut frames = Vec::new();
+                for frame in child.children().filter(|node| node.has_tag_name("frame")) {
+                    let attributes = Attributes::new(&document, frame);
+                    let index: usize = attributes.required("tileid")?;
+                    if index >= count as usize {
+                        return Err(TiledParseError::InvalidTile {
+                            line: attributes.line,
+                        });
+                    }
+                    let duration = Duration::from_millis(attributes.required("duration")?);
+                    frames.push((index, duration));
+                }
+                if !frames.is_empty() {
+                    animations.push((id, frames));
+                }
+            }
+        }
+    }
+
+    Ok(Tileset {
+        image: image.str("source")?.to_owned(),
+        tile_size,
+        spacing,
+        margin,
+        columns,
+        count,
+        properties: properties_by_tile,
+        animations,
+    })
+}
@@ -0,0 +1,45 @@
+//! Helpers for displaying atlas textures in UI nodes, as well as on sprites.
+
+use bevy::{prelude::*, ui::NodeImageMode};
+
+use super::{handle::AtlasHandle, TextureAtlasAsset, TextureKey};
+
+impl TextureAtlasAsset {
+    /// Creates a [`UiImage`] displaying the texture identified by `texture` (see [`TextureKey`]). Textures with
+    /// nine-slice borders (see [`TextureInfo::slices`]) are displayed with [`NodeImageMode::Sliced`], so that they can be
+    /// stretched to the size of their node. Returns `None` if the texture is unknown or its page's layout is not loaded.
+    ///
+    /// UI images can't be rotated, so rotated textures (see [`TextureInfo::rotated`]) are displayed on their side.
+    ///
+    /// [`TextureInfo::slices`]: crate::TextureInfo::slices
+    /// [`TextureInfo::rotated`]: crate::TextureInfo::rotated
+    pub fn ui_image<'a>(
+        &self,
+        layouts: &Assets<TextureAtlasLayout>,
+        texture: impl Into<TextureKey<'a>>,
+    ) -> Option<UiImage> {
+        let location = self.paths.location_of_key(texture)?;
+        let page = self.page(location.page)?;
+        let rect = layouts.get(&page.layout)?.textures[location.index];
+        let info = self.paths.info_at(location)?;
+        let image =
+            UiImage::from_atlas_image(page.texture.clone(), self.handle_from_location(location)?);
+        Some(match info.slicer(rect) {
+            Some(slicer) => image.with_mode(NodeImageMode::Sliced(slicer)),
+            None => image,
+        })
+    }
+}
+
+impl AtlasHandle {
+    /// Creates a [`UiImage`] displaying the texture identified by `texture`, like [`TextureAtlasAsset::ui_image`].
+    /// Returns `None` if the atlas is not loaded or the texture is unknown.
+    pub fn ui_image<'a>(
+        &self,
+        atlases: &Assets<TextureAtlasAsset>,
+        layouts: &Assets<TextureAtlasLayout>,
+        texture: impl Into<TextureKey<'a>>,
+    ) -> Option<UiImage> {
+        self.get(atlases)?.ui_image(layouts, texture)
+    }
+}